@@ -5,7 +5,7 @@
 //! 
 //! This Rust library can create and resolve spiking neural networks defined for any possible applicable model, thanks to the powerful extensibility achieved through Rust's type system: simply implement the [Model] trait for your personally defined custom model and be good to go!
 //! 
-//! By default, the **_Leaky Integrate and Fire_** model is provided in the [lif] submodule.
+//! By default, the **_Leaky Integrate and Fire_** model is provided in the [lif] submodule, and the **_Adaptive Exponential Integrate and Fire_** model, whose neurons adapt their firing rate to sustained inputs, in the [adex] submodule. The **_Conductance-based Leaky Integrate and Fire_** model, whose synapses open conductances instead of injecting fixed currents, is in the [coba] submodule, and the **_Stochastic Leaky Integrate and Fire_** model, whose neurons fire with a probability that grows with their potential, is in the [stochastic] submodule. Neurons of different models can be mixed in the same network through the [hybrid] submodule.
 //! 
 //! ## Getting started
 //! 
@@ -47,6 +47,7 @@
 //!             // in this library, just be warned that "bad" networks can be
 //!             // created, and attempting to solve them might result in
 //!             // an infinite number of output spikes being generated
+//!             // (see `nn::builder::intra_weights_from` to validate them)
 //!             [0.0, -0.3],
 //!             [-0.2, 0.0]
 //!         ]
@@ -74,7 +75,7 @@
 //! 
 //! ### Define the input spikes that will stimulate the network
 //! 
-//! The [NN::solve] method requires spikes to be passed as a single [Vec] of [Spike] instances. Spikes can be either created manually, or through the provided helper functions. The latter method is shown in the next example.
+//! The [NN::solve] method requires spikes to be passed as a single [Vec] of [Spike] instances. Spikes can be either created manually, or through the provided helper functions. The latter method is shown in the next example. Analog inputs, like the pixels of an image, can also be turned into spikes by the rate and latency encoders of the [encoding] submodule.
 //! 
 //! ```
 //! # use pds_spiking_nn::{NNBuilder, lif::*};
@@ -178,10 +179,12 @@
 //! 
 //! This crate provides the following cargo features, which can be enabled at will:
 //! 
-//!  - **async** - [NN::solve] becomes an async function, which can be run with your favorite runtime. Internally, the implementation uses [tokio](https://crates.io/crates/tokio), and will spawn tokio `task`s in place of threads. The rationale for this is that, on larger networks, the parallelization strategy of firing a kernel thread for every layer will quickly result in hundreds if not thousands of threads, thus producing massive overhead due to the context switch between all of them. By employing _green threads_ (in the form of tasks), the user can effectively spread their allocation on a more reasonable number of kernel threads, hence dramatically improving the performance.  _If you enable this feature, remember to `.await` the `Future` returned by [NN::solve]!_
+//!  - **async** - [NN::solve] becomes an async function, which can be run with your favorite runtime. Internally, the implementation uses [tokio](https://crates.io/crates/tokio), and will spawn tokio `task`s in place of threads. The rationale for this is that, on larger networks, the kernel threads solving the different groups of layers (at most one for every logical core, see [SolveConfig]) spend most of their time blocking on each other, thus producing massive overhead due to the context switches between all of them. By employing _green threads_ (in the form of tasks), the user can effectively spread their allocation on a more reasonable number of kernel threads, hence dramatically improving the performance.  _If you enable this feature, remember to `.await` the `Future` returned by [NN::solve]!_
 //!   - **simd** - enable explicit SIMD support for the solver through [packed_simd](https://github.com/rust-lang/packed_simd) (**_this requires the latest nightly compiler_**). If this feature flag is enabled, the `Model` trait will require the "x4" version of the [Neuron](Model::Neuron) and [SolverVars](Model::SolverVars) types, together with their respective `handle_spike` function. The default implementation of the _lif_ model will exploit 256 bit wide vectorization extensions, like `AVX` on x86 platforms. _To obtain the most out of this feature, remember to enable the necessary extensions for rustc through, for example, the "-C target-features" compiler flag._
+//!   - **serde** - implement [serde](https://crates.io/crates/serde)'s `Serialize` and `Deserialize` for [NN] (and its [Layer]s), [Spike] and the types of the [lif] model, so that networks can be saved to and loaded back from disk in any format supported by serde.
+//!   - **arrow** - enable `NN::solve_traces_to_parquet` for the [lif] model, which writes the membrane traces of all the neurons of a network to a columnar [Parquet](https://parquet.apache.org/) file through the [arrow](https://crates.io/crates/arrow) ecosystem, ready to be loaded by data analysis tools.
 //! 
-//! Neither of these features are enabled by default, but their usage is strongly recommended when possible due to the performance improvement they can provide. See the [Performance](#performance) section for details.
+//! None of these features are enabled by default, but the usage of `async` and `simd` is strongly recommended when possible due to the performance improvement they can provide. See the [Performance](#performance) section for details.
 //! 
 //! ## Performance
 //! 
@@ -202,14 +205,21 @@
 //! As you can see, for very small networks, the barebones multi-threaded implementation usually edges out the alternatives, but for moderately big to "huge" neural networks, **`async` provides a consistent ~20% improvement, and `simd` another ~9-10% on top of it**.
 
 pub mod nn;
+pub mod testing;
 mod sync;
 
 // Re-exports
-pub use nn::{NN, Spike};
+pub use nn::{NN, Spike, SolveConfig, SolveResult, SynapseStats, LayerScales, SimulationState, SolveError, ValidationIssue, Float};
 pub use nn::layer::Layer;
 pub use nn::builder::NNBuilder;
 pub use nn::model::Model;
 pub use nn::model::lif;
+pub use nn::model::adex;
+pub use nn::model::coba;
+pub use nn::model::stochastic;
+pub use nn::model::hybrid;
+pub use nn::plasticity;
+pub use nn::encoding;
 
 #[cfg(feature = "expose-test-solver")]
 pub use nn::solver_v1 as test_solver;
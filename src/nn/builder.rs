@@ -7,10 +7,11 @@
 //! Additionally, a dynamically checked variant is supplied for building neural networks whose
 //! dimensions are not known at compile time.
 
-use std::{marker::PhantomData, borrow::Borrow, fmt::Debug};
-use ndarray::{Array2, Array1};
+use std::{marker::PhantomData, borrow::Borrow, fmt::Debug, collections::BTreeMap, ops::Range};
+use ndarray::{Array2, Array1, Axis, concatenate, s};
 use thiserror::Error;
-use crate::{NN, Model};
+use rand::{Rng, distributions::Distribution};
+use crate::{NN, Model, nn::Float};
 use super::layer::Layer;
 
 /// Used for compile-time checks of [NNBuilder]'s dimensions
@@ -26,20 +27,158 @@ impl Dim for Zero { }
 pub struct NotZero<const N: usize>;
 impl<const N: usize> Dim for NotZero<N> { }
 
+/// For statically checked builders whose output layer is split in heads, all fed by the same layer of `N` neurons
+#[derive(Clone, Copy)]
+pub struct Heads<const N: usize>;
+impl<const N: usize> Dim for Heads<N> { }
+
 /// For dynamically checked builders
 #[derive(Clone, Copy)]
 pub struct Dynamic;
 impl Dim for Dynamic { }
 
+/// Kind of a neuron according to Dale's law, which constrains the sign of all of its outgoing synapses.
+/// 
+/// Neurons can be tagged with their kind through [neuron_kinds](NNBuilder::neuron_kinds) on a dynamic [NNBuilder].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NeuronKind {
+    /// All the outgoing synapses of the neuron have a non-negative weight
+    Excitatory,
+    /// All the outgoing synapses of the neuron have a non-positive weight
+    Inhibitory
+}
+
 /// An error type for the dynamic variant of [NNBuilder].
 /// All the error variants contain the builder that generated them, for reuse.
 #[derive(Error, Debug)]
-pub enum DynamicBuilderError<M: Model> {
+pub enum DynamicBuilderError<M: Model, F: Float = f64> {
     #[error("Empty builder can not be built")]
-    EmptyNN(NNBuilder<M, Dynamic>),
+    EmptyNN(NNBuilder<M, Dynamic, F>),
+
+    #[error("Layers must have at least one neuron")]
+    EmptyLayer(NNBuilder<M, Dynamic, F>),
+
+    #[error("Output heads must be fed by at least one layer")]
+    MissingBackbone(NNBuilder<M, Dynamic, F>),
+
+    #[error("Intra weights must be a square matrix with a row for every neuron: expected {expected} weights, found {found}")]
+    InvalidIntraWeights {
+        builder: NNBuilder<M, Dynamic, F>,
+        expected: usize,
+        found: usize
+    },
+
+    #[error("Input weights must have a row for every neuron of the previous layer (a single one for the entry layer) and a column for every neuron of the layer: expected {expected} weights, found {found}")]
+    InvalidInputWeights {
+        builder: NNBuilder<M, Dynamic, F>,
+        expected: usize,
+        found: usize
+    },
+
+    #[error("Input delays must have the same shape as the input weights: expected {expected} delays, found {found}")]
+    InvalidInputDelays {
+        builder: NNBuilder<M, Dynamic, F>,
+        expected: usize,
+        found: usize
+    },
+
+    #[error("Intra delays must have the same shape as the intra weights: expected {expected} delays, found {found}")]
+    InvalidIntraDelays {
+        builder: NNBuilder<M, Dynamic, F>,
+        expected: usize,
+        found: usize
+    },
+
+    #[error("Synapses with a non-zero weight must have a positive delay, found a zero delay from neuron {from} to neuron {to} (intra-layer: {intra})")]
+    ZeroDelay {
+        builder: NNBuilder<M, Dynamic, F>,
+        from: usize,
+        to: usize,
+        intra: bool
+    },
+
+    #[error("Neuron kinds must be provided for every neuron of the last layer: expected {expected} kinds, found {found}")]
+    InvalidNeuronKinds {
+        builder: NNBuilder<M, Dynamic, F>,
+        expected: usize,
+        found: usize
+    },
+
+    #[error("Neuron {neuron} of layer {layer} is {kind:?}, but one of its outgoing synapses has a weight of the opposite sign")]
+    DaleViolation {
+        builder: NNBuilder<M, Dynamic, F>,
+        layer: usize,
+        neuron: usize,
+        kind: NeuronKind
+    },
+
+    #[error("Skip connections must go from a layer to a later one, both already added: found a connection from layer {from} to layer {to}")]
+    InvalidSkipConnection {
+        builder: NNBuilder<M, Dynamic, F>,
+        from: usize,
+        to: usize
+    },
+
+    #[error("Skip weights must have a row for every neuron of the source layer and a column for every neuron of the target layer: expected shape {expected:?}, found {found:?}")]
+    InvalidSkipWeights {
+        builder: NNBuilder<M, Dynamic, F>,
+        expected: (usize, usize),
+        found: (usize, usize)
+    }
+}
+
+/// An error type for [intra_weights_from].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IntraWeightsError {
+    #[error("Intra weights must be a square matrix: found {rows} rows and {cols} columns")]
+    NotSquare {
+        rows: usize,
+        cols: usize
+    },
+
+    #[error("Neuron {0} has a synapse onto itself (non-zero diagonal entry)")]
+    SelfSynapse(usize)
+}
+
+/// Validate a matrix of intra weights, making sure it is square and has a null diagonal, since a neuron shouldn't synapse onto itself.
+/// 
+/// The builders don't enforce a null diagonal, so this can be used to catch accidental self-excitation before adding a layer.
+/// Non-zero diagonal entries are rejected, unless `zero_diagonal` is set, in which case they are silently zeroed instead.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use ndarray::array;
+/// use pds_spiking_nn::{NNBuilder, lif::*, nn::builder::{intra_weights_from, IntraWeightsError}};
+/// 
+/// assert_eq!(intra_weights_from(array![[0.5, -0.3], [-0.2, 0.0]], false), Err(IntraWeightsError::SelfSynapse(0)));
+/// 
+/// let intra = intra_weights_from(array![[0.5, -0.3], [-0.2, 0.0]], true).unwrap();
+/// assert_eq!(intra, array![[0.0, -0.3], [-0.2, 0.0]]);
+/// 
+/// let config = LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2);
+/// let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+///     .layer(vec![LifNeuron::new(&config); 2], [1.5, 1.8], intra.as_slice().unwrap())?
+///     .build()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn intra_weights_from<F: Float>(data: Array2<F>, zero_diagonal: bool) -> Result<Array2<F>, IntraWeightsError> {
+    let (rows, cols) = data.dim();
+    if rows != cols {
+        return Err(IntraWeightsError::NotSquare { rows, cols });
+    }
+
+    let mut data = data.as_standard_layout().into_owned();
+    for i in 0..rows {
+        if data[(i, i)] != F::zero() {
+            if !zero_diagonal {
+                return Err(IntraWeightsError::SelfSynapse(i));
+            }
+            data[(i, i)] = F::zero();
+        }
+    }
 
-    #[error("Invalid input sizes provided for layer")]
-    InvalidSizes(NNBuilder<M, Dynamic>)
+    Ok(data)
 }
 
 /// Helper type that implements the builder pattern for [NN].
@@ -119,14 +258,16 @@ pub enum DynamicBuilderError<M: Model> {
 /// # Ok::<(), DynamicBuilderError<LeakyIntegrateFire>>(())
 /// ```
 #[derive(Clone)]
-pub struct NNBuilder<M: Model, D: Dim> {
+pub struct NNBuilder<M: Model, D: Dim, F: Float = f64> {
     /// Inner, growing [NN]
-    nn: NN<M>,
+    nn: NN<M, F>,
+    /// Kinds of the neurons of the tagged layers, keyed by layer index, to be enforced at build time
+    kinds: BTreeMap<usize, Vec<NeuronKind>>,
     /// Needed because of `D`, which would otherwise be unused
     _phantom: PhantomData<D>,
 }
 
-impl<M: Model> NNBuilder<M, Dynamic> {
+impl<M: Model, F: Float> NNBuilder<M, Dynamic, F> {
     /// Create a new dynamically sized instance of [NNBuilder].
     /// Every instance of this type can be used to build one [NN].
     /// 
@@ -142,22 +283,22 @@ impl<M: Model> NNBuilder<M, Dynamic> {
     /// let dynamic_builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic();
     /// ```
     pub fn new_dynamic() -> Self {
-        Self { nn: Self::new_nn(), _phantom: PhantomData }
+        Self { nn: Self::new_nn(), kinds: BTreeMap::new(), _phantom: PhantomData }
     }
 
     /// Add a layer to the neural network.
     /// 
     /// Note: input and intra weights are flattened row-major matrices (one row for each neuron in the layer).
     /// 
-    /// This function can fail with [DynamicBuilderError::InvalidSizes] iff:
-    ///  - neurons.len() is zero
-    ///  - input_weights.len() is not compatible with the previous layer's size and the current one
-    ///  - intra_weights.len() is different from neurons.len() squared
+    /// This function can fail with:
+    ///  - [DynamicBuilderError::EmptyLayer] iff neurons.len() is zero
+    ///  - [DynamicBuilderError::InvalidIntraWeights] iff intra_weights.len() is different from neurons.len() squared
+    ///  - [DynamicBuilderError::InvalidInputWeights] iff input_weights.len() is not compatible with the previous layer's size and the current one
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// # use pds_spiking_nn::{NNBuilder, nn::builder::DynamicBuilderError, lif::*};
     /// let mut builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
     ///     .layer(
     ///         [
@@ -181,32 +322,36 @@ impl<M: Model> NNBuilder<M, Dynamic> {
     ///     [0.0]
     /// );
     /// 
-    /// assert!(builder.is_err());
+    /// assert!(matches!(
+    ///     builder,
+    ///     Err(DynamicBuilderError::InvalidInputWeights { expected: 2, found: 1, .. })
+    /// ));
     /// ```
     pub fn layer(
         mut self,
         neurons: impl Borrow<[M::Neuron]>,
-        input_weights: impl Borrow<[f64]>,
-        intra_weights: impl Borrow<[f64]>
-    ) -> Result<Self, DynamicBuilderError<M>>
+        input_weights: impl Borrow<[F]>,
+        intra_weights: impl Borrow<[F]>
+    ) -> Result<Self, DynamicBuilderError<M, F>>
     {
         let len_last_layer = self.nn.layers.last().map(|l| l.neurons.len()).unwrap_or(0);
         let n = neurons.borrow().len();
         
         // Check layer len not zero
         if n == 0 {
-            return Err(DynamicBuilderError::InvalidSizes(self));
+            return Err(DynamicBuilderError::EmptyLayer(self));
         }
 
         // Check size compatibilities
-        if intra_weights.borrow().len() != n*n {
-            return Err(DynamicBuilderError::InvalidSizes(self));
+        let found = intra_weights.borrow().len();
+        if found != n*n {
+            return Err(DynamicBuilderError::InvalidIntraWeights { builder: self, expected: n*n, found });
         }
 
-        if input_weights.borrow().len() != (
-            if len_last_layer == 0 { n } else { len_last_layer * n }
-        ) {
-            return Err(DynamicBuilderError::InvalidSizes(self));
+        let expected = if len_last_layer == 0 { n } else { len_last_layer * n };
+        let found = input_weights.borrow().len();
+        if found != expected {
+            return Err(DynamicBuilderError::InvalidInputWeights { builder: self, expected, found });
         }
 
         let input_weights = if len_last_layer == 0 {
@@ -219,16 +364,474 @@ impl<M: Model> NNBuilder<M, Dynamic> {
         let new_layer = Layer {
             neurons: neurons.borrow().to_vec(),
             input_weights,
-            intra_weights: Array2::from_shape_vec((n, n), intra_weights.borrow().to_vec()).unwrap()
+            intra_weights: Array2::from_shape_vec((n, n), intra_weights.borrow().to_vec()).unwrap(),
+            input_delays: None,
+            intra_delays: None,
+            skips: vec![]
         };
+        // Any previous heads become a regular layer, feeding this one
+        self.nn.heads.clear();
         self.nn.layers.push(new_layer);
 
         Ok(self)
     }
 
+    /// Add an output head to the neural network.
+    /// 
+    /// Heads are parallel output layers, all fed by the same layer (the last one added through [layer](NNBuilder::layer) or its variants),
+    /// which can be solved at once with [solve_multihead](NN::solve_multihead). Every head has its own intra weights, and there are no synapses between different heads.
+    /// 
+    /// The heads are stored as a single output layer, with the neurons of every head following those of the previous one:
+    /// the other solvers of [NN] return their outputs together, in this order.
+    /// Adding a layer after some heads turns them into a regular layer, fully feeding the new one.
+    /// 
+    /// Note: input and intra weights are flattened row-major matrices, like in [layer](NNBuilder::layer).
+    /// 
+    /// This function can fail with:
+    ///  - [DynamicBuilderError::MissingBackbone] iff no layer was added yet
+    ///  - [DynamicBuilderError::EmptyLayer] iff neurons.len() is zero
+    ///  - [DynamicBuilderError::InvalidIntraWeights] iff intra_weights.len() is different from neurons.len() squared
+    ///  - [DynamicBuilderError::InvalidInputWeights] iff input_weights.len() is not compatible with the size of the feeding layer and the head's one
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, nn::builder::DynamicBuilderError, lif::*};
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+    ///     .layer(vec![LifNeuron::new(&config); 2], [1.5, 1.5], [0.0, -0.3, -0.3, 0.0])?
+    ///     .head([LifNeuron::new(&config)], [1.2, 0.4], [0.0])?
+    ///     .head(vec![LifNeuron::new(&config); 2], [0.4, 1.2, 1.2, 0.4], [0.0, -0.2, -0.2, 0.0])?
+    ///     .build()?;
+    /// 
+    /// assert_eq!(nn.num_heads(), 2);
+    /// assert_eq!(nn.layer_size(1), Some(3));
+    /// # Ok::<(), DynamicBuilderError<LeakyIntegrateFire>>(())
+    /// ```
+    pub fn head(
+        self,
+        neurons: impl Borrow<[M::Neuron]>,
+        input_weights: impl Borrow<[F]>,
+        intra_weights: impl Borrow<[F]>
+    ) -> Result<Self, DynamicBuilderError<M, F>>
+    {
+        let num_layers = self.nn.layers.len();
+        let len_backbone = match (self.nn.heads.is_empty(), num_layers) {
+            (_, 0) => return Err(DynamicBuilderError::MissingBackbone(self)),
+            (true, _) => self.nn.layers[num_layers - 1].neurons.len(),
+            (false, _) => self.nn.layers[num_layers - 2].neurons.len()
+        };
+        let n = neurons.borrow().len();
+
+        if n == 0 {
+            return Err(DynamicBuilderError::EmptyLayer(self));
+        }
+
+        let found = intra_weights.borrow().len();
+        if found != n*n {
+            return Err(DynamicBuilderError::InvalidIntraWeights { builder: self, expected: n*n, found });
+        }
+
+        let found = input_weights.borrow().len();
+        if found != len_backbone * n {
+            return Err(DynamicBuilderError::InvalidInputWeights { builder: self, expected: len_backbone * n, found });
+        }
+
+        let input_weights = Array2::from_shape_vec((len_backbone, n), input_weights.borrow().to_vec()).unwrap();
+        let intra_weights = Array2::from_shape_vec((n, n), intra_weights.borrow().to_vec()).unwrap();
+
+        Ok(self.push_head(neurons.borrow().to_vec(), input_weights, intra_weights))
+    }
+
+    /// Add a layer to the neural network, whose input synapses have the provided delays.
+    /// 
+    /// A spike generated at time `ts` reaches a neuron of this layer at time `ts + delay`, where `delay` is
+    /// the delay of the synapse between the two. `input_delays` has the same layout as `input_weights`.
+    /// 
+    /// Other than the errors of [layer](NNBuilder::layer), this function can fail with
+    /// [DynamicBuilderError::InvalidInputDelays] iff input_delays.len() is different from input_weights.len().
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, nn::builder::DynamicBuilderError, lif::*};
+    /// let builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.3, 2.8, 1.0)),
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.2, 0.5, 3.1, 0.9))
+    ///         ],
+    ///         [0.9, 1.4],
+    ///         [
+    ///             0.0, -0.3,
+    ///             -0.3, 0.0
+    ///         ]
+    ///     )?
+    ///     .layer_with_delays(
+    ///         [LifNeuron::new(&LifNeuronConfig::new(0.9, 0.6, 2.5, 1.1))],
+    ///         [1.5, 1.2],
+    ///         [0.0],
+    ///         [5] // Not enough delays provided! Should be 2x1 = 2
+    ///     );
+    /// 
+    /// assert!(matches!(
+    ///     builder,
+    ///     Err(DynamicBuilderError::InvalidInputDelays { expected: 2, found: 1, .. })
+    /// ));
+    /// # Ok::<(), DynamicBuilderError<LeakyIntegrateFire>>(())
+    /// ```
+    pub fn layer_with_delays(
+        self,
+        neurons: impl Borrow<[M::Neuron]>,
+        input_weights: impl Borrow<[F]>,
+        intra_weights: impl Borrow<[F]>,
+        input_delays: impl Borrow<[u128]>
+    ) -> Result<Self, DynamicBuilderError<M, F>>
+    {
+        // Adding a layer turns the heads into a regular layer, which must be undone on error
+        let heads = self.nn.heads.clone();
+        let mut builder = self.layer(neurons, input_weights, intra_weights)?;
+        let is_entry_layer = builder.nn.layers.len() == 1;
+        let layer = builder.nn.layers.last_mut().unwrap();
+
+        // The entry layer only has a weight for each of its neurons, on the diagonal
+        let expected = if is_entry_layer { layer.neurons.len() } else { layer.input_weights.len() };
+        let found = input_delays.borrow().len();
+        if found != expected {
+            // Give back the builder as it was before this layer
+            builder.nn.layers.pop();
+            builder.nn.heads = heads;
+            return Err(DynamicBuilderError::InvalidInputDelays { builder, expected, found });
+        }
+
+        layer.input_delays = Some(if is_entry_layer {
+            Array2::from_diag(&Array1::from_vec(input_delays.borrow().to_vec()))
+        } else {
+            Array2::from_shape_vec(layer.input_weights.raw_dim(), input_delays.borrow().to_vec()).unwrap()
+        });
+
+        Ok(builder)
+    }
+
+    /// Add a layer to the neural network from full weight and delay matrices, giving control over the transmission delay of every synapse.
+    /// 
+    /// `input_weights` and `input_delays` have a row for every neuron of the previous layer and a column for every neuron of this layer;
+    /// for the entry layer, they're instead row vectors with one element for every neuron. `intra_weights` and `intra_delays` are square matrices
+    /// with a row (the source) and a column (the destination) for every neuron.
+    /// A spike generated at time `ts` reaches a connected neuron at time `ts + delay`, where `delay` is the delay of the synapse between the two.
+    /// 
+    /// Other than the errors of [layer](NNBuilder::layer) (which are reported whenever a weight matrix doesn't have the expected shape),
+    /// this function can fail with:
+    ///  - [DynamicBuilderError::InvalidInputDelays] iff input_delays doesn't have the same shape as input_weights
+    ///  - [DynamicBuilderError::InvalidIntraDelays] iff intra_delays doesn't have the same shape as intra_weights
+    ///  - [DynamicBuilderError::ZeroDelay] iff a synapse with a non-zero weight has a null delay
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, nn::builder::DynamicBuilderError, lif::*};
+    /// use ndarray::array;
+    /// 
+    /// let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+    ///     .layer_with_delay_matrices(
+    ///         vec![LifNeuron::new(&config); 2],
+    ///         array![[1.0, 1.0]],
+    ///         array![[0.0, -0.3], [-0.3, 0.0]],
+    ///         array![[1, 2]],
+    ///         array![[0, 4], [4, 0]]
+    ///     )?
+    ///     .build()?;
+    /// 
+    /// // The input spike reaches the first neuron after 1 unit of time
+    /// assert_eq!(nn.solve_sequential(vec![Spike::new(0, 0)]), vec![Spike::new(1, 0)]);
+    /// 
+    /// let builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+    ///     .layer_with_delay_matrices(
+    ///         vec![LifNeuron::new(&config); 2],
+    ///         array![[1.0, 1.0]],
+    ///         array![[0.0, -0.3], [-0.3, 0.0]],
+    ///         array![[1, 2]],
+    ///         array![[0, 4], [0, 0]] // The synapse from neuron 1 to neuron 0 has no delay!
+    ///     );
+    /// 
+    /// assert!(matches!(
+    ///     builder,
+    ///     Err(DynamicBuilderError::ZeroDelay { from: 1, to: 0, intra: true, .. })
+    /// ));
+    /// # Ok::<(), DynamicBuilderError<LeakyIntegrateFire>>(())
+    /// ```
+    pub fn layer_with_delay_matrices(
+        self,
+        neurons: impl Borrow<[M::Neuron]>,
+        input_weights: Array2<F>,
+        intra_weights: Array2<F>,
+        input_delays: Array2<u128>,
+        intra_delays: Array2<u128>
+    ) -> Result<Self, DynamicBuilderError<M, F>>
+    {
+        let n = neurons.borrow().len();
+        let is_entry_layer = self.nn.layers.is_empty();
+        let input_dim = (self.nn.layers.last().map(|l| l.neurons.len()).unwrap_or(1), n);
+
+        if n == 0 {
+            return Err(DynamicBuilderError::EmptyLayer(self));
+        }
+        if intra_weights.dim() != (n, n) {
+            return Err(DynamicBuilderError::InvalidIntraWeights { builder: self, expected: n*n, found: intra_weights.len() });
+        }
+        if input_weights.dim() != input_dim {
+            return Err(DynamicBuilderError::InvalidInputWeights { builder: self, expected: input_dim.0 * input_dim.1, found: input_weights.len() });
+        }
+
+        if input_delays.dim() != input_weights.dim() {
+            return Err(DynamicBuilderError::InvalidInputDelays { builder: self, expected: input_weights.len(), found: input_delays.len() });
+        }
+        if intra_delays.dim() != intra_weights.dim() {
+            return Err(DynamicBuilderError::InvalidIntraDelays { builder: self, expected: intra_weights.len(), found: intra_delays.len() });
+        }
+
+        for (intra, weights, delays) in [(false, &input_weights, &input_delays), (true, &intra_weights, &intra_delays)] {
+            if let Some(((from, to), _)) = weights.indexed_iter().find(|&(idx, w)| !w.is_zero() && delays[idx] == 0) {
+                // The input synapses of the entry layer go from each input to the neuron with the same id
+                let from = if is_entry_layer && !intra { to } else { from };
+                return Err(DynamicBuilderError::ZeroDelay { builder: self, from, to, intra });
+            }
+        }
+
+        let mut builder = self.layer(neurons, input_weights.iter().cloned().collect::<Vec<_>>(), intra_weights.iter().cloned().collect::<Vec<_>>())?;
+        let layer = builder.nn.layers.last_mut().unwrap();
+
+        layer.input_delays = Some(if is_entry_layer {
+            Array2::from_diag(&input_delays.row(0))
+        } else {
+            input_delays
+        });
+        layer.intra_delays = Some(intra_delays);
+
+        Ok(builder)
+    }
+
+    /// Add a layer to the neural network, with random input and intra weights sampled from `weight_dist`.
+    /// 
+    /// Intra weights are only sampled off the diagonal, since a neuron is never connected to itself.
+    /// The same `rng` state always produces the same weights, so a seeded `rng` makes the network reproducible.
+    /// 
+    /// This function can fail with [DynamicBuilderError::EmptyLayer] iff neurons.len() is zero.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, nn::builder::DynamicBuilderError, lif::*};
+    /// use rand::{SeedableRng, distributions::Uniform};
+    /// use rand_pcg::Pcg64Mcg;
+    /// 
+    /// let mut rng = Pcg64Mcg::seed_from_u64(42);
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+    ///     .layer_random(vec![LifNeuron::new(&config); 50], &mut rng, Uniform::new(1.0, 2.0))?
+    ///     .layer_random(vec![LifNeuron::new(&config); 100], &mut rng, Uniform::new(-0.5, 1.0))?
+    ///     .build()?;
+    /// 
+    /// assert_eq!(nn[1].get_intra_weight(3, 3), Some(0.0));
+    /// # Ok::<(), DynamicBuilderError<LeakyIntegrateFire>>(())
+    /// ```
+    pub fn layer_random(
+        self,
+        neurons: impl Borrow<[M::Neuron]>,
+        rng: &mut impl Rng,
+        weight_dist: impl Distribution<F>
+    ) -> Result<Self, DynamicBuilderError<M, F>>
+    {
+        let n = neurons.borrow().len();
+        let len_last_layer = self.nn.layers.last().map(|l| l.neurons.len()).unwrap_or(1);
+
+        let input_weights = (0..len_last_layer * n).map(|_| weight_dist.sample(rng)).collect::<Vec<_>>();
+        let intra_weights = (0..n*n).map(|i| if i / n == i % n { F::zero() } else { weight_dist.sample(rng) }).collect::<Vec<_>>();
+
+        self.layer(neurons, input_weights, intra_weights)
+    }
+
+    /// Add a layer of `n` identical neurons, all built from `config`, to the neural network.
+    /// 
+    /// This is the same as [layer](NNBuilder::layer) with `n` copies of the same neuron (like [LifNeuron::new_vec](crate::lif::LifNeuron::new_vec)
+    /// does with a single config), and it can fail with the same errors.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, nn::builder::DynamicBuilderError, lif::*};
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+    ///     .layer_uniform(2, &config, [1.2, 1.3], [0.0, -0.2, -0.2, 0.0])?
+    ///     .layer_uniform(3, &config, [1.0; 6], [0.0; 9])?
+    ///     .build()?;
+    /// 
+    /// assert_eq!(nn.layer_size(1), Some(3));
+    /// # Ok::<(), DynamicBuilderError<LeakyIntegrateFire>>(())
+    /// ```
+    pub fn layer_uniform(
+        self,
+        n: usize,
+        config: &M::Config,
+        input_weights: impl Borrow<[F]>,
+        intra_weights: impl Borrow<[F]>
+    ) -> Result<Self, DynamicBuilderError<M, F>>
+    where for<'c> &'c M::Config: Into<M::Neuron>
+    {
+        self.layer(vec![config.into(); n], input_weights, intra_weights)
+    }
+
+    /// Create a new dynamic [NNBuilder] with a dense feedforward network, whose layers have the sizes in `layer_sizes`
+    /// and are made of identical neurons built from `config`.
+    /// 
+    /// Like in [layer_random](NNBuilder::layer_random), the input weights (including those of the entry layer) are sampled from `weight_dist`,
+    /// so a seeded `rng` makes the network reproducible; there are no intra-layer synapses.
+    /// More layers or heads can be added to the returned builder.
+    /// 
+    /// This function can fail with [DynamicBuilderError::EmptyLayer] iff any of the sizes is zero.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, nn::builder::DynamicBuilderError, lif::*};
+    /// use rand::{SeedableRng, distributions::Uniform};
+    /// use rand_pcg::Pcg64Mcg;
+    /// 
+    /// let mut rng = Pcg64Mcg::seed_from_u64(42);
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::fully_connected(&[784, 100, 10], &config, &mut rng, Uniform::new(0.0, 0.2))?
+    ///     .build()?;
+    /// 
+    /// assert_eq!(nn.layer_size(2), Some(10));
+    /// assert_eq!(nn[1].get_intra_weight(0, 1), Some(0.0));
+    /// # Ok::<(), DynamicBuilderError<LeakyIntegrateFire>>(())
+    /// ```
+    pub fn fully_connected(
+        layer_sizes: &[usize],
+        config: &M::Config,
+        rng: &mut impl Rng,
+        weight_dist: impl Distribution<F>
+    ) -> Result<Self, DynamicBuilderError<M, F>>
+    where for<'c> &'c M::Config: Into<M::Neuron>
+    {
+        let mut builder = Self::new_dynamic();
+        let mut len_last_layer = 1;
+
+        for &n in layer_sizes {
+            let input_weights = (0..len_last_layer * n).map(|_| weight_dist.sample(rng)).collect::<Vec<_>>();
+            builder = builder.layer_uniform(n, config, input_weights, vec![F::zero(); n*n])?;
+            len_last_layer = n;
+        }
+
+        Ok(builder)
+    }
+
+    /// Tag every neuron of the last layer added so far as either [Excitatory](NeuronKind::Excitatory) or [Inhibitory](NeuronKind::Inhibitory),
+    /// following Dale's law.
+    /// 
+    /// The signs of the outgoing synapses of the tagged neurons, both to the same layer and to the next one, are checked by [build](NNBuilder::build):
+    /// synapses with a zero weight are allowed for both kinds. Untagged layers are not checked.
+    /// Neurons of heads added after the tagging are left untagged.
+    /// 
+    /// This function can fail with [DynamicBuilderError::InvalidNeuronKinds] iff kinds.len() is different from the size of the last layer
+    /// (or if no layer was added yet).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, nn::builder::{DynamicBuilderError, NeuronKind}, lif::*};
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+    ///     .layer_uniform(2, &config, [1.2, 1.3], [0.0, -0.2, 0.1, 0.0])?
+    ///     .neuron_kinds([NeuronKind::Inhibitory, NeuronKind::Excitatory])?
+    ///     .layer_uniform(1, &config, [-1.0, 0.5], [0.0])?
+    ///     .build();
+    /// assert!(nn.is_ok());
+    /// 
+    /// // The first neuron is inhibitory, so it can't excite the next layer
+    /// let builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+    ///     .layer_uniform(2, &config, [1.2, 1.3], [0.0, -0.2, 0.1, 0.0])?
+    ///     .neuron_kinds([NeuronKind::Inhibitory, NeuronKind::Excitatory])?
+    ///     .layer_uniform(1, &config, [1.0, 0.5], [0.0])?;
+    /// assert!(matches!(
+    ///     builder.build(),
+    ///     Err(DynamicBuilderError::DaleViolation { layer: 0, neuron: 0, kind: NeuronKind::Inhibitory, .. })
+    /// ));
+    /// # Ok::<(), DynamicBuilderError<LeakyIntegrateFire>>(())
+    /// ```
+    pub fn neuron_kinds(mut self, kinds: impl Borrow<[NeuronKind]>) -> Result<Self, DynamicBuilderError<M, F>> {
+        let expected = self.nn.layers.last().map(|l| l.neurons.len()).unwrap_or(0);
+        let found = kinds.borrow().len();
+        if expected == 0 || found != expected {
+            return Err(DynamicBuilderError::InvalidNeuronKinds { builder: self, expected, found });
+        }
+
+        self.kinds.insert(self.nn.layers.len() - 1, kinds.borrow().to_vec());
+
+        Ok(self)
+    }
+
+    /// Connect the layer `from_layer` directly to the later layer `to_layer`, skipping the layers in between (a skip, or residual, connection).
+    /// 
+    /// `weights` has a row for every neuron of `from_layer` and a column for every neuron of `to_layer`. The spikes generated by `from_layer`
+    /// reach `to_layer` through these synapses at the same timestamp they were generated at, and their inputs are summed with those of the
+    /// input synapses of `to_layer` (and of its other skip connections, if any).
+    /// 
+    /// The networks with skip connections are always solved correctly, but the parallel solvers never split the layers between the two ends
+    /// of a skip connection on different threads. The reference solver ([solve_reference](NN::solve_reference)) doesn't support them.
+    /// 
+    /// This function can fail with:
+    ///  - [DynamicBuilderError::InvalidSkipConnection] iff `from_layer` is not lower than `to_layer`, or `to_layer` was not added yet
+    ///  - [DynamicBuilderError::InvalidSkipWeights] iff the shape of `weights` doesn't match the sizes of the two layers
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, nn::builder::DynamicBuilderError, lif::*};
+    /// use ndarray::array;
+    /// 
+    /// let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    /// 
+    /// // The middle layer never fires, but the skip connection drives the output one anyway
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+    ///     .layer_uniform(2, &config, [1.0, 1.0], [0.0; 4])?
+    ///     .layer_uniform(1, &config, [0.0, 0.0], [0.0])?
+    ///     .layer_uniform(1, &config, [0.0], [0.0])?
+    ///     .skip_connection(0, 2, array![[0.0], [1.0]])?
+    ///     .build()?;
+    /// 
+    /// let spikes = Spike::from_times(&[vec![1], vec![2]]);
+    /// assert_eq!(nn.solve_sequential(spikes), vec![Spike::new(2, 0)]);
+    /// # Ok::<(), DynamicBuilderError<LeakyIntegrateFire>>(())
+    /// ```
+    pub fn skip_connection(mut self, from_layer: usize, to_layer: usize, weights: Array2<F>) -> Result<Self, DynamicBuilderError<M, F>> {
+        if from_layer >= to_layer || to_layer >= self.nn.layers.len() {
+            return Err(DynamicBuilderError::InvalidSkipConnection { builder: self, from: from_layer, to: to_layer });
+        }
+
+        let expected = (self.nn.layers[from_layer].neurons.len(), self.nn.layers[to_layer].neurons.len());
+        if weights.dim() != expected {
+            let found = weights.dim();
+            return Err(DynamicBuilderError::InvalidSkipWeights { builder: self, expected, found });
+        }
+
+        self.nn.layers[to_layer].skips.push((to_layer - from_layer, weights));
+
+        Ok(self)
+    }
+
     /// Build the [NN]
     /// 
-    /// This function can fail with [DynamicBuilderError::EmptyNN] if called on an empty builder.
+    /// This function can fail with:
+    ///  - [DynamicBuilderError::EmptyNN] iff called on an empty builder
+    ///  - [DynamicBuilderError::DaleViolation] iff an outgoing synapse of a neuron tagged by [neuron_kinds](NNBuilder::neuron_kinds) has the wrong sign
     /// 
     /// # Examples
     /// 
@@ -274,16 +877,41 @@ impl<M: Model> NNBuilder<M, Dynamic> {
     /// # use pds_spiking_nn::{NNBuilder, lif::*};
     /// assert!(NNBuilder::<LeakyIntegrateFire, _>::new_dynamic().build().is_err());
     /// ```
-    pub fn build(self) -> Result<NN<M>, DynamicBuilderError<M>> {
+    pub fn build(self) -> Result<NN<M, F>, DynamicBuilderError<M, F>> {
         if self.nn.layers.is_empty() {
-            Err(DynamicBuilderError::EmptyNN(self))
-        } else {
-            Ok(self.inner_build())
+            return Err(DynamicBuilderError::EmptyNN(self));
+        }
+
+        if let Some((layer, neuron, kind)) = self.find_dale_violation() {
+            return Err(DynamicBuilderError::DaleViolation { builder: self, layer, neuron, kind });
         }
+
+        Ok(self.inner_build())
+    }
+
+    /// Find a tagged neuron with an outgoing synapse of the wrong sign, returning its layer, its id and its kind
+    fn find_dale_violation(&self) -> Option<(usize, usize, NeuronKind)> {
+        self.kinds.iter().find_map(|(&layer, kinds)| kinds.iter().enumerate().find_map(|(neuron, &kind)| {
+            let mut outgoing = self.nn.layers[layer].intra_weights.row(neuron).to_vec();
+            if let Some(next) = self.nn.layers.get(layer + 1) {
+                outgoing.extend(next.input_weights.row(neuron));
+            }
+            for (to, later) in self.nn.layers.iter().enumerate().skip(layer + 1) {
+                for (_, weights) in later.skips.iter().filter(|(back, _)| to - back == layer) {
+                    outgoing.extend(weights.row(neuron));
+                }
+            }
+
+            let violated = match kind {
+                NeuronKind::Excitatory => outgoing.iter().any(|w| *w < F::zero()),
+                NeuronKind::Inhibitory => outgoing.iter().any(|w| *w > F::zero())
+            };
+            violated.then_some((layer, neuron, kind))
+        }))
     }
 }
 
-impl<M: Model> NNBuilder<M, Zero> {
+impl<M: Model, F: Float> NNBuilder<M, Zero, F> {
     /// Create a new statically sized instance of [NNBuilder].
     /// Every instance of this type can be used to build one [NN].
     /// 
@@ -299,7 +927,7 @@ impl<M: Model> NNBuilder<M, Zero> {
     /// let builder = NNBuilder::<LeakyIntegrateFire, _>::new();
     /// ```
     pub fn new() -> Self {
-        Self { nn: Self::new_nn(), _phantom: PhantomData }
+        Self { nn: Self::new_nn(), kinds: BTreeMap::new(), _phantom: PhantomData }
     }
 
     /// Add the entry layer to the neural network.
@@ -326,40 +954,261 @@ impl<M: Model> NNBuilder<M, Zero> {
     pub fn layer<const N: usize>(
         mut self,
         neurons: impl Borrow<[M::Neuron; N]>,
-        input_weights: impl Borrow<[f64; N]>,
-        intra_weights: impl Borrow<[[f64; N]; N]>
-    ) -> NNBuilder<M, NotZero<N>>
+        input_weights: impl Borrow<[F; N]>,
+        intra_weights: impl Borrow<[[F; N]; N]>
+    ) -> NNBuilder<M, NotZero<N>, F>
     {
         let new_layer = Layer {
             neurons: neurons.borrow().to_vec(),
             input_weights: Array2::from_diag(&Array1::from_vec(input_weights.borrow().to_vec())),
-            intra_weights: Array2::from_shape_vec((N, N), intra_weights.borrow().iter().flatten().cloned().collect()).unwrap()
+            intra_weights: Array2::from_shape_vec((N, N), intra_weights.borrow().iter().flatten().cloned().collect()).unwrap(),
+            input_delays: None,
+            intra_delays: None,
+            skips: vec![]
         };
         self.nn.layers.push(new_layer);
         
         self.morph()
     }
+
+    /// Add the entry layer to the neural network, delaying every input spike by the provided amount
+    /// before it reaches the corresponding neuron.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let builder = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer_with_delays(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.3, 2.8, 1.0)),
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.2, 0.5, 3.1, 0.9))
+    ///         ],
+    ///         [0.9, 1.4],
+    ///         [
+    ///             [0.0, -0.3],
+    ///             [-0.3, 0.0]
+    ///         ],
+    ///         [0, 2]
+    ///     );
+    /// ```
+    pub fn layer_with_delays<const N: usize>(
+        self,
+        neurons: impl Borrow<[M::Neuron; N]>,
+        input_weights: impl Borrow<[F; N]>,
+        intra_weights: impl Borrow<[[F; N]; N]>,
+        input_delays: impl Borrow<[u128; N]>
+    ) -> NNBuilder<M, NotZero<N>, F>
+    {
+        let mut builder = self.layer(neurons, input_weights, intra_weights);
+        builder.nn.layers.last_mut().unwrap().input_delays = Some(Array2::from_diag(&Array1::from_vec(input_delays.borrow().to_vec())));
+
+        builder
+    }
+
+    /// Add the entry layer to the neural network, with random input and intra weights sampled from `weight_dist`.
+    /// 
+    /// Intra weights are only sampled off the diagonal, since a neuron is never connected to itself.
+    /// The same `rng` state always produces the same weights, so a seeded `rng` makes the network reproducible.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// use rand::{SeedableRng, distributions::Uniform};
+    /// use rand_pcg::Pcg64Mcg;
+    /// 
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    /// let builder = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer_random(
+    ///         [From::from(&config), From::from(&config), From::from(&config)],
+    ///         &mut Pcg64Mcg::seed_from_u64(42),
+    ///         Uniform::new(1.0, 2.0)
+    ///     );
+    /// ```
+    pub fn layer_random<const N: usize>(
+        self,
+        neurons: impl Borrow<[M::Neuron; N]>,
+        rng: &mut impl Rng,
+        weight_dist: impl Distribution<F>
+    ) -> NNBuilder<M, NotZero<N>, F>
+    {
+        let input_weights: [F; N] = std::array::from_fn(|_| weight_dist.sample(rng));
+        let intra_weights = random_intra_weights(rng, &weight_dist);
+
+        self.layer(neurons, input_weights, intra_weights)
+    }
+
+    /// Add the entry layer to the neural network, made of identical neurons all built from `config`.
+    /// 
+    /// The number of neurons is given by the size of the weights.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let builder = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer_uniform(
+    ///         &LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0),
+    ///         [1.2, 1.3],
+    ///         [
+    ///             [0.0, -0.2],
+    ///             [-0.2, 0.0]
+    ///         ]
+    ///     );
+    /// ```
+    pub fn layer_uniform<const N: usize>(
+        self,
+        config: &M::Config,
+        input_weights: impl Borrow<[F; N]>,
+        intra_weights: impl Borrow<[[F; N]; N]>
+    ) -> NNBuilder<M, NotZero<N>, F>
+    where for<'c> &'c M::Config: Into<M::Neuron>
+    {
+        let neuron: M::Neuron = config.into();
+
+        self.layer(std::array::from_fn(|_| neuron.clone()), input_weights, intra_weights)
+    }
 }
 
-impl<M: Model, const LEN_LAST_LAYER: usize> NNBuilder<M, NotZero<LEN_LAST_LAYER>> {
+impl<M: Model, F: Float, const LEN_LAST_LAYER: usize> NNBuilder<M, NotZero<LEN_LAST_LAYER>, F> {
     /// Add a layer to the neural network.
     pub fn layer<const N: usize>(
         mut self,
         neurons: impl Borrow<[M::Neuron; N]>,
-        input_weights: impl Borrow<[[f64; N]; LEN_LAST_LAYER]>,
-        intra_weights: impl Borrow<[[f64; N]; N]>
-    ) -> NNBuilder<M, NotZero<N>>
+        input_weights: impl Borrow<[[F; N]; LEN_LAST_LAYER]>,
+        intra_weights: impl Borrow<[[F; N]; N]>
+    ) -> NNBuilder<M, NotZero<N>, F>
     {
         let new_layer = Layer {
             neurons: neurons.borrow().to_vec(),
             input_weights: Array2::from_shape_vec((LEN_LAST_LAYER, N), input_weights.borrow().iter().flatten().cloned().collect()).unwrap(),
-            intra_weights: Array2::from_shape_vec((N, N), intra_weights.borrow().iter().flatten().cloned().collect()).unwrap()
+            intra_weights: Array2::from_shape_vec((N, N), intra_weights.borrow().iter().flatten().cloned().collect()).unwrap(),
+            input_delays: None,
+            intra_delays: None,
+            skips: vec![]
         };
         self.nn.layers.push(new_layer);
         
         self.morph()
     }
 
+    /// Add a layer to the neural network, whose input synapses have the provided delays.
+    /// 
+    /// A spike generated at time `ts` by a neuron of the previous layer reaches a neuron of this layer at time `ts + delay`,
+    /// where `delay` is the delay of the synapse between the two. `input_delays` has the same layout as `input_weights`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.3, 2.8, 1.0)),
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.2, 0.5, 3.1, 0.9))
+    ///         ],
+    ///         [0.9, 1.4],
+    ///         [
+    ///             [0.0, -0.3],
+    ///             [-0.3, 0.0]
+    ///         ]
+    ///     )
+    ///     .layer_with_delays(
+    ///         [LifNeuron::new(&LifNeuronConfig::new(0.9, 0.6, 2.5, 1.1))],
+    ///         [
+    ///             [1.5],
+    ///             [1.2]
+    ///         ],
+    ///         [[0.0]],
+    ///         [
+    ///             [3],
+    ///             [5]
+    ///         ]
+    ///     )
+    ///     .build();
+    /// ```
+    pub fn layer_with_delays<const N: usize>(
+        self,
+        neurons: impl Borrow<[M::Neuron; N]>,
+        input_weights: impl Borrow<[[F; N]; LEN_LAST_LAYER]>,
+        intra_weights: impl Borrow<[[F; N]; N]>,
+        input_delays: impl Borrow<[[u128; N]; LEN_LAST_LAYER]>
+    ) -> NNBuilder<M, NotZero<N>, F>
+    {
+        let mut builder = self.layer(neurons, input_weights, intra_weights);
+        builder.nn.layers.last_mut().unwrap().input_delays = Some(
+            Array2::from_shape_vec((LEN_LAST_LAYER, N), input_delays.borrow().iter().flatten().cloned().collect()).unwrap()
+        );
+
+        builder
+    }
+
+    /// Add a layer to the neural network, with random input and intra weights sampled from `weight_dist`.
+    /// 
+    /// Intra weights are only sampled off the diagonal, since a neuron is never connected to itself.
+    /// The same `rng` state always produces the same weights, so a seeded `rng` makes the network reproducible.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// use rand::{SeedableRng, distributions::Uniform};
+    /// use rand_pcg::Pcg64Mcg;
+    /// 
+    /// let mut rng = Pcg64Mcg::seed_from_u64(42);
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer_random([From::from(&config), From::from(&config)], &mut rng, Uniform::new(1.0, 2.0))
+    ///     .layer_random([From::from(&config), From::from(&config), From::from(&config)], &mut rng, Uniform::new(-0.5, 1.0))
+    ///     .build();
+    /// 
+    /// assert!(nn.synapses_between(0).unwrap().iter().all(|w| (-0.5..1.0).contains(w)));
+    /// ```
+    pub fn layer_random<const N: usize>(
+        self,
+        neurons: impl Borrow<[M::Neuron; N]>,
+        rng: &mut impl Rng,
+        weight_dist: impl Distribution<F>
+    ) -> NNBuilder<M, NotZero<N>, F>
+    {
+        let input_weights: [[F; N]; LEN_LAST_LAYER] = std::array::from_fn(|_| std::array::from_fn(|_| weight_dist.sample(rng)));
+        let intra_weights = random_intra_weights(rng, &weight_dist);
+
+        self.layer(neurons, input_weights, intra_weights)
+    }
+
+    /// Add a layer to the neural network, made of identical neurons all built from `config`.
+    /// 
+    /// The number of neurons is given by the size of the weights.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer_uniform(&config, [1.2, 1.3], [[0.0, -0.2], [-0.2, 0.0]])
+    ///     .layer_uniform(&config, [[1.0; 3]; 2], [[0.0; 3]; 3])
+    ///     .build();
+    /// 
+    /// assert_eq!(nn.layer_size(1), Some(3));
+    /// ```
+    pub fn layer_uniform<const N: usize>(
+        self,
+        config: &M::Config,
+        input_weights: impl Borrow<[[F; N]; LEN_LAST_LAYER]>,
+        intra_weights: impl Borrow<[[F; N]; N]>
+    ) -> NNBuilder<M, NotZero<N>, F>
+    where for<'c> &'c M::Config: Into<M::Neuron>
+    {
+        let neuron: M::Neuron = config.into();
+
+        self.layer(std::array::from_fn(|_| neuron.clone()), input_weights, intra_weights)
+    }
+
     /// Build the [NN].
     /// 
     /// # Examples
@@ -398,40 +1247,173 @@ impl<M: Model, const LEN_LAST_LAYER: usize> NNBuilder<M, NotZero<LEN_LAST_LAYER>
     ///     )
     ///     .build();
     /// ```
-    pub fn build(self) -> NN<M> {
+    pub fn build(self) -> NN<M, F> {
         self.inner_build()
     }
+
+    /// Add an output head to the neural network, fed by the last layer.
+    /// 
+    /// Heads are parallel output layers, all fed by the same layer, which can be solved at once with [solve_multihead](NN::solve_multihead).
+    /// Every head has its own intra weights, and there are no synapses between different heads.
+    /// 
+    /// The heads are stored as a single output layer, with the neurons of every head following those of the previous one:
+    /// the other solvers of [NN] return their outputs together, in this order.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer([From::from(&config), From::from(&config)], [1.5, 1.5], [[0.0, -0.3], [-0.3, 0.0]])
+    ///     .head([From::from(&config)], [[1.2], [0.4]], [[0.0]])
+    ///     .head([From::from(&config), From::from(&config)], [[0.4, 1.2], [1.2, 0.4]], [[0.0, -0.2], [-0.2, 0.0]])
+    ///     .build();
+    /// 
+    /// assert_eq!(nn.num_heads(), 2);
+    /// assert_eq!(nn.layer_size(1), Some(3));
+    /// ```
+    pub fn head<const N: usize>(
+        self,
+        neurons: impl Borrow<[M::Neuron; N]>,
+        input_weights: impl Borrow<[[F; N]; LEN_LAST_LAYER]>,
+        intra_weights: impl Borrow<[[F; N]; N]>
+    ) -> NNBuilder<M, Heads<LEN_LAST_LAYER>, F>
+    {
+        self.morph::<Heads<LEN_LAST_LAYER>>().head(neurons, input_weights, intra_weights)
+    }
 }
 
-impl<M: Model, D: Dim> NNBuilder<M, D> {
+impl<M: Model, F: Float, const LEN_BACKBONE: usize> NNBuilder<M, Heads<LEN_BACKBONE>, F> {
+    /// Add another output head to the neural network, fed by the same layer as the previous ones.
+    pub fn head<const N: usize>(
+        self,
+        neurons: impl Borrow<[M::Neuron; N]>,
+        input_weights: impl Borrow<[[F; N]; LEN_BACKBONE]>,
+        intra_weights: impl Borrow<[[F; N]; N]>
+    ) -> Self
+    {
+        self.push_head(
+            neurons.borrow().to_vec(),
+            Array2::from_shape_vec((LEN_BACKBONE, N), input_weights.borrow().iter().flatten().cloned().collect()).unwrap(),
+            Array2::from_shape_vec((N, N), intra_weights.borrow().iter().flatten().cloned().collect()).unwrap()
+        )
+    }
+
+    /// Build the neural network
+    pub fn build(self) -> NN<M, F> {
+        self.inner_build()
+    }
+}
+
+impl<M: Model, D: Dim, F: Float> NNBuilder<M, D, F> {
+    /// Jitter the initial membrane potential of every neuron added so far, offsetting it by a sample drawn uniformly from `range`,
+    /// so that the neurons of the network don't start (and fire) in lock-step.
+    ///
+    /// Offsets are applied through [Model::offset_initial_potential], so call this after the last layer or head; a seeded `rng`
+    /// makes the network reproducible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// use rand::SeedableRng;
+    /// use rand_pcg::Pcg64Mcg;
+    ///
+    /// let config = LifNeuronConfig::new(0.0, 0.0, 1.0, 1.0);
+    ///
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer_uniform::<3>(&config, [1.0; 3], [[0.0; 3]; 3])
+    ///     .with_random_init(&mut Pcg64Mcg::seed_from_u64(42), -0.2..0.2)
+    ///     .build();
+    ///
+    /// assert!(nn.get_neuron(0, 2).is_some_and(|neuron| neuron.v_init_offset.abs() < 0.2));
+    /// ```
+    pub fn with_random_init(mut self, rng: &mut impl Rng, range: Range<f64>) -> Self {
+        assert!(!range.is_empty(), "empty range of initial potentials");
+
+        for neuron in self.nn.layers.iter_mut().flat_map(|layer| layer.neurons.iter_mut()) {
+            M::offset_initial_potential(neuron, rng.gen_range(range.clone()));
+        }
+
+        self
+    }
+
     /// Create a new, empty [NN]
-    fn new_nn() -> NN<M> {
+    fn new_nn() -> NN<M, F> {
         NN {
-            layers: vec![]
+            layers: vec![],
+            heads: vec![]
+        }
+    }
+
+    /// Add an output head, merging it in the output layer with the previous heads (if any)
+    fn push_head(mut self, neurons: Vec<M::Neuron>, input_weights: Array2<F>, intra_weights: Array2<F>) -> Self {
+        let n = neurons.len();
+
+        if self.nn.heads.is_empty() {
+            self.nn.layers.push(Layer {
+                neurons,
+                input_weights,
+                intra_weights,
+                input_delays: None,
+                intra_delays: None,
+                skips: vec![]
+            });
+        } else {
+            let layer = self.nn.layers.last_mut().unwrap();
+            let old_len = layer.neurons.len();
+
+            // No synapses between different heads
+            let mut new_intra_weights = Array2::zeros((old_len + n, old_len + n));
+            new_intra_weights.slice_mut(s![..old_len, ..old_len]).assign(&layer.intra_weights);
+            new_intra_weights.slice_mut(s![old_len.., old_len..]).assign(&intra_weights);
+
+            layer.neurons.extend(neurons);
+            layer.input_weights = concatenate(Axis(1), &[layer.input_weights.view(), input_weights.view()]).unwrap();
+            layer.intra_weights = new_intra_weights;
+
+            // The skip connections of the previous heads don't reach the new one
+            for (_, weights) in &mut layer.skips {
+                *weights = concatenate(Axis(1), &[weights.view(), Array2::zeros((weights.nrows(), n)).view()]).unwrap();
+            }
         }
+        self.nn.heads.push(n);
+
+        self
     }
 
     /// Morph into another diensionality variant
-    fn morph<E: Dim>(self) -> NNBuilder<M, E> {
-        NNBuilder { nn: self.nn, _phantom: PhantomData }
+    fn morph<E: Dim>(self) -> NNBuilder<M, E, F> {
+        NNBuilder { nn: self.nn, kinds: self.kinds, _phantom: PhantomData }
     }
 
     /// Build the [NN].
     /// Note: we don't expose a global 'build' in order to:
     ///  - not allow building NNBuilder<Zero> variants
     ///  - allow checking dimensions at runtime for NNBuilder<Dynamic> variants
-    fn inner_build(self) -> NN<M> {
+    fn inner_build(self) -> NN<M, F> {
         self.nn
     }
 }
 
-impl<M: Model> Default for NNBuilder<M, Zero> {
+/// Sample a square matrix of intra weights from `weight_dist`, leaving the diagonal to zero
+fn random_intra_weights<F: Float, const N: usize>(rng: &mut impl Rng, weight_dist: &impl Distribution<F>) -> [[F; N]; N] {
+    std::array::from_fn(|i| std::array::from_fn(|j| if i == j { F::zero() } else { weight_dist.sample(rng) }))
+}
+
+impl<M: Model, F: Float> Default for NNBuilder<M, Zero, F> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<M: Model> Debug for NNBuilder<M, Dynamic> {
+impl<M: Model, F: Float> Debug for NNBuilder<M, Dynamic, F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("NNBuilder").finish()
     }
@@ -0,0 +1,75 @@
+//! Encoders turning analog input values (e.g. the pixel intensities of an image) into the [Spike]s that stimulate the entry layer of a [NN](crate::NN).
+//!
+//! The value at index `i` of the input always drives the neuron with id `i`.
+
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+use crate::Spike;
+use super::poisson_timestamps;
+
+/// Rate coding: encode every value as a Poisson spike train spanning timestamps `0..duration`, whose firing rate is proportional to the value.
+///
+/// Values are expected in the range `0.0..=1.0`: a value `v` fires at `v * max_rate` Hz, with timestamps interpreted as milliseconds
+/// like [poisson_train](Spike::poisson_train) does. A neuron can fire at most once per unit of time, so spikes falling on the same timestamp are merged.
+///
+/// The same `seed` always produces the same spikes, which are returned sorted like [create_terminal_vec](Spike::create_terminal_vec) does.
+///
+/// # Panics
+///
+/// Panics if any resulting rate is negative or not finite.
+///
+/// # Examples
+///
+/// ```
+/// use pds_spiking_nn::encoding::rate_encode;
+///
+/// let spikes = rate_encode(&[0.0, 1.0, 0.5], 1000, 100.0, 42);
+/// let count = |id| spikes.iter().filter(|s| s.neuron_id == id).count();
+///
+/// assert_eq!(count(0), 0);
+/// assert!(count(1) > count(2) && count(2) > 0);
+/// assert!(spikes.iter().all(|s| s.ts < 1000));
+/// assert_eq!(spikes, rate_encode(&[0.0, 1.0, 0.5], 1000, 100.0, 42));
+/// ```
+pub fn rate_encode(values: &[f64], duration: u128, max_rate: f64, seed: u64) -> Vec<Spike> {
+    let mut rng = Pcg64Mcg::seed_from_u64(seed);
+    let mut res = Vec::new();
+
+    for (neuron_id, value) in values.iter().enumerate() {
+        res.extend(
+            poisson_timestamps(&mut rng, value * max_rate / 1000.0, duration)
+                .into_iter()
+                .map(|ts| Spike::new(ts, neuron_id))
+        );
+    }
+    res.sort();
+
+    res
+}
+
+/// Latency coding: encode every value as a single spike, fired the earlier the stronger the value is.
+///
+/// Values are expected in the range `0.0..=1.0`: a value `v` fires at timestamp `round((1 - v) * t_max)`, so `1.0` fires at 0 and
+/// values close to 0 fire close to `t_max`. Values greater than 1 are treated as 1, while null, negative and NaN values don't fire at all.
+///
+/// The spikes are returned sorted like [create_terminal_vec](Spike::create_terminal_vec) does.
+///
+/// # Examples
+///
+/// ```
+/// use pds_spiking_nn::{Spike, encoding::latency_encode};
+///
+/// let spikes = latency_encode(&[0.2, 1.0, 0.0, 0.5], 10);
+///
+/// assert_eq!(spikes, vec![Spike::new(0, 1), Spike::new(5, 3), Spike::new(8, 0)]);
+/// ```
+pub fn latency_encode(values: &[f64], t_max: u128) -> Vec<Spike> {
+    let mut res = values.iter()
+        .enumerate()
+        .filter(|(_, v)| **v > 0.0)
+        .map(|(neuron_id, v)| Spike::new(((1.0 - v.min(1.0)) * t_max as f64).round() as u128, neuron_id))
+        .collect::<Vec<_>>();
+    res.sort();
+
+    res
+}
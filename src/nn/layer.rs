@@ -1,24 +1,42 @@
 //! `Layer` type for each layer of the neural network
 
-use std::ops::{Index, IndexMut};
-use ndarray::Array2;
-use crate::Model;
+use std::{ops::{Index, IndexMut}, collections::BTreeMap, fmt};
+use ndarray::{Array2, s};
+use crate::{Model, nn::Float};
 
 /// A single layer in the neural network
 /// 
 /// This contains all the neurons of the layer, as well as the intra-layer weights and input weights from
 /// the previous layer.
 #[derive(Clone)]
-pub struct Layer<M: Model> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(
+    serialize = "M::Neuron: serde::Serialize, F: serde::Serialize",
+    deserialize = "M::Neuron: serde::Deserialize<'de>, F: serde::Deserialize<'de>"
+)))]
+pub struct Layer<M: Model, F: Float = f64> {
     /// List of all neurons in this layer
     pub(crate) neurons: Vec<M::Neuron>,
     /// Matrix of the input weights. For the first layer, this must be a square diagonal matrix.
-    pub(crate) input_weights: Array2<f64>,
+    pub(crate) input_weights: Array2<F>,
     /// Square matrix of the intra-layer weights
-    pub(crate) intra_weights: Array2<f64>
+    pub(crate) intra_weights: Array2<F>,
+    /// Optional matrix of the delays of the input synapses, with the same shape as `input_weights`.
+    /// If [None], every input reaches the layer at the same timestamp it was generated at.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) input_delays: Option<Array2<u128>>,
+    /// Optional square matrix of the delays of the intra-layer synapses, with the same shape as `intra_weights`.
+    /// If [None], every intra-layer spike reaches the other neurons at the same timestamp it was generated at.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) intra_delays: Option<Array2<u128>>,
+    /// Synapses from earlier, non-adjacent layers (skip connections), as the distance back to their source layer
+    /// and the matrix of their weights, with a row for every neuron of the source layer and a column for every neuron of this one.
+    /// Their inputs reach the layer at the same timestamp they were generated at, added to those of the input synapses.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) skips: Vec<(usize, Array2<F>)>
 }
 
-impl<M: Model> Layer<M> {
+impl<M: Model, F: Float> Layer<M, F> {
     /// Return the number of neurons in this [Layer]
     /// 
     /// # Examples
@@ -120,7 +138,7 @@ impl<M: Model> Layer<M> {
     /// assert_eq!(nn[0].get_intra_weight(0, 2), Some(-0.3));
     /// assert_eq!(nn[0].get_intra_weight(2, 3), None);
     /// ```
-    pub fn get_intra_weight(&self, from: usize, to: usize) -> Option<f64> {
+    pub fn get_intra_weight(&self, from: usize, to: usize) -> Option<F> {
         self.intra_weights.get((from, to)).copied()
     }
 
@@ -151,7 +169,7 @@ impl<M: Model> Layer<M> {
     /// 
     /// assert_eq!(nn[0][(1, 2)], -0.3);
     /// ```
-    pub fn get_intra_weight_mut(&mut self, from: usize, to: usize) -> Option<&mut f64> {
+    pub fn get_intra_weight_mut(&mut self, from: usize, to: usize) -> Option<&mut F> {
         self.intra_weights.get_mut((from, to))
     }
 
@@ -236,9 +254,140 @@ impl<M: Model> Layer<M> {
     pub fn into_iter_neurons(self) -> <Vec<M::Neuron> as IntoIterator>::IntoIter {
         self.neurons.into_iter()
     }
+
+    /// Compute the input of every neuron of this layer caused by `spike`, the output of the previous layer
+    /// (or the network's inputs for the entry layer).
+    pub(crate) fn weigh_inputs(&self, spike: &Array2<f64>) -> Array2<f64> {
+        weigh(spike, &self.input_weights)
+    }
+
+    /// Compute the input of every neuron of this layer caused by `spike` at time `ts`, when the input synapses have `delays`.
+    /// 
+    /// The weighted inputs are accumulated into `pending`, keyed by the time they reach the neurons.
+    pub(crate) fn schedule_inputs(&self, ts: u128, spike: &Array2<f64>, delays: &Array2<u128>, pending: &mut BTreeMap<u128, Array2<f64>>) {
+        let n = self.neurons.len();
+
+        for (i, v) in spike.iter().enumerate().filter(|(_, v)| **v != 0.0) {
+            for (j, (w, d)) in self.input_weights.row(i).iter().zip(delays.row(i)).enumerate().filter(|(_, (w, _))| !w.is_zero()) {
+                pending.entry(ts + d).or_insert_with(|| Array2::zeros((1, n)))[(0, j)] += v * w.widen();
+            }
+        }
+    }
+
+    /// Compute the input of every neuron of this layer caused by `output`, the output of this same layer.
+    pub(crate) fn weigh_intra(&self, output: &Array2<f64>) -> Array2<f64> {
+        weigh(output, &self.intra_weights)
+    }
+
+    /// Compute the input of every neuron of this layer caused by `output`, the output of this same layer at time `ts`,
+    /// taking the delays of the intra-layer synapses into account.
+    /// 
+    /// Only the inputs of the synapses without delay are returned; the others are accumulated into `pending`,
+    /// keyed by the time they reach the neurons, like [schedule_inputs](Layer::schedule_inputs) does.
+    pub(crate) fn schedule_intra(&self, ts: u128, output: &Array2<f64>, pending: &mut BTreeMap<u128, Array2<f64>>) -> Array2<f64> {
+        let delays = match &self.intra_delays {
+            Some(delays) => delays,
+            None => return self.weigh_intra(output)
+        };
+
+        let n = self.neurons.len();
+        let mut res = Array2::zeros((1, n));
+
+        for (i, v) in output.iter().enumerate().filter(|(_, v)| **v != 0.0) {
+            for (j, (w, d)) in self.intra_weights.row(i).iter().zip(delays.row(i)).enumerate().filter(|(_, (w, _))| !w.is_zero()) {
+                if *d == 0 {
+                    res[(0, j)] += v * w.widen();
+                } else {
+                    pending.entry(ts + d).or_insert_with(|| Array2::zeros((1, n)))[(0, j)] += v * w.widen();
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Find a cycle of excitatory (positive) intra-layer synapses without delay, returning the ids of the neurons along it.
+    /// 
+    /// Such intra-layer spikes are delivered at the same timestamp they're generated at, so the neurons of the cycle
+    /// could keep exciting each other forever without time ever advancing.
+    pub(crate) fn excitatory_intra_cycle(&self) -> Option<Vec<usize>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Visit { New, OnPath, Done }
+
+        let n = self.neurons.len();
+        let mut visits = vec![Visit::New; n];
+
+        for root in 0..n {
+            if visits[root] != Visit::New {
+                continue;
+            }
+
+            // Depth-first search, keeping the current path along with the next successor to try for each of its neurons
+            let mut path = vec![(root, 0)];
+            visits[root] = Visit::OnPath;
+
+            while let Some(&(from, next)) = path.last() {
+                let is_instant = |to| self.intra_delays.as_ref().is_none_or(|delays| delays[(from, to)] == 0);
+
+                match (next..n).find(|&to| self.intra_weights[(from, to)] > F::zero() && is_instant(to)) {
+                    Some(to) => {
+                        path.last_mut().unwrap().1 = to + 1;
+
+                        match visits[to] {
+                            Visit::New => {
+                                visits[to] = Visit::OnPath;
+                                path.push((to, 0));
+                            },
+                            Visit::OnPath => {
+                                let start = path.iter().position(|&(neuron, _)| neuron == to).unwrap();
+                                return Some(path[start..].iter().map(|&(neuron, _)| neuron).collect());
+                            },
+                            Visit::Done => ()
+                        }
+                    },
+                    None => {
+                        visits[from] = Visit::Done;
+                        path.pop();
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Multiply the row vector `spike` by `weights`.
+/// 
+/// Only the rows of the weights of the active neurons are accumulated: rows are contiguous in memory, and spikes
+/// are usually very sparse as only few neurons fire at the same time. This is also much faster than
+/// a regular dot product on dense spikes, which would go through a general matrix-matrix product.
+/// 
+/// Weights are widened to [f64] while being accumulated, and the returned array is always in standard layout.
+pub(crate) fn weigh<F: Float>(spike: &Array2<f64>, weights: &Array2<F>) -> Array2<f64> {
+    let mut res = Array2::zeros((1, weights.ncols()));
+
+    for (i, v) in spike.iter().enumerate().filter(|(_, v)| **v != 0.0) {
+        res.zip_mut_with(&weights.slice(s![i..i+1, ..]), |r, w| *r += v * w.widen());
+    }
+
+    res
+}
+
+impl<M: Model, F: Float> fmt::Debug for Layer<M, F> where M::Neuron: fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Layer")
+            .field("neurons", &self.neurons)
+            .field("input_weights", &self.input_weights)
+            .field("intra_weights", &self.intra_weights)
+            .field("input_delays", &self.input_delays)
+            .field("intra_delays", &self.intra_delays)
+            .field("skips", &self.skips)
+            .finish()
+    }
 }
 
-impl<M: Model> Index<usize> for Layer<M> {
+impl<M: Model, F: Float> Index<usize> for Layer<M, F> {
     type Output = M::Neuron;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -246,21 +395,21 @@ impl<M: Model> Index<usize> for Layer<M> {
     }
 }
 
-impl<M: Model> IndexMut<usize> for Layer<M> {
+impl<M: Model, F: Float> IndexMut<usize> for Layer<M, F> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.neurons[index]
     }
 }
 
-impl<M: Model> Index<(usize, usize)> for Layer<M> {
-    type Output = f64;
+impl<M: Model, F: Float> Index<(usize, usize)> for Layer<M, F> {
+    type Output = F;
 
     fn index(&self, index: (usize, usize)) -> &Self::Output {
         &self.intra_weights[index]
     }
 }
 
-impl<M: Model> IndexMut<(usize, usize)> for Layer<M> {
+impl<M: Model, F: Float> IndexMut<(usize, usize)> for Layer<M, F> {
     fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
         &mut self.intra_weights[index]
     }
@@ -1,33 +1,42 @@
 //! Neural network-related types
 
-use crate::Model;
+use crate::{Model, sync};
 
-use self::layer::Layer;
-use std::{fmt, ops::{Index, IndexMut}, borrow::Borrow};
-use ndarray::Array2;
+use self::{layer::Layer, model::fired};
+use std::{fmt, ops::{Index, IndexMut}, borrow::Borrow, io::{self, Write, Read, BufRead, BufReader}, num::NonZeroUsize, collections::{VecDeque, BTreeMap}};
+use ndarray::{Array2, ArrayView1};
 use thiserror::Error;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
 
 pub mod layer;
 pub mod model;
 pub mod builder;
+pub mod plasticity;
+pub mod encoding;
+pub(crate) mod sequential;
 
-#[cfg(all(test, not(feature = "expose-test-solver")))]
+#[cfg(not(feature = "expose-test-solver"))]
 pub(crate) mod solver_v1;
-#[cfg(any(
-    all(not(test), feature = "expose-test-solver"),
-    all(test,      feature = "expose-test-solver")
-))]
+#[cfg(feature = "expose-test-solver")]
 pub mod solver_v1;
 #[cfg(test)]
 mod tests;
 
 /// Represents the 'spike' that stimulates a neuron in a spiking neural network.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Spike {
     /// Stands for "time of the spike", and represents a timestamp of when the spike occurs
     pub ts: u128,
     /// Index of the neuron this spike applies to inside its layer
-    pub neuron_id: usize
+    pub neuron_id: usize,
+    /// Sign of the spike: when applied to the network, the input weight of the neuron is multiplied by this value,
+    /// so that negative spikes directly carry an inhibitory drive.
+    /// 
+    /// This is 1 for every spike built by [new](Spike::new), as well as for the spikes generated by the network.
+    #[cfg_attr(feature = "serde", serde(default = "Spike::default_polarity"))]
+    pub polarity: i8
 }
 
 impl Spike {
@@ -40,15 +49,40 @@ impl Spike {
     /// ```
     /// # use pds_spiking_nn::Spike;
     /// let spike = Spike::new(18, 3);
-    /// assert_eq!(spike, Spike { ts: 18, neuron_id: 3 });
+    /// assert_eq!(spike, Spike { ts: 18, neuron_id: 3, polarity: 1 });
     /// ```
     pub fn new(ts: u128, neuron_id: usize) -> Spike {
         Spike {
             ts,
-            neuron_id
+            neuron_id,
+            polarity: Self::default_polarity()
+        }
+    }
+
+    /// Create a new spike at time `ts` for neuron `neuron_id`, with the given [polarity](Spike::polarity)
+    /// 
+    /// # Examples
+    /// 
+    /// Create an inhibitory [Spike] at instant 18 for neuron 3:
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::Spike;
+    /// let spike = Spike::with_polarity(18, 3, -1);
+    /// assert_eq!(spike, Spike { ts: 18, neuron_id: 3, polarity: -1 });
+    /// ```
+    pub fn with_polarity(ts: u128, neuron_id: usize, polarity: i8) -> Spike {
+        Spike {
+            ts,
+            neuron_id,
+            polarity
         }
     }
 
+    /// Polarity of regular, excitatory spikes
+    fn default_polarity() -> i8 {
+        1
+    }
+
     /// Create an array of spikes for a single neuron, given its ID.
     /// The `ts_vec` does not need to be ordered.
     /// 
@@ -86,6 +120,9 @@ impl Spike {
     /// It takes a Matrix where i-th row represents an array of spikes for the i-th entry neuron,
     /// then a single Vec is created. Eventually the array is sorted.
     /// 
+    /// Spikes are ordered by timestamp first, and then by neuron id. The sort is stable: spikes with the same timestamp and neuron id
+    /// (e.g. of different polarity) keep the order they were given in, row by row.
+    /// 
     /// # Examples
     /// 
     /// ```
@@ -104,9 +141,9 @@ impl Spike {
     /// 
     /// let mut iter = sorted_spike_array_for_nn.into_iter();
     /// 
-    /// assert_eq!(iter.next(), Some(Spike {ts: 1, neuron_id: 2}));
-    /// assert_eq!(iter.next(), Some(Spike {ts: 3, neuron_id: 2}));
-    /// assert_eq!(iter.next(), Some(Spike {ts: 9, neuron_id: 1}));
+    /// assert_eq!(iter.next(), Some(Spike::new(1, 2)));
+    /// assert_eq!(iter.next(), Some(Spike::new(3, 2)));
+    /// assert_eq!(iter.next(), Some(Spike::new(9, 1)));
     /// ```
     pub fn create_terminal_vec(spikes: Vec<Vec<Spike>>) -> Vec<Spike> {
         let mut res: Vec<Spike> = Vec::new();
@@ -116,290 +153,3277 @@ impl Spike {
                 res.push(spike);
             }
         }
-        res.sort(); //ascending
+        // Stable, so duplicates keep their insertion order
+        res.sort_by_key(|spike| (spike.ts, spike.neuron_id));
     
         res
     }
-}
-
-impl fmt::Display for Spike {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Spike(ts: {}, neuron_id: {})", self.ts, self.neuron_id)
-    }
-}
 
-/// Error for [NN]'s [concat](NN::concat) and [extend](NN::extend).
-/// 
-/// Only one variant is needed because only one kind of error can happen.
-#[derive(Error, Debug)]
-pub enum NNConcatError {
-    #[error("Provided intra-nn weights have invalid dimensions")]
-    InvalidWeightsLen
-}
+    /// Create the ordered array of the spikes sent to the NN directly from the timestamps of the spikes of every entry neuron,
+    /// where the i-th row lists the timestamps of the i-th neuron.
+    /// 
+    /// This is the same as calling [spike_vec_for](Spike::spike_vec_for) on every row, and then [create_terminal_vec](Spike::create_terminal_vec).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::Spike;
+    /// let spikes = Spike::from_times(&[vec![4, 1], vec![], vec![2, 4]]);
+    /// 
+    /// assert_eq!(spikes, vec![Spike::new(1, 0), Spike::new(2, 2), Spike::new(4, 0), Spike::new(4, 2)]);
+    /// ```
+    pub fn from_times(times: &[Vec<u128>]) -> Vec<Spike> {
+        let mut res = times.iter()
+            .enumerate()
+            .flat_map(|(neuron_id, ts_vec)| ts_vec.iter().map(move |&ts| Spike::new(ts, neuron_id)))
+            .collect::<Vec<_>>();
+        res.sort();
 
-/// The Neural Network itself.
-/// 
-/// This organizes [Neuron](Model::Neuron)s into consecutive layers, each constituted of some amount of [Neuron](Model::Neuron)s.
-/// [Neuron](Model::Neuron)s of the same or consecutive layers are connected by a weighted synapse [f64].
-/// 
-/// A neural network is stimulated by [Spike]s applied to the [Neuron](Model::Neuron)s of its entry layer.
-/// 
-/// Create a new [NN] through the builder at [NNBuilder](crate::NNBuilder).
-#[derive(Clone)]
-pub struct NN<M: Model> {
-    /// All the sorted layers of the neural network
-    layers: Vec<Layer<M>>
-}
+        res
+    }
 
-impl<M: Model> NN<M> {
-    /// Return the number of layers in this neural network.
+    /// Create the ordered array of the spikes sent to the NN from a dense matrix of bits, where the i-th row lists
+    /// whether the i-th entry neuron fires at every timestep: a set bit in position `(neuron_id, timestep)` generates a spike at `timestep * dt`.
     /// 
-    /// This is always guaranteed to be greater than zero
-    /// by the [NNBuilder](crate::NNBuilder) necessary to construct an instance of [NN].
+    /// This is a compact way to describe dense, clock-driven inputs. Rows can have different lengths, missing bits are unset.
+    /// The spikes are generated already sorted, like [create_terminal_vec](Spike::create_terminal_vec) does.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
-    ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
-    ///     )
-    ///     .build();
+    /// # use pds_spiking_nn::Spike;
+    /// let spikes = Spike::from_bitmatrix(&[vec![true, false, true], vec![], vec![true, true]], 5);
     /// 
-    /// assert_eq!(nn.num_layers(), 1);
+    /// assert_eq!(spikes, Spike::from_times(&[vec![0, 10], vec![], vec![0, 5]]));
     /// ```
-    pub fn num_layers(&self) -> usize {
-        self.layers.len()
+    pub fn from_bitmatrix(bits: &[Vec<bool>], dt: u128) -> Vec<Spike> {
+        let num_steps = bits.iter().map(Vec::len).max().unwrap_or(0);
+
+        (0..num_steps)
+            .flat_map(|step| bits.iter()
+                .enumerate()
+                .filter(move |(_, row)| row.get(step) == Some(&true))
+                .map(move |(neuron_id, _)| Spike::new(step as u128 * dt, neuron_id))
+            )
+            .collect()
     }
 
-    /// Get the specified layer, or [None] if the index is out of bounds.
+    /// Merge two [Vec]s of spikes, both already sorted like [create_terminal_vec](Spike::create_terminal_vec) does,
+    /// into a single sorted one in linear time.
     /// 
-    /// An unchecked variant of this functionality is provided via the [Index] implementation.
+    /// Like [create_terminal_vec](Spike::create_terminal_vec), the merge is stable: spikes with the same timestamp and neuron id
+    /// keep their order, with those of `a` first. If the inputs are not sorted, neither is the result.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
-    ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
-    ///     )
-    ///     .build();
+    /// # use pds_spiking_nn::Spike;
+    /// let a = Spike::from_times(&[vec![1, 4], vec![4]]);
+    /// let b = Spike::from_times(&[vec![2], vec![], vec![4, 7]]);
     /// 
-    /// // Get a reference to the first layer
-    /// let first_layer = nn.get_layer(0);
+    /// let merged = Spike::merge_sorted(&a, &b);
     /// 
-    /// assert_eq!(first_layer.unwrap().num_neurons(), 2);
+    /// assert_eq!(merged, Spike::create_terminal_vec(vec![a, b]));
     /// ```
-    pub fn get_layer(&self, layer: usize) -> Option<&Layer<M>> {
-        self.layers.get(layer)
+    pub fn merge_sorted(a: &[Spike], b: &[Spike]) -> Vec<Spike> {
+        let mut res = Vec::with_capacity(a.len() + b.len());
+        let (mut i, mut j) = (0, 0);
+
+        while i < a.len() && j < b.len() {
+            if (b[j].ts, b[j].neuron_id) < (a[i].ts, a[i].neuron_id) {
+                res.push(b[j]);
+                j += 1;
+            } else {
+                res.push(a[i]);
+                i += 1;
+            }
+        }
+        res.extend_from_slice(&a[i..]);
+        res.extend_from_slice(&b[j..]);
+
+        res
     }
 
-    /// Get a mutable reference to the specified layer, or [None] if the index is out of bounds.
+    /// Generate a Poisson spike train for the neuron with id `neuron_id`, spanning timestamps `0..duration`.
     /// 
-    /// An unchecked variant of this functionality is provided via the [IndexMut] implementation.
+    /// Inter-spike intervals are sampled from an exponential distribution, with timestamps interpreted as milliseconds:
+    /// `rate_hz` is thus the average number of spikes every 1000 units of time.
+    /// A neuron can fire at most once per unit of time, so spikes falling on the same timestamp are merged.
+    /// 
+    /// The same `seed` always produces the same train, which is returned sorted (it can be used directly with [create_terminal_vec](Spike::create_terminal_vec)).
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `rate_hz` is negative or not finite.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
-    ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
-    ///     )
-    ///     .build();
-    /// 
-    /// // Modify an intra-layer weight
-    /// *nn.get_layer_mut(0).unwrap().get_intra_weight_mut(0, 1).unwrap() = -1.7;
+    /// # use pds_spiking_nn::Spike;
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::poisson_train(0, 40.0, 1000, 1),
+    ///     Spike::poisson_train(1, 0.0, 1000, 2)
+    /// ]);
     /// 
-    /// assert_eq!(nn[0][(0, 1)], -1.7);
+    /// assert!(!spikes.is_empty());
+    /// assert!(spikes.iter().all(|s| s.neuron_id == 0 && s.ts < 1000));
+    /// assert_eq!(spikes, Spike::poisson_train(0, 40.0, 1000, 1));
     /// ```
-    pub fn get_layer_mut(&mut self, layer: usize) -> Option<&mut Layer<M>> {
-        self.layers.get_mut(layer)
+    pub fn poisson_train(neuron_id: usize, rate_hz: f64, duration: u128, seed: u64) -> Vec<Spike> {
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+
+        poisson_timestamps(&mut rng, rate_hz / 1000.0, duration)
+            .into_iter()
+            .map(|ts| Spike::new(ts, neuron_id))
+            .collect()
     }
 
-    /// Get the neuron at the specified position of the specified layer, or [None] if any index is out of bounds.
+    /// Generate a regular spike train for the neuron with id `neuron_id`, made of exactly `count` spikes
+    /// at timestamps `start, start + interval, start + 2 * interval, ...`.
     /// 
-    /// An unchecked variant of this functionality is provided via the [Index] implementation.
+    /// Unlike [poisson_train](Spike::poisson_train), this stimulus is fully deterministic, so it's a simple baseline for benchmarks.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `interval` is 0 and `count` is greater than one, as a neuron can fire at most once per timestamp.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
-    ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
-    ///     )
-    ///     .build();
-    /// 
-    /// // Get a reference to the second neuron of the only layer of the nn
-    /// let neuron = nn.get_neuron(0, 1);
+    /// # use pds_spiking_nn::Spike;
+    /// let spikes = Spike::regular_train(0, 10, 3, 5);
     /// 
-    /// println!("{:?}", neuron); // Some(LifNeuron { v_rest: 1.0, v_reset: 0.4, v_threshold: 3.1, tau: 1.1 })
+    /// assert_eq!(spikes, Spike::spike_vec_for(0, vec![5, 15, 25]));
     /// ```
-    pub fn get_neuron(&self, layer: usize, neuron: usize) -> Option<&M::Neuron> {
-        self.layers.get(layer)?.neurons.get(neuron)
+    pub fn regular_train(neuron_id: usize, interval: u128, count: usize, start: u128) -> Vec<Spike> {
+        assert!(interval > 0 || count <= 1, "the interval between the spikes must be positive");
+
+        (0..count)
+            .map(|i| Spike::new(start + i as u128 * interval, neuron_id))
+            .collect()
     }
 
-    /// Get a mutable reference to the neuron at the specified position of the specified layer, or [None] if any index is out of bounds.
+    /// Generate Poisson spike trains for many neurons, from a schedule of firing rates over consecutive time windows.
     /// 
-    /// An unchecked variant of this functionality is provided via the [IndexMut] implementation.
+    /// `rates` has a row for every neuron and a column for every window: `rates[(neuron_id, window_index)]` is the firing rate of
+    /// neuron `neuron_id` in the window starting at `window_index * window`, expressed in spikes per unit of time.
+    /// A neuron can fire at most once per unit of time, so rates should be well below 1.
+    /// 
+    /// The same `seed` always produces the same spikes, which are returned sorted like [create_terminal_vec](Spike::create_terminal_vec) does.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if any rate is negative or not finite.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
-    ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
-    ///     )
-    ///     .build();
+    /// # use pds_spiking_nn::Spike;
+    /// use ndarray::array;
     /// 
-    /// // Get a mutable reference to the second neuron of the only layer of the nn
-    /// let mut neuron = nn.get_neuron_mut(0, 1).unwrap();
-    /// neuron.v_rest += 0.2;
+    /// // Neuron 0 is silent and then fires, neuron 1 the opposite
+    /// let rates = array![
+    ///     [0.0, 0.2],
+    ///     [0.2, 0.0]
+    /// ];
     /// 
-    /// assert_eq!(nn[0][1].v_rest, 1.2);
+    /// let spikes = Spike::from_rate_schedule(&rates, 100, 42);
+    /// 
+    /// assert!(spikes.iter().all(|s| (s.neuron_id == 0) == (s.ts >= 100)));
+    /// assert!(spikes.iter().all(|s| s.ts < 200));
     /// ```
-    pub fn get_neuron_mut(&mut self, layer: usize, neuron: usize) -> Option<&mut M::Neuron> {
-        self.layers.get_mut(layer)?.neurons.get_mut(neuron)
+    pub fn from_rate_schedule(rates: &Array2<f64>, window: u128, seed: u64) -> Vec<Spike> {
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+        let mut res = Vec::new();
+
+        for (neuron_id, neuron_rates) in rates.outer_iter().enumerate() {
+            for (window_index, rate) in neuron_rates.iter().enumerate() {
+                let start = window_index as u128 * window;
+
+                res.extend(
+                    poisson_timestamps(&mut rng, *rate, window)
+                        .into_iter()
+                        .map(|ts| Spike::new(start + ts, neuron_id))
+                );
+            }
+        }
+        res.sort();
+
+        res
     }
 
-    /// Get the input weight to the specified entry-layer neuron
+    /// Return the timestamps of the earliest and of the latest spike of `spikes`, or [None] if there are none.
+    /// 
+    /// `spikes` doesn't need to be sorted.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
-    ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
-    ///     )
-    ///     .build();
+    /// # use pds_spiking_nn::Spike;
+    /// let spikes = vec![Spike::new(7, 0), Spike::new(2, 1), Spike::new(12, 0)];
     /// 
-    /// assert_eq!(nn.get_input_weight(0), Some(1.5));
-    /// assert_eq!(nn.get_input_weight(2), None);
+    /// assert_eq!(Spike::time_span(&spikes), Some((2, 12)));
+    /// assert_eq!(Spike::time_span(&[]), None);
     /// ```
-    pub fn get_input_weight(&self, to: usize) -> Option<f64> {
-        self.layers[0].input_weights.get((to, to)).copied()
+    pub fn time_span(spikes: &[Spike]) -> Option<(u128, u128)> {
+        let first = spikes.iter().map(|spike| spike.ts).min()?;
+        let last = spikes.iter().map(|spike| spike.ts).max()?;
+
+        Some((first, last))
     }
 
-    /// Get a mutable reference to the specified entry-layer neuron
+    /// Compute the firing rate of each of `num_neurons` neurons over the window of timestamps `0..window`,
+    /// in spikes per unit of time: the spikes of every neuron in the window are counted and divided by `window`.
+    /// 
+    /// Spikes outside the window are ignored, and all the rates are 0.0 if `window` is 0.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the id of a spike is not lower than `num_neurons`.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
-    ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
-    ///     )
-    ///     .build();
-    /// 
-    /// // Modify the second input weight
-    /// *nn.get_input_weight_mut(1).unwrap() = 2.0;
+    /// # use pds_spiking_nn::Spike;
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![10, 20, 30, 40]),
+    ///     Spike::spike_vec_for(2, vec![50, 150])
+    /// ]);
     /// 
-    /// assert_eq!(nn.get_input_weight(1), Some(2.0));
+    /// assert_eq!(Spike::firing_rates(&spikes, 3, 100), vec![0.04, 0.0, 0.01]);
     /// ```
-    pub fn get_input_weight_mut(&mut self, to: usize) -> Option<&mut f64> {
-        self.layers[0].input_weights.get_mut((to, to))
+    pub fn firing_rates(spikes: &[Spike], num_neurons: usize, window: u128) -> Vec<f64> {
+        let mut counts = vec![0usize; num_neurons];
+
+        for spike in spikes {
+            assert!(spike.neuron_id < num_neurons, "the spike of neuron {} is out of bounds", spike.neuron_id);
+
+            if spike.ts < window {
+                counts[spike.neuron_id] += 1;
+            }
+        }
+
+        counts.into_iter()
+            .map(|count| if window == 0 { 0.0 } else { count as f64 / window as f64 })
+            .collect()
     }
 
-    /// Get the intra or input weight determined by the `from` and `to` neurons.
+    /// Rate decoding: return the class (i.e. the id of the output neuron) that fired the most among the first `num_classes`,
+    /// or [None] if none of them fired at all.
     /// 
-    /// The given neurons must be of the same or consecutive layers, otherwise this function will
-    /// return [None].
-    /// 
-    /// An unchecked variant of this functionality is provided via the [Index] implementation.
+    /// Spikes of neurons with an id not lower than `num_classes` are ignored. In case of a tie, the class with the lowest id wins.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
-    ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
-    ///     )
-    ///     .build();
+    /// # use pds_spiking_nn::Spike;
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![10, 20]),
+    ///     Spike::spike_vec_for(1, vec![5, 15, 25]),
+    ///     Spike::spike_vec_for(3, vec![1, 2, 3, 4])
+    /// ]);
     /// 
-    /// assert_eq!(nn.get_weight((0, 1), (0, 0)), Some(-0.2));
-    /// assert_eq!(nn.get_weight((0, 0), (1, 2)), None);
-    /// assert_eq!(nn.get_weight((0, 1), (2, 0)), None);
+    /// assert_eq!(Spike::argmax_by_count(&spikes, 3), Some(1));
+    /// assert_eq!(Spike::argmax_by_count(&spikes, 4), Some(3));
+    /// assert_eq!(Spike::argmax_by_count(&[], 4), None);
     /// ```
-    pub fn get_weight(&self, from: (usize, usize), to: (usize, usize)) -> Option<f64> {
-        if from.0 == to.0 {
-            // Intra-layer weight
-            self.get_layer(from.0)?.intra_weights.get((from.1, to.1)).copied()
-        } else if from.0 + 1 == to.0 {
-            // Inter-layer weight
-            self.get_layer(to.0)?.input_weights.get((from.1, to.1)).copied()
-        } else {
-            None
+    pub fn argmax_by_count(spikes: &[Spike], num_classes: usize) -> Option<usize> {
+        let mut counts = vec![0usize; num_classes];
+
+        for spike in spikes.iter().filter(|s| s.neuron_id < num_classes) {
+            counts[spike.neuron_id] += 1;
         }
+
+        counts.into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .fold(None, |best: Option<(usize, usize)>, (class, count)| match best {
+                Some((_, best_count)) if best_count >= count => best,
+                _ => Some((class, count))
+            })
+            .map(|(class, _)| class)
     }
 
-    /// Get a mutable reference to the intra or input weight determined by the `from` and `to` neurons.
+    /// Latency decoding: return the id of the neuron that fired first, or [None] if `spikes` is empty.
     /// 
-    /// The given neurons must be of the same or consecutive layers, otherwise this function will
-    /// return [None].
+    /// `spikes` doesn't need to be sorted. In case of a tie, the neuron with the lowest id wins.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::Spike;
+    /// let spikes = vec![Spike::new(7, 0), Spike::new(3, 2), Spike::new(3, 1)];
+    /// 
+    /// assert_eq!(Spike::first_to_spike(&spikes), Some(1));
+    /// assert_eq!(Spike::first_to_spike(&[]), None);
+    /// ```
+    pub fn first_to_spike(spikes: &[Spike]) -> Option<usize> {
+        spikes.iter().min().map(|spike| spike.neuron_id)
+    }
+
+    /// Compress the sorted timestamps of a spike train (like the ones of every neuron returned by [solve](NN::solve)),
+    /// storing the gaps between consecutive spikes instead of their absolute timestamps.
+    /// 
+    /// Every `(gap, count)` pair stands for `count` consecutive spikes, each one `gap` after the previous one
+    /// (the first spike of the train is measured from 0). Regular trains are thus compressed to a handful of pairs,
+    /// whatever their length. Decompress the train with [rle_decode](Spike::rle_decode).
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `ts` is not sorted.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::Spike;
+    /// let train = vec![100, 200, 300, 400, 450, 500];
+    /// 
+    /// assert_eq!(Spike::rle_encode(&train), vec![(100, 4), (50, 2)]);
+    /// ```
+    pub fn rle_encode(ts: &[u128]) -> Vec<(u128, usize)> {
+        let mut res: Vec<(u128, usize)> = Vec::new();
+        let mut previous = 0;
+
+        for &ts in ts {
+            let gap = ts.checked_sub(previous).expect("the spike train must be sorted");
+            previous = ts;
+
+            match res.last_mut() {
+                Some((last_gap, count)) if *last_gap == gap => *count += 1,
+                _ => res.push((gap, 1))
+            }
+        }
+
+        res
+    }
+
+    /// Decompress a spike train compressed by [rle_encode](Spike::rle_encode), returning its sorted timestamps.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::Spike;
+    /// let train = Spike::rle_decode(&[(100, 4), (50, 2)]);
+    /// 
+    /// assert_eq!(train, vec![100, 200, 300, 400, 450, 500]);
+    /// ```
+    pub fn rle_decode(encoded: &[(u128, usize)]) -> Vec<u128> {
+        let mut res = Vec::with_capacity(encoded.iter().map(|(_, count)| count).sum());
+        let mut ts = 0;
+
+        for &(gap, count) in encoded {
+            for _ in 0..count {
+                ts += gap;
+                res.push(ts);
+            }
+        }
+
+        res
+    }
+
+    /// Read spikes from `reader` in CSV format, with one `neuron_id,ts` record per line.
+    /// 
+    /// A third `polarity` field is accepted too, for the spikes written by [to_csv](Spike::to_csv) with a non-default [polarity](Spike::polarity).
+    /// Empty lines are skipped. The spikes are returned sorted, ready to be fed to [solve](NN::solve).
+    /// 
+    /// # Errors
+    /// 
+    /// Fails if `reader` can't be read, or with [SpikeCsvError::Malformed] at the first line that isn't a valid record.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::Spike;
+    /// let csv = "1,5\n0,3\n1,2,-1\n";
+    /// 
+    /// assert_eq!(
+    ///     Spike::from_csv(csv.as_bytes()).unwrap(),
+    ///     vec![Spike::with_polarity(2, 1, -1), Spike::new(3, 0), Spike::new(5, 1)]
+    /// );
+    /// assert!(Spike::from_csv("0,3\n1;5\n".as_bytes()).is_err());
+    /// ```
+    pub fn from_csv(reader: impl Read) -> Result<Vec<Spike>, SpikeCsvError> {
+        let mut res = Vec::new();
+
+        for (i, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let malformed = |reason: &str| SpikeCsvError::Malformed { line: i + 1, content: line.clone(), reason: reason.to_string() };
+            let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+
+            let (neuron_id, ts, polarity) = match fields[..] {
+                [neuron_id, ts] => (neuron_id, ts, None),
+                [neuron_id, ts, polarity] => (neuron_id, ts, Some(polarity)),
+                _ => return Err(malformed("expected 2 or 3 comma-separated fields"))
+            };
+
+            res.push(Spike::with_polarity(
+                ts.parse().map_err(|_| malformed("invalid timestamp"))?,
+                neuron_id.parse().map_err(|_| malformed("invalid neuron id"))?,
+                match polarity {
+                    Some(polarity) => polarity.parse().map_err(|_| malformed("invalid polarity"))?,
+                    None => Spike::default_polarity()
+                }
+            ));
+        }
+        res.sort();
+
+        Ok(res)
+    }
+
+    /// Write `spikes` to `writer` in the CSV format read by [from_csv](Spike::from_csv), with one `neuron_id,ts` record per line.
+    /// 
+    /// Records are sorted by timestamp (and then by neuron id), so the same spikes always produce the same output regardless of their order.
+    /// The polarity is only written, as a third field, for spikes whose polarity isn't the default one.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::Spike;
+    /// let spikes = vec![Spike::new(5, 1), Spike::new(3, 0), Spike::with_polarity(2, 1, -1)];
+    /// 
+    /// let mut csv = vec![];
+    /// Spike::to_csv(&spikes, &mut csv).unwrap();
+    /// 
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "1,2,-1\n0,3\n1,5\n");
+    /// ```
+    pub fn to_csv(spikes: &[Spike], mut writer: impl Write) -> io::Result<()> {
+        let mut sorted = spikes.iter().collect::<Vec<_>>();
+        sorted.sort();
+
+        for spike in sorted {
+            if spike.polarity == Spike::default_polarity() {
+                writeln!(writer, "{},{}", spike.neuron_id, spike.ts)?;
+            } else {
+                writeln!(writer, "{},{},{}", spike.neuron_id, spike.ts, spike.polarity)?;
+            }
+        }
+
+        writer.flush()
+    }
+}
+
+/// Sample the (sorted, unique) timestamps in `0..duration` of a Poisson process with the given rate,
+/// by accumulating exponentially distributed inter-spike intervals.
+/// 
+/// Multiple events falling in the same unit of time are merged in a single one.
+fn poisson_timestamps(rng: &mut impl Rng, rate: f64, duration: u128) -> Vec<u128> {
+    assert!(rate.is_finite() && rate >= 0.0, "firing rates must be finite and non-negative");
+
+    let mut res: Vec<u128> = Vec::new();
+    if rate == 0.0 {
+        return res;
+    }
+
+    let mut t = 0.0;
+    loop {
+        // 1 - u is in (0, 1], so the logarithm is always finite
+        t += -(1.0 - rng.gen::<f64>()).ln() / rate;
+
+        let ts = t as u128;
+        if ts >= duration {
+            break;
+        }
+        if res.last() != Some(&ts) {
+            res.push(ts);
+        }
+    }
+
+    res
+}
+
+/// Sample a standard normal distribution through the Box-Muller transform
+fn gaussian(rng: &mut impl Rng) -> f64 {
+    // 1 - u is in (0, 1], so the logarithm is always finite
+    let (u1, u2) = (1.0 - rng.gen::<f64>(), rng.gen::<f64>());
+
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+impl fmt::Display for Spike {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Spike(ts: {}, neuron_id: {}, polarity: {})", self.ts, self.neuron_id, self.polarity)
+    }
+}
+
+/// Error for [NN]'s [concat](NN::concat) and [extend](NN::extend).
+/// 
+/// Only one variant is needed because only one kind of error can happen.
+#[derive(Error, Debug)]
+pub enum NNConcatError {
+    #[error("Provided intra-nn weights have invalid dimensions")]
+    InvalidWeightsLen
+}
+
+/// Error for [Spike::from_csv].
+#[derive(Error, Debug)]
+pub enum SpikeCsvError {
+    /// The spikes couldn't be read
+    #[error("Failed to read the spikes: {0}")]
+    Io(#[from] io::Error),
+    /// A line isn't a valid `neuron_id,ts` record
+    #[error("Malformed spike at line {line} (\"{content}\"): {reason}")]
+    Malformed {
+        /// Number of the line, starting from 1
+        line: usize,
+        /// Content of the line
+        content: String,
+        /// What's wrong with the line
+        reason: String
+    }
+}
+
+/// Error for [NN]'s [try_solve](NN::try_solve) and [check_zero_delay_cycles](NN::check_zero_delay_cycles).
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The neurons of a layer excite each other in a cycle of intra-layer synapses.
+    /// 
+    /// Intra-layer spikes have no delay, so the solver can't order the spikes of these neurons,
+    /// which could keep firing forever at the same timestamp.
+    #[error("Neurons {neurons:?} of layer {layer} form a cycle of excitatory zero-delay synapses")]
+    ZeroDelayCycle {
+        /// Index of the layer the cycle is in
+        layer: usize,
+        /// Ids of the neurons along the cycle, in order
+        neurons: Vec<usize>
+    }
+}
+
+/// Set of synapses of a layer of a [NN], as located by a [ValidationIssue].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Synapses {
+    /// The input synapses, from the previous layer (or from the inputs, for the entry layer)
+    Input,
+    /// The intra-layer synapses
+    Intra,
+    /// The skip connections from an earlier layer, going back the given number of layers
+    Skip(usize)
+}
+
+/// Issue found by [validate](NN::validate) in a [NN].
+/// 
+/// Every issue locates its layer and, where relevant, the synapse it concerns, as the ids of its presynaptic and postsynaptic neurons.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    #[error("The network has no layers")]
+    NoLayers,
+
+    #[error("Layer {layer} has no neurons")]
+    EmptyLayer {
+        layer: usize
+    },
+
+    #[error("{synapses:?} weights of layer {layer} have shape {found:?}, expected {expected:?}")]
+    WeightsShape {
+        layer: usize,
+        synapses: Synapses,
+        expected: (usize, usize),
+        found: (usize, usize)
+    },
+
+    #[error("{synapses:?} delays of layer {layer} have shape {found:?}, expected {expected:?}")]
+    DelaysShape {
+        layer: usize,
+        synapses: Synapses,
+        expected: (usize, usize),
+        found: (usize, usize)
+    },
+
+    #[error("Input weight from {from} to {to} of the entry layer is off the diagonal")]
+    OffDiagonalInput {
+        from: usize,
+        to: usize
+    },
+
+    #[error("Neuron {neuron} of layer {layer} has a synapse onto itself (non-zero diagonal entry)")]
+    SelfSynapse {
+        layer: usize,
+        neuron: usize
+    },
+
+    #[error("{synapses:?} weight from {from} to {to} of layer {layer} is not finite")]
+    NonFiniteWeight {
+        layer: usize,
+        synapses: Synapses,
+        from: usize,
+        to: usize
+    },
+
+    #[error("Skip connection of layer {layer} goes back {back} layers, which is not an earlier layer")]
+    InvalidSkip {
+        layer: usize,
+        back: usize
+    },
+
+    #[error("Heads {heads:?} don't add up to the {found} neurons of the output layer")]
+    HeadsMismatch {
+        heads: Vec<usize>,
+        found: usize
+    }
+}
+
+/// Floating point type of the weights of the synapses of a [NN]: either [f64] (the default) or [f32].
+/// 
+/// Weights are only stored with this type: weighted inputs are always accumulated as [f64] before being handed to the [Model],
+/// so a smaller type saves memory (and cache) at the cost of the precision of the weights alone.
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use pds_spiking_nn::{NN, NNBuilder, lif::*};
+/// let nn: NN<LeakyIntegrateFire, f32> = NNBuilder::<LeakyIntegrateFire, _, f32>::new()
+///     .layer(
+///         [
+///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+///         ],
+///         [1.5, 1.8],
+///         [[0.0, -0.3], [-0.2, 0.0]]
+///     )
+///     .build();
+/// 
+/// assert_eq!(nn.get_input_weight(1), Some(1.8f32));
+/// ```
+pub trait Float: num_traits::Float + fmt::Debug + fmt::Display + Send + Sync + 'static {
+    /// Convert the weight to [f64], without loss of precision
+    fn widen(self) -> f64 {
+        self.to_f64().unwrap()
+    }
+
+    /// Convert a [f64] to a weight, rounding to the nearest representable value
+    fn narrow(v: f64) -> Self {
+        <Self as num_traits::NumCast>::from(v).unwrap()
+    }
+}
+
+impl Float for f32 { }
+impl Float for f64 { }
+
+/// The Neural Network itself.
+/// 
+/// This organizes [Neuron](Model::Neuron)s into consecutive layers, each constituted of some amount of [Neuron](Model::Neuron)s.
+/// [Neuron](Model::Neuron)s of the same or consecutive layers are connected by a weighted synapse, whose weight is a [Float]
+/// ([f64] by default, or [f32] to halve the memory taken by the weights of large networks).
+/// 
+/// A neural network is stimulated by [Spike]s applied to the [Neuron](Model::Neuron)s of its entry layer.
+/// 
+/// Create a new [NN] through the builder at [NNBuilder](crate::NNBuilder).
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(
+    serialize = "M::Neuron: serde::Serialize, F: serde::Serialize",
+    deserialize = "M::Neuron: serde::Deserialize<'de>, F: serde::Deserialize<'de>"
+)))]
+pub struct NN<M: Model, F: Float = f64> {
+    /// All the sorted layers of the neural network
+    layers: Vec<Layer<M, F>>,
+    /// Sizes of the heads the output layer is split in, in order (empty if the output layer is a single head)
+    #[cfg_attr(feature = "serde", serde(default))]
+    heads: Vec<usize>
+}
+
+/// Options for solving a [NN] with [solve_with_config](NN::solve_with_config).
+/// 
+/// The [Default] configuration is the one used by [solve](NN::solve).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SolveConfig {
+    /// Maximum number of threads (or tasks, with the `async` feature) the layers of the network are spread on.
+    /// 
+    /// Defaults to the [available parallelism](std::thread::available_parallelism) of the system.
+    pub max_threads: NonZeroUsize,
+    /// Minimum number of neurons worth a thread (or task) of their own.
+    /// 
+    /// The layers are split in fewer groups if needed, so that every group has at least this many neurons (small layers
+    /// are grouped with their neighbours), and networks with fewer neurons in total are solved inline, on the calling thread: for small layers,
+    /// the overhead of the threads outweighs the benefit of solving them in parallel.
+    /// 
+    /// Defaults to 0, so that every group of layers always gets its own thread.
+    pub min_neurons_for_threading: usize
+}
+
+impl Default for SolveConfig {
+    fn default() -> Self {
+        Self {
+            max_threads: std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+            min_neurons_for_threading: 0
+        }
+    }
+}
+
+impl SolveConfig {
+    /// Maximum number of groups the layers of a network with `num_neurons` neurons can be split in,
+    /// or [None] if the network must be solved inline
+    fn max_groups(&self, num_neurons: usize) -> Option<usize> {
+        if num_neurons < self.min_neurons_for_threading {
+            return None;
+        }
+
+        Some(self.max_threads.get().min(num_neurons / self.min_neurons_for_threading.max(1)))
+    }
+}
+
+/// Output of a solve with [solve_with_stats](NN::solve_with_stats), along with some statistics about it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SolveResult {
+    /// The timestamps of the spikes generated by every neuron of the output layer, as returned by [solve](NN::solve)
+    pub output: Vec<Vec<u128>>,
+    /// Number of spikes generated by every neuron of the output layer
+    pub spikes_per_neuron: Vec<usize>,
+    /// Total number of spikes generated by the output layer
+    pub total_spikes: usize
+}
+
+/// Statistics about the synapses of a [NN], as returned by [synapse_stats](NN::synapse_stats).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SynapseStats {
+    /// Number of synapses the network could have: one for every entry of the input (diagonal, for the entry layer),
+    /// intra-layer and skip connection weight matrices
+    pub total: usize,
+    /// Number of synapses whose weight is not null
+    pub nonzero: usize,
+    /// Fraction of the potential synapses whose weight is null, between 0 and 1
+    pub sparsity: f64
+}
+
+/// Scale factors of the weight matrices of a layer, as returned by [quantize](NN::quantize).
+/// 
+/// A weight `w` is stored on the hardware as the integer `w / s`, where `s` is the scale factor of its matrix.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LayerScales {
+    /// Scale factor of the input weights
+    pub input: f64,
+    /// Scale factor of the intra-layer weights
+    pub intra: f64,
+    /// Scale factor of the weights of every skip connection ending in the layer, in the same order as the connections
+    pub skips: Vec<f64>
+}
+
+/// In-flight state of a solve of a [NN], returned by [solve_resumable](NN::solve_resumable) to resume the solve later.
+/// 
+/// This holds the [SolverVars](Model::SolverVars) of every neuron and the inputs still traveling through delayed synapses.
+pub struct SimulationState<M: Model> {
+    /// State of every layer of the network, in order
+    layers: Vec<sync::LayerState<M>>,
+    /// Latest timestamp any neuron was updated at, if any
+    ts: Option<u128>
+}
+
+impl<M: Model> SimulationState<M> {
+    /// Latest timestamp the solve reached, if any: the spikes the solve is resumed with must come after it.
+    pub fn ts(&self) -> Option<u128> {
+        self.ts
+    }
+
+    /// Whether some inputs are still traveling through delayed synapses, which will reach their neurons once the solve is resumed.
+    pub fn has_pending_inputs(&self) -> bool {
+        self.layers.iter().any(|(_, pending)| !pending.is_empty())
+    }
+}
+
+impl<M: Model> fmt::Debug for SimulationState<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimulationState")
+            .field("ts", &self.ts)
+            .field("has_pending_inputs", &self.has_pending_inputs())
+            .finish()
+    }
+}
+
+impl From<Vec<Vec<u128>>> for SolveResult {
+    fn from(output: Vec<Vec<u128>>) -> Self {
+        let spikes_per_neuron = output.iter().map(Vec::len).collect::<Vec<_>>();
+        let total_spikes = spikes_per_neuron.iter().sum();
+
+        Self { output, spikes_per_neuron, total_spikes }
+    }
+}
+
+impl<M: Model, F: Float> NN<M, F> {
+    /// Return the number of layers in this neural network.
+    /// 
+    /// This is always guaranteed to be greater than zero
+    /// by the [NNBuilder](crate::NNBuilder) necessary to construct an instance of [NN].
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// assert_eq!(nn.num_layers(), 1);
+    /// ```
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Return the number of neurons in the specified layer, or [None] if the index is out of bounds.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// assert_eq!(nn.layer_size(0), Some(2));
+    /// assert_eq!(nn.layer_size(1), None);
+    /// ```
+    pub fn layer_size(&self, index: usize) -> Option<usize> {
+        self.layers.get(index).map(|layer| layer.neurons.len())
+    }
+
+    /// Return the number of output heads of the network (see [NNBuilder::head](crate::NNBuilder::head)).
+    /// 
+    /// A network built without heads has a single one, made of its whole output layer.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// assert_eq!(nn.num_heads(), 1);
+    /// ```
+    pub fn num_heads(&self) -> usize {
+        self.heads.len().max(1)
+    }
+
+    /// Get the specified layer, or [None] if the index is out of bounds.
+    /// 
+    /// An unchecked variant of this functionality is provided via the [Index] implementation.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Get a reference to the first layer
+    /// let first_layer = nn.get_layer(0);
+    /// 
+    /// assert_eq!(first_layer.unwrap().num_neurons(), 2);
+    /// ```
+    pub fn get_layer(&self, layer: usize) -> Option<&Layer<M, F>> {
+        self.layers.get(layer)
+    }
+
+    /// Get a mutable reference to the specified layer, or [None] if the index is out of bounds.
+    /// 
+    /// An unchecked variant of this functionality is provided via the [IndexMut] implementation.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Modify an intra-layer weight
+    /// *nn.get_layer_mut(0).unwrap().get_intra_weight_mut(0, 1).unwrap() = -1.7;
+    /// 
+    /// assert_eq!(nn[0][(0, 1)], -1.7);
+    /// ```
+    pub fn get_layer_mut(&mut self, layer: usize) -> Option<&mut Layer<M, F>> {
+        self.layers.get_mut(layer)
+    }
+
+    /// Get the neuron at the specified position of the specified layer, or [None] if any index is out of bounds.
+    /// 
+    /// An unchecked variant of this functionality is provided via the [Index] implementation.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Get a reference to the second neuron of the only layer of the nn
+    /// let neuron = nn.get_neuron(0, 1);
+    /// 
+    /// println!("{:?}", neuron); // Some(LifNeuron { v_rest: 1.0, v_reset: 0.4, v_threshold: 3.1, tau: 1.1, refractory_period: 0, threshold_adaptation: 0.0, tau_threshold: 0.0, v_init_offset: 0.0 })
+    /// ```
+    pub fn get_neuron(&self, layer: usize, neuron: usize) -> Option<&M::Neuron> {
+        self.layers.get(layer)?.neurons.get(neuron)
+    }
+
+    /// Get a mutable reference to the neuron at the specified position of the specified layer, or [None] if any index is out of bounds.
+    /// 
+    /// An unchecked variant of this functionality is provided via the [IndexMut] implementation.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Get a mutable reference to the second neuron of the only layer of the nn
+    /// let mut neuron = nn.get_neuron_mut(0, 1).unwrap();
+    /// neuron.v_rest += 0.2;
+    /// 
+    /// assert_eq!(nn[0][1].v_rest, 1.2);
+    /// ```
+    pub fn get_neuron_mut(&mut self, layer: usize, neuron: usize) -> Option<&mut M::Neuron> {
+        self.layers.get_mut(layer)?.neurons.get_mut(neuron)
+    }
+
+    /// Get the input weight to the specified entry-layer neuron
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// assert_eq!(nn.get_input_weight(0), Some(1.5));
+    /// assert_eq!(nn.get_input_weight(2), None);
+    /// ```
+    pub fn get_input_weight(&self, to: usize) -> Option<F> {
+        self.layers[0].input_weights.get((to, to)).copied()
+    }
+
+    /// Get a mutable reference to the specified entry-layer neuron
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Modify the second input weight
+    /// *nn.get_input_weight_mut(1).unwrap() = 2.0;
+    /// 
+    /// assert_eq!(nn.get_input_weight(1), Some(2.0));
+    /// ```
+    pub fn get_input_weight_mut(&mut self, to: usize) -> Option<&mut F> {
+        self.layers[0].input_weights.get_mut((to, to))
+    }
+
+    /// Get a read-only view of the input weights of the entry layer, one for every neuron.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// assert_eq!(nn.input_weights().to_vec(), vec![1.5, 1.8]);
+    /// ```
+    pub fn input_weights(&self) -> ArrayView1<'_, F> {
+        self.layers[0].input_weights.diag()
+    }
+
+    /// Get the intra or input weight determined by the `from` and `to` neurons.
+    /// 
+    /// The given neurons must be of the same or consecutive layers, otherwise this function will
+    /// return [None].
+    /// 
+    /// An unchecked variant of this functionality is provided via the [Index] implementation.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// assert_eq!(nn.get_weight((0, 1), (0, 0)), Some(-0.2));
+    /// assert_eq!(nn.get_weight((0, 0), (1, 2)), None);
+    /// assert_eq!(nn.get_weight((0, 1), (2, 0)), None);
+    /// ```
+    pub fn get_weight(&self, from: (usize, usize), to: (usize, usize)) -> Option<F> {
+        if from.0 == to.0 {
+            // Intra-layer weight
+            self.get_layer(from.0)?.intra_weights.get((from.1, to.1)).copied()
+        } else if from.0 + 1 == to.0 {
+            // Inter-layer weight
+            self.get_layer(to.0)?.input_weights.get((from.1, to.1)).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the intra or input weight determined by the `from` and `to` neurons.
+    /// 
+    /// The given neurons must be of the same or consecutive layers, otherwise this function will
+    /// return [None].
+    /// 
+    /// The network doesn't need to be rebuilt after changing a weight: the next solve already uses the new one,
+    /// which makes this the cheapest way to sweep a parameter (see also [get_input_weight_mut](NN::get_input_weight_mut)
+    /// for the input weights of the entry layer).
+    /// 
+    /// An unchecked variant of this functionality is provided via the [IndexMut] implementation.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Modify a weight
+    /// *nn.get_weight_mut((0, 1), (0, 0)).unwrap() = -0.5;
+    /// 
+    /// assert_eq!(nn[0][(1, 0)], -0.5);
+    /// ```
+    pub fn get_weight_mut(&mut self, from: (usize, usize), to: (usize, usize)) -> Option<&mut F> {
+        if from.0 == to.0 {
+            // Intra-layer weight
+            self.get_layer_mut(from.0)?.intra_weights.get_mut((from.1, to.1))
+        } else if from.0 + 1 == to.0 {
+            // Inter-layer weight
+            self.get_layer_mut(to.0)?.input_weights.get_mut((from.1, to.1))
+        } else {
+            None
+        }
+    }
+
+    /// Get the matrix of the synapses from the neurons of `layer` to those of the next layer,
+    /// or [None] if `layer` is the last layer or is out of bounds.
+    /// 
+    /// The matrix has a row for every neuron of `layer` and a column for every neuron of the next layer.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .layer(
+    ///         [LifNeuron::new(&LifNeuronConfig::new(0.9, 0.5, 2.8, 1.4))],
+    ///         [[1.3], [1.4]],
+    ///         [[0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let synapses = nn.synapses_between(0).unwrap();
+    /// assert_eq!(synapses.dim(), (2, 1));
+    /// assert_eq!(synapses[(1, 0)], 1.4);
+    /// assert!(nn.synapses_between(1).is_none());
+    /// ```
+    pub fn synapses_between(&self, layer: usize) -> Option<&Array2<F>> {
+        self.layers.get(layer.checked_add(1)?).map(|layer| &layer.input_weights)
+    }
+
+    /// Count the synapses of the network, over the input, intra-layer and skip connection weights of all of its layers.
+    /// 
+    /// Weights whose absolute value is not greater than `epsilon` are considered null. The input weights of the
+    /// entry layer only count on their diagonal, as every input neuron is connected to a single neuron.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .layer(
+    ///         [LifNeuron::new(&LifNeuronConfig::new(0.9, 0.5, 2.8, 1.4))],
+    ///         [[1.3], [1.4]],
+    ///         [[0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let stats = nn.synapse_stats(0.0);
+    /// assert_eq!((stats.total, stats.nonzero), (9, 6));
+    /// assert!((stats.sparsity - 1.0 / 3.0).abs() < 1e-12);
+    /// 
+    /// // Ignore the weak inhibitory synapses too
+    /// assert_eq!(nn.synapse_stats(0.5).nonzero, 4);
+    /// ```
+    pub fn synapse_stats(&self, epsilon: f64) -> SynapseStats {
+        let is_nonzero = |w: &F| w.widen().abs() > epsilon;
+        let (mut total, mut nonzero) = (0, 0);
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            if i == 0 {
+                total += layer.input_weights.nrows();
+                nonzero += layer.input_weights.diag().iter().filter(|w| is_nonzero(w)).count();
+            } else {
+                total += layer.input_weights.len();
+                nonzero += layer.input_weights.iter().filter(|w| is_nonzero(w)).count();
+            }
+
+            total += layer.intra_weights.len();
+            nonzero += layer.intra_weights.iter().filter(|w| is_nonzero(w)).count();
+
+            for (_, weights) in &layer.skips {
+                total += weights.len();
+                nonzero += weights.iter().filter(|w| is_nonzero(w)).count();
+            }
+        }
+
+        SynapseStats {
+            total,
+            nonzero,
+            sparsity: if total == 0 { 0.0 } else { 1.0 - nonzero as f64 / total as f64 }
+        }
+    }
+
+    /// Extend this`[NN] in place by appending the other provided network to it.
+    /// 
+    /// The two neural networks are merged via the provided new input weights, which will replace `other`'s.
+    /// 
+    /// In case of errors, `self` will be preserved.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let mut nn1 = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let nn2 = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [LifNeuron::new(&LifNeuronConfig::new(0.9, 0.5, 2.8, 1.4))],
+    ///         [1.3],
+    ///         [[0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Extend nn1 by concatenating nn2 to it in place
+    /// assert!(nn1.extend(&nn2, [1.3, 1.4]).is_ok());
+    /// assert_eq!(nn1.num_layers(), 2);
+    /// assert_eq!(nn1[((0, 1), (1, 0))], 1.4);
+    /// ```
+    pub fn extend(&mut self, other: &Self, intra_nn_weights: impl Borrow<[F]>) -> Result<(), NNConcatError> {
+        let new_input_weights = Array2::from_shape_vec(
+            (self.layers.last().unwrap().num_neurons(), other.layers[0].num_neurons()),
+            intra_nn_weights.borrow().to_vec()
+        ).map_err(|_| NNConcatError::InvalidWeightsLen)?;
+
+        let old_len = self.num_layers();
+        self.layers.extend_from_slice(&other.layers[..]);
+        self.layers[old_len].input_weights = new_input_weights;
+        self.layers[old_len].input_delays = None;
+        self.heads = other.heads.clone();
+
+        Ok(())
+    }
+
+    /// Concatenate this [NN] with another one, to obtain a new [NN].
+    /// 
+    /// The two neural networks are merged via the provided new input weights, which will replace `other`'s.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// // Create a sample nn
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Create a new NN by concatenating nn to itself
+    /// let new_nn = nn.concat(&nn, [1.2, 1.4, 1.5, 1.2]).unwrap();
+    /// 
+    /// assert_eq!(new_nn.num_layers(), 2);
+    /// assert_eq!(new_nn[((0, 0), (1, 1))], 1.4);
+    /// ```
+    pub fn concat(&self, other: &Self, intra_nn_weights: impl Borrow<[F]>) -> Result<Self, NNConcatError> {
+        let mut new_nn = self.clone();
+        new_nn.extend(other, intra_nn_weights).map(|_| new_nn)
+    }
+
+    /// Quantize every weight of the network to a uniform fixed-point grid of `2^bits` levels spanning `range`,
+    /// as required by neuromorphic hardware targets that only support low-precision synapses.
+    /// 
+    /// Weights outside of `range` are clamped to its bounds, while null weights are left untouched since they
+    /// represent missing synapses.
+    /// 
+    /// Returns the root mean square quantization error over all the quantized weights, which can be used
+    /// to assess the loss of accuracy before deploying the network.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `bits` is not in the range `1..64`, or if `range.0` is not smaller than `range.1`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // 16 levels between -4.0 and 3.5, i.e. steps of 0.5
+    /// let error = nn.quantize_weights(4, (-4.0, 3.5));
+    /// 
+    /// assert_eq!(nn.get_input_weight(0), Some(1.5));
+    /// assert_eq!(nn.get_input_weight(1), Some(2.0));
+    /// assert_eq!(nn[0].get_intra_weight(0, 1), Some(-0.5));
+    /// assert!(error <= 0.25);
+    /// ```
+    pub fn quantize_weights(&mut self, bits: u8, range: (f64, f64)) -> f64 {
+        let (min, max) = range;
+        assert!((1..64).contains(&bits), "the number of bits must be in the range 1..64");
+        assert!(min < max, "invalid quantization range");
+
+        let step = (max - min) / ((1u64 << bits) - 1) as f64;
+        let mut squared_error = 0.0;
+        let mut num_weights = 0usize;
+
+        for layer in &mut self.layers {
+            for w in layer.input_weights.iter_mut()
+                .chain(layer.intra_weights.iter_mut())
+                .chain(layer.skips.iter_mut().flat_map(|(_, weights)| weights.iter_mut()))
+            {
+                if w.is_zero() {
+                    continue;
+                }
+
+                let quantized = F::narrow(min + ((w.widen().clamp(min, max) - min) / step).round() * step);
+                squared_error += (quantized - *w).widen().powi(2);
+                num_weights += 1;
+                *w = quantized;
+            }
+        }
+
+        if num_weights == 0 {
+            0.0
+        } else {
+            (squared_error / num_weights as f64).sqrt()
+        }
+    }
+
+    /// Quantize every weight matrix of the network to a symmetric fixed-point grid of `bits` bits (sign included),
+    /// scaled on the weights actually found in the matrix.
+    /// 
+    /// Unlike [quantize_weights](NN::quantize_weights), the range is not shared: the input, the intra-layer and every skip connection
+    /// weights of every layer have their own scale factor `s = max(|w|) / (2^(bits-1) - 1)`, and every weight is rounded to the nearest multiple of it.
+    /// Null weights stay null, and the largest weights (in absolute value) of every matrix map to the extreme levels of the grid.
+    /// 
+    /// Returns the [scale factors](LayerScales) of every layer, in order: a weight `w` is stored on the hardware
+    /// as the integer `w / s`, and reconstructed by multiplying it back by `s`. The scale factor of a matrix without synapses is 0.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `bits` is not in the range `2..64`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Levels from -3 to 3: steps of 0.6 for the input weights and of 0.1 for the intra-layer ones
+    /// let scales = nn.quantize(3);
+    /// let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+    /// 
+    /// assert_eq!(scales.len(), 1);
+    /// assert!(close(scales[0].input, 0.6) && close(scales[0].intra, 0.1));
+    /// assert!(scales[0].skips.is_empty());
+    /// assert!(close(nn.get_input_weight(0).unwrap(), 1.8));
+    /// assert!(close(nn[0].get_intra_weight(1, 0).unwrap(), -0.2));
+    /// ```
+    pub fn quantize(&mut self, bits: u8) -> Vec<LayerScales> {
+        assert!((2..64).contains(&bits), "the number of bits must be in the range 2..64");
+        let max_level = ((1u64 << (bits - 1)) - 1) as f64;
+
+        let quantize_matrix = |weights: &mut Array2<F>| {
+            let max = weights.iter().fold(0.0, |max: f64, w| max.max(w.widen().abs()));
+            if max == 0.0 {
+                return 0.0;
+            }
+
+            let scale = max / max_level;
+            weights.mapv_inplace(|w| F::narrow((w.widen() / scale).round() * scale));
+            scale
+        };
+
+        self.layers.iter_mut()
+            .map(|layer| LayerScales {
+                input: quantize_matrix(&mut layer.input_weights),
+                intra: quantize_matrix(&mut layer.intra_weights),
+                skips: layer.skips.iter_mut().map(|(_, weights)| quantize_matrix(weights)).collect()
+            })
+            .collect()
+    }
+
+    /// Clamp every weight of the network to the range `min..=max`, to keep the weights bounded while they are learned
+    /// (e.g. through [plasticity](crate::plasticity)) or mutated.
+    /// 
+    /// Null weights are left untouched, since they represent missing synapses.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `min` is greater than `max`, or if either is NaN.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// nn.clamp_weights(-0.25, 1.6);
+    /// 
+    /// assert_eq!(nn.get_input_weight(1), Some(1.6));
+    /// assert_eq!(nn[0].get_intra_weight(0, 1), Some(-0.25));
+    /// assert_eq!(nn[0].get_intra_weight(1, 0), Some(-0.2));
+    /// assert_eq!(nn[0].get_intra_weight(0, 0), Some(0.0));
+    /// ```
+    pub fn clamp_weights(&mut self, min: f64, max: f64) {
+        assert!(min <= max, "invalid clamping range");
+
+        for layer in &mut self.layers {
+            for w in layer.input_weights.iter_mut()
+                .chain(layer.intra_weights.iter_mut())
+                .chain(layer.skips.iter_mut().flat_map(|(_, weights)| weights.iter_mut()))
+                .filter(|w| !w.is_zero())
+            {
+                *w = F::narrow(w.widen().clamp(min, max));
+            }
+        }
+    }
+
+    /// Rescale the incoming weights of every neuron, so that their absolute values add up to `target_sum` (L1 normalization),
+    /// which keeps the total drive of the neurons constant while their weights are learned.
+    /// 
+    /// The incoming weights of a neuron are those of its input synapses from the previous layer and of its skip connections,
+    /// i.e. its columns of the respective weight matrices; their signs and ratios are preserved. The entry layer and the intra-layer synapses,
+    /// which are not learned by the [plasticity](crate::plasticity) rules, are left untouched, as are the neurons without incoming synapses.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2);
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer([From::from(&config), From::from(&config)], [1.5, 1.8], [[0.0, -0.3], [-0.2, 0.0]])
+    ///     .layer([From::from(&config), From::from(&config)], [[3.0, 0.0], [-1.0, 0.0]], [[0.0, 0.0], [0.0, 0.0]])
+    ///     .build();
+    /// 
+    /// nn.normalize_incoming(2.0);
+    /// 
+    /// assert_eq!(nn.get_weight((0, 0), (1, 0)), Some(1.5));
+    /// assert_eq!(nn.get_weight((0, 1), (1, 0)), Some(-0.5));
+    /// assert_eq!(nn.get_weight((0, 0), (1, 1)), Some(0.0));
+    /// assert_eq!(nn.get_input_weight(0), Some(1.5));
+    /// ```
+    pub fn normalize_incoming(&mut self, target_sum: f64) {
+        for layer in self.layers.iter_mut().skip(1) {
+            for neuron_id in 0..layer.neurons.len() {
+                let mut columns = std::iter::once(&mut layer.input_weights)
+                    .chain(layer.skips.iter_mut().map(|(_, weights)| weights))
+                    .map(|weights| weights.column_mut(neuron_id))
+                    .collect::<Vec<_>>();
+
+                let sum = columns.iter().flat_map(|column| column.iter()).map(|w| w.widen().abs()).sum::<f64>();
+                if sum == 0.0 {
+                    continue;
+                }
+
+                for column in &mut columns {
+                    column.mapv_inplace(|w| F::narrow(w.widen() * target_sum / sum));
+                }
+            }
+        }
+    }
+
+    /// Create a copy of the network with randomly perturbed weights and neurons, e.g. to mutate the individuals of a genetic algorithm.
+    /// 
+    /// Gaussian noise with standard deviation `weight_sigma` is added to every weight, except the null ones which represent missing synapses,
+    /// and the parameters of every neuron are perturbed through [Model::perturb] with Gaussian noise of standard deviation `param_sigma`.
+    /// The same `rng` state always produces the same network, so a seeded `rng` makes the mutations reproducible.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// use rand::SeedableRng;
+    /// use rand_pcg::Pcg64Mcg;
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let mut rng = Pcg64Mcg::seed_from_u64(42);
+    /// let mutant = nn.perturbed(&mut rng, 0.1, 0.0);
+    /// 
+    /// assert_ne!(mutant.get_input_weight(0), nn.get_input_weight(0));
+    /// assert_eq!(mutant[0].get_intra_weight(0, 0), Some(0.0));
+    /// assert_eq!(mutant.get_neuron(0, 1).unwrap().v_threshold, 3.1);
+    /// ```
+    pub fn perturbed(&self, rng: &mut impl Rng, weight_sigma: f64, param_sigma: f64) -> NN<M, F> {
+        let mut res = self.clone();
+
+        for layer in &mut res.layers {
+            for w in layer.input_weights.iter_mut()
+                .chain(layer.intra_weights.iter_mut())
+                .chain(layer.skips.iter_mut().flat_map(|(_, weights)| weights.iter_mut()))
+                .filter(|w| !w.is_zero())
+            {
+                *w = F::narrow(w.widen() + weight_sigma * gaussian(rng));
+            }
+
+            for neuron in &mut layer.neurons {
+                M::perturb(neuron, || param_sigma * gaussian(rng));
+            }
+        }
+
+        res
+    }
+
+    /// Check that no layer has a cycle of excitatory intra-layer synapses.
+    /// 
+    /// Intra-layer synapses have no delay, so neurons exciting each other in a cycle could keep firing
+    /// forever at the same timestamp: such networks are rejected by [try_solve](NN::try_solve).
+    /// Inhibitory (negative) intra-layer synapses are always fine.
+    /// 
+    /// The check is conservative: a cycle is reported even if its weights are too weak to actually make the neurons fire again.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, SolveError, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, 0.3], [0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// assert_eq!(nn.check_zero_delay_cycles(), Err(SolveError::ZeroDelayCycle { layer: 0, neurons: vec![0, 1] }));
+    /// ```
+    pub fn check_zero_delay_cycles(&self) -> Result<(), SolveError> {
+        for (layer, l) in self.layers.iter().enumerate() {
+            if let Some(neurons) = l.excitatory_intra_cycle() {
+                return Err(SolveError::ZeroDelayCycle { layer, neurons });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the consistency of the network, which is guaranteed for networks built through [NNBuilder](crate::NNBuilder),
+    /// but not for the ones coming from untrusted sources (e.g. deserialized).
+    /// 
+    /// Every issue found is returned, in layer order:
+    ///  - the network must have at least one layer, and every layer at least one neuron;
+    ///  - the input weights of the entry layer must be a square diagonal matrix, with a row and a column for every neuron;
+    ///  - the input weights of the other layers must have a row for every neuron of the previous layer and a column for every neuron of theirs;
+    ///  - the intra weights must be a square matrix with a zero diagonal, since a neuron shouldn't synapse onto itself;
+    ///  - skip connections must come from an earlier layer, with a row for every neuron of that layer;
+    ///  - delays, if any, must have the same shape as their weights;
+    ///  - weights must all be finite;
+    ///  - the heads, if any, must add up to the size of the output layer.
+    /// 
+    /// Issues in the shape of a matrix make its weights skip the other checks.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, ValidationIssue, nn::Synapses, lif::*};
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// assert_eq!(nn.validate(), Ok(()));
+    /// 
+    /// *nn.get_weight_mut((0, 1), (0, 1)).unwrap() = 0.4;
+    /// *nn.get_weight_mut((0, 0), (0, 1)).unwrap() = f64::NAN;
+    /// assert_eq!(nn.validate(), Err(vec![
+    ///     ValidationIssue::NonFiniteWeight { layer: 0, synapses: Synapses::Intra, from: 0, to: 1 },
+    ///     ValidationIssue::SelfSynapse { layer: 0, neuron: 1 }
+    /// ]));
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ValidationIssue>> {
+        let mut issues = vec![];
+
+        if self.layers.is_empty() {
+            issues.push(ValidationIssue::NoLayers);
+        }
+
+        for (layer_id, layer) in self.layers.iter().enumerate() {
+            let n = layer.neurons.len();
+            if n == 0 {
+                issues.push(ValidationIssue::EmptyLayer { layer: layer_id });
+            }
+
+            let len_prev_layer = if layer_id == 0 { n } else { self.layers[layer_id - 1].neurons.len() };
+            let mut matrices = vec![(Synapses::Input, &layer.input_weights, (len_prev_layer, n)), (Synapses::Intra, &layer.intra_weights, (n, n))];
+            for (back, weights) in &layer.skips {
+                match layer_id.checked_sub(*back).filter(|_| *back > 0) {
+                    Some(from) => matrices.push((Synapses::Skip(*back), weights, (self.layers[from].neurons.len(), n))),
+                    None => issues.push(ValidationIssue::InvalidSkip { layer: layer_id, back: *back })
+                }
+            }
+
+            for (synapses, weights, expected) in matrices {
+                if weights.dim() != expected {
+                    issues.push(ValidationIssue::WeightsShape { layer: layer_id, synapses, expected, found: weights.dim() });
+                    continue;
+                }
+
+                for ((from, to), w) in weights.indexed_iter() {
+                    if !w.is_finite() {
+                        issues.push(ValidationIssue::NonFiniteWeight { layer: layer_id, synapses, from, to });
+                    } else if from == to && !w.is_zero() && synapses == Synapses::Intra {
+                        issues.push(ValidationIssue::SelfSynapse { layer: layer_id, neuron: from });
+                    } else if from != to && !w.is_zero() && synapses == Synapses::Input && layer_id == 0 {
+                        issues.push(ValidationIssue::OffDiagonalInput { from, to });
+                    }
+                }
+            }
+
+            for (synapses, delays, weights) in [(Synapses::Input, &layer.input_delays, &layer.input_weights), (Synapses::Intra, &layer.intra_delays, &layer.intra_weights)] {
+                if let Some(delays) = delays.as_ref().filter(|delays| delays.dim() != weights.dim()) {
+                    issues.push(ValidationIssue::DelaysShape { layer: layer_id, synapses, expected: weights.dim(), found: delays.dim() });
+                }
+            }
+        }
+
+        if let Some(last) = self.layers.last().filter(|_| !self.heads.is_empty()) {
+            if self.heads.iter().sum::<usize>() != last.neurons.len() {
+                issues.push(ValidationIssue::HeadsMismatch { heads: self.heads.clone(), found: last.neurons.len() });
+            }
+        }
+
+        if issues.is_empty() { Ok(()) } else { Err(issues) }
+    }
+
+    /// For every neuron of the output layer, list the neurons of the input layer that can influence it, in ascending order.
+    /// 
+    /// This is a static analysis of the graph of the synapses, through the input, the intra-layer and the skip connections ones:
+    /// synapses with a zero weight are considered absent, while the weights of the others are ignored.
+    /// Input neurons missing from every list can be pruned without affecting the output of the network.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer([From::from(&config), From::from(&config), From::from(&config)], [1.0, 1.0, 0.0], [[0.0; 3]; 3])
+    ///     .layer(
+    ///         [From::from(&config), From::from(&config)],
+    ///         [[1.0, 0.0], [0.0, 0.0], [1.0, 1.0]],
+    ///         [[0.0, -0.5], [0.0, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// // Input neuron 1 doesn't reach the output layer, and input neuron 2 doesn't even reach the first layer
+    /// assert_eq!(nn.reachability(), vec![vec![0], vec![0]]);
+    /// ```
+    pub fn reachability(&self) -> Vec<Vec<usize>> {
+        let num_inputs = self.layers[0].neurons.len();
+        // Inputs reaching every neuron of every layer so far
+        let mut reachable: Vec<Vec<Vec<bool>>> = vec![];
+
+        for (layer_id, layer) in self.layers.iter().enumerate() {
+            // Every matrix of synapses reaching this layer, along with the reachability of its source layer
+            let sources = (layer_id > 0).then(|| (&reachable[layer_id - 1], &layer.input_weights))
+                .into_iter()
+                .chain(layer.skips.iter().map(|(back, weights)| (&reachable[layer_id - back], weights)))
+                .collect::<Vec<_>>();
+
+            let mut current = (0..layer.neurons.len())
+                .map(|post| match layer_id {
+                    // The input weights of the first layer are diagonal
+                    0 => (0..num_inputs).map(|input| input == post && !layer.input_weights[(post, post)].is_zero()).collect(),
+                    _ => (0..num_inputs)
+                        .map(|input| sources.iter().any(|(source, weights)| source.iter()
+                            .enumerate()
+                            .any(|(pre, inputs)| inputs[input] && !weights[(pre, post)].is_zero())
+                        ))
+                        .collect()
+                })
+                .collect::<Vec<Vec<bool>>>();
+
+            // Propagate through the intra-layer synapses until nothing changes anymore
+            let mut changed = true;
+            while changed {
+                changed = false;
+
+                for ((pre, post), weight) in layer.intra_weights.indexed_iter() {
+                    if pre == post || weight.is_zero() {
+                        continue;
+                    }
+
+                    let from = current[pre].clone();
+                    for (to, from) in current[post].iter_mut().zip(from) {
+                        if from && !*to {
+                            *to = true;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+
+            reachable.push(current);
+        }
+
+        reachable.pop().unwrap().into_iter()
+            .map(|inputs| inputs.into_iter().enumerate().filter(|(_, reachable)| *reachable).map(|(input, _)| input).collect())
+            .collect()
+    }
+
+    /// Export the topology of the network as a [Graphviz](https://graphviz.org) DOT graph, to be rendered with `dot -Tpng`, for example.
+    ///
+    /// Every neuron is a node, named `l<layer>n<neuron>` and grouped with the others of its layer into a cluster subgraph;
+    /// the inputs of the network are the additional nodes `in<neuron>`.
+    /// The synapses from the inputs and between consecutive layers are solid edges, while the intra-layer ones are dashed
+    /// and the skip connections dotted, all labeled with their weights. Synapses with a zero weight are omitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [0.0, 0.0]]
+    ///     )
+    ///     .build();
+    ///
+    /// let dot = nn.to_dot();
+    ///
+    /// assert!(dot.starts_with("digraph nn {\n"));
+    /// assert!(dot.contains("    in1 -> l0n1 [label=\"1.8\"];\n"));
+    /// assert!(dot.contains("    l0n0 -> l0n1 [label=\"-0.3\", style=dashed];\n"));
+    /// assert!(!dot.contains("l0n1 -> l0n0"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph nn {\n    rankdir=LR;\n");
+
+        dot += "    subgraph cluster_input {\n        label=\"input\";\n";
+        for neuron in 0..self.layers[0].neurons.len() {
+            dot += &format!("        in{neuron};\n");
+        }
+        dot += "    }\n";
+
+        for (layer_id, layer) in self.layers.iter().enumerate() {
+            dot += &format!("    subgraph cluster_{layer_id} {{\n        label=\"layer {layer_id}\";\n");
+            for neuron in 0..layer.neurons.len() {
+                dot += &format!("        l{layer_id}n{neuron};\n");
+            }
+            dot += "    }\n";
+        }
+
+        for (layer_id, layer) in self.layers.iter().enumerate() {
+            for ((pre, post), weight) in layer.input_weights.indexed_iter() {
+                if weight.is_zero() {
+                    continue;
+                }
+
+                match layer_id {
+                    // The input weights of the first layer are diagonal
+                    0 if pre == post => dot += &format!("    in{pre} -> l0n{post} [label=\"{weight}\"];\n"),
+                    0 => (),
+                    _ => dot += &format!("    l{}n{pre} -> l{layer_id}n{post} [label=\"{weight}\"];\n", layer_id - 1)
+                }
+            }
+
+            for ((pre, post), weight) in layer.intra_weights.indexed_iter() {
+                if pre != post && !weight.is_zero() {
+                    dot += &format!("    l{layer_id}n{pre} -> l{layer_id}n{post} [label=\"{weight}\", style=dashed];\n");
+                }
+            }
+
+            for (back, weights) in &layer.skips {
+                for ((pre, post), weight) in weights.indexed_iter().filter(|(_, weight)| !weight.is_zero()) {
+                    dot += &format!("    l{}n{pre} -> l{layer_id}n{post} [label=\"{weight}\", style=dotted];\n", layer_id - back);
+                }
+            }
+        }
+
+        dot += "}\n";
+        dot
+    }
+
+    /// Returns an iterator over references of every layer
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let mut iterator = nn.iter();
+    /// assert!(iterator.next().is_some());
+    /// assert!(iterator.next().is_none());
+    /// ```
+    pub fn iter(&self) -> <&Vec<Layer<M, F>> as IntoIterator>::IntoIter {
+        self.into_iter()
+    }
+
+    /// Returns an iterator over mutable references of every layer
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, lif::*};
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let mut iterator = nn.iter_mut();
+    /// 
+    /// iterator.next().unwrap()[0].v_rest += 1.0;
+    /// assert!(iterator.next().is_none());
+    /// ```
+    pub fn iter_mut(&mut self) -> <&mut Vec<Layer<M, F>> as IntoIterator>::IntoIter {
+        self.into_iter()
+    }
+}
+
+impl<M: Model, F: Float> NN<M, F> where for<'a> &'a M::Neuron: Into<M::SolverVars> {
+    /// Solve the neural network stimulated by the provided spikes.
+    /// 
+    /// This function returns a list of every spike's timestamp generated by every neuron.
+    /// 
+    /// The dynamic state of the neurons only lives in the [SolverVars](Model::SolverVars) created for this call,
+    /// so every solve starts from the initial state of the network: the same [NN] can be stimulated by
+    /// independent trials without having to reset or rebuild it.
+    /// 
+    /// The neurons of a layer are updated simultaneously: the spikes they generate are applied to the layer through the intra-layer synapses
+    /// only once all of them have received the same input, so two neurons firing together never inhibit each other first, whatever their order.
+    /// Neurons exciting each other through intra-layer synapses fire each other at the same timestamp: if the weights
+    /// along such a cycle are strong enough, this never returns. Use [try_solve](NN::try_solve) to reject these networks.
+    /// 
+    /// The network is not only solved until the last input spike (see [time_span](Spike::time_span)): spikes still traveling through
+    /// delayed synapses are processed after the inputs are over, so the output can extend beyond the last input spike.
+    /// 
+    /// # Panics
+    /// 
+    /// If the model panics while solving a layer (e.g. in [handle_spike](Model::handle_spike)), the same panic is resumed here,
+    /// once every worker has stopped.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// assert_eq!(nn.solve(spikes), vec![vec![4], vec![3]]);
+    /// ```
+    #[cfg(not(feature = "async"))]
+    pub fn solve(&self, spikes: Vec<Spike>) -> Vec<Vec<u128>> {
+        self.solve_with_config(spikes, SolveConfig::default())
+    }
+
+    /// Solve the neural network stimulated by the provided spikes.
+    /// 
+    /// This function returns a list of every spike's timestamp generated by every neuron.
+    /// 
+    /// The dynamic state of the neurons only lives in the [SolverVars](Model::SolverVars) created for this call,
+    /// so every solve starts from the initial state of the network: the same [NN] can be stimulated by
+    /// independent trials without having to reset or rebuild it.
+    /// 
+    /// The neurons of a layer are updated simultaneously: the spikes they generate are applied to the layer through the intra-layer synapses
+    /// only once all of them have received the same input, so two neurons firing together never inhibit each other first, whatever their order.
+    /// Neurons exciting each other through intra-layer synapses fire each other at the same timestamp: if the weights
+    /// along such a cycle are strong enough, this never returns. Use [try_solve](NN::try_solve) to reject these networks.
+    /// 
+    /// The network is not only solved until the last input spike (see [time_span](Spike::time_span)): spikes still traveling through
+    /// delayed synapses are processed after the inputs are over, so the output can extend beyond the last input spike.
+    /// 
+    /// # Panics
+    /// 
+    /// If the model panics while solving a layer (e.g. in [handle_spike](Model::handle_spike)), the same panic is resumed here,
+    /// once every worker has stopped.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// # use tokio::runtime::Runtime;
+    /// # let runtime = Runtime::new().unwrap();
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// # runtime.block_on(async {
+    /// assert_eq!(nn.solve(spikes).await, vec![vec![4], vec![3]]);
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn solve(&self, spikes: Vec<Spike>) -> Vec<Vec<u128>> {
+        self.solve_with_config(spikes, SolveConfig::default()).await
+    }
+
+    /// Solve the neural network stimulated by the provided spikes like [solve](NN::solve),
+    /// also counting the spikes generated by the output layer.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// let result = nn.solve_with_stats(spikes);
+    /// assert_eq!(result.output, vec![vec![4], vec![3]]);
+    /// assert_eq!(result.spikes_per_neuron, vec![1, 1]);
+    /// assert_eq!(result.total_spikes, 2);
+    /// ```
+    #[cfg(not(feature = "async"))]
+    pub fn solve_with_stats(&self, spikes: Vec<Spike>) -> SolveResult {
+        self.solve(spikes).into()
+    }
+
+    /// Solve the neural network stimulated by the provided spikes like [solve](NN::solve),
+    /// also counting the spikes generated by the output layer.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// # use tokio::runtime::Runtime;
+    /// # let runtime = Runtime::new().unwrap();
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// # runtime.block_on(async {
+    /// let result = nn.solve_with_stats(spikes).await;
+    /// assert_eq!(result.output, vec![vec![4], vec![3]]);
+    /// assert_eq!(result.spikes_per_neuron, vec![1, 1]);
+    /// assert_eq!(result.total_spikes, 2);
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn solve_with_stats(&self, spikes: Vec<Spike>) -> SolveResult {
+        self.solve(spikes).await.into()
+    }
+
+    /// Solve the neural network stimulated by the provided spikes, like [solve](NN::solve),
+    /// after checking that the network has no cycles of excitatory zero-delay synapses (see [check_zero_delay_cycles](NN::check_zero_delay_cycles)).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, SolveError, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, 3.0], [3.0, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::spike_vec_for(0, vec![1, 3, 4]);
+    /// 
+    /// // The two neurons would keep firing each other at the same timestamp
+    /// assert!(matches!(nn.try_solve(spikes), Err(SolveError::ZeroDelayCycle { layer: 0, .. })));
+    /// ```
+    #[cfg(not(feature = "async"))]
+    pub fn try_solve(&self, spikes: Vec<Spike>) -> Result<Vec<Vec<u128>>, SolveError> {
+        self.check_zero_delay_cycles()?;
+
+        Ok(self.solve(spikes))
+    }
+
+    /// Solve the neural network stimulated by the provided spikes, like [solve](NN::solve),
+    /// after checking that the network has no cycles of excitatory zero-delay synapses (see [check_zero_delay_cycles](NN::check_zero_delay_cycles)).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, SolveError, lif::*};
+    /// # use tokio::runtime::Runtime;
+    /// # let runtime = Runtime::new().unwrap();
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, 3.0], [3.0, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::spike_vec_for(0, vec![1, 3, 4]);
+    /// 
+    /// // The two neurons would keep firing each other at the same timestamp
+    /// # runtime.block_on(async {
+    /// assert!(matches!(nn.try_solve(spikes).await, Err(SolveError::ZeroDelayCycle { layer: 0, .. })));
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn try_solve(&self, spikes: Vec<Spike>) -> Result<Vec<Vec<u128>>, SolveError> {
+        self.check_zero_delay_cycles()?;
+
+        Ok(self.solve(spikes).await)
+    }
+
+    /// Solve the neural network stimulated by the provided spikes, with the provided [SolveConfig].
+    /// 
+    /// The layers of the network are split in at most [max_threads](SolveConfig::max_threads) groups of consecutive layers,
+    /// each solved by its own thread, unless they're too small to be worth it (see [min_neurons_for_threading](SolveConfig::min_neurons_for_threading)).
+    /// The result is the same as [solve](NN::solve).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, SolveConfig, lif::*};
+    /// # use std::num::NonZeroUsize;
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// // Solve everything in a single worker thread
+    /// let config = SolveConfig { max_threads: NonZeroUsize::new(1).unwrap(), ..Default::default() };
+    /// assert_eq!(nn.solve_with_config(spikes.clone(), config), vec![vec![4], vec![3]]);
+    /// 
+    /// // Or directly on the calling thread, since the network is so small
+    /// let config = SolveConfig { min_neurons_for_threading: 100, ..Default::default() };
+    /// assert_eq!(nn.solve_with_config(spikes, config), vec![vec![4], vec![3]]);
+    /// ```
+    #[cfg(not(feature = "async"))]
+    pub fn solve_with_config(&self, spikes: Vec<Spike>, config: SolveConfig) -> Vec<Vec<u128>> {
+        use crate::sync::{GroupManager, group_layers};
+        use self::sequential::input_events;
+        use std::{mem::replace, thread, sync::mpsc::channel, panic::resume_unwind};
+
+        let max_groups = match config.max_groups(self.layers.iter().map(|layer| layer.neurons.len()).sum()) {
+            Some(max_groups) => max_groups,
+            None => return self.solve_on_current_thread(spikes)
+        };
+        
+        // These will be respectively the first group's sender and the last group's receiver
+        let (sender, mut receiver) = channel();
+
+        // Inject spikes into first layer
+        for event in input_events(self.layers[0].neurons.len(), spikes) {
+            sender.send(event).unwrap();
+        }
+
+        // Drop the first sender.
+        // This will cause a chain reaction that will ultimately lead to the last receiver being closed.
+        drop(sender);
+
+        // The threads are scoped, so they can borrow the layers: they are all joined before returning
+        thread::scope(|scope| {
+            let mut workers = vec![];
+            for group in group_layers(&self.layers, max_groups, config.min_neurons_for_threading) {
+                let layers = &self.layers[group];
+                let (group_sender, mut group_receiver) = channel();
+                group_receiver = replace(&mut receiver, group_receiver);
+                
+                workers.push(scope.spawn(move || {
+                    let mngr = GroupManager::<M, F>::new(
+                        layers,
+                        group_receiver,
+                        group_sender
+                    );
+
+                    mngr.run();
+                }));
+            }
+
+            // Read spikes from last layer and convert to proper format for output
+            let mut res = vec![vec![]; self.layers.last().unwrap().neurons.len()];
+            for (ts, spike) in receiver {
+                for (neuron_id, _) in spike.into_iter().enumerate().filter(|(_, v)| fired::<M>(*v)) {
+                    res[neuron_id].push(ts);
+                }
+            }
+
+            // A group whose thread panicked (e.g. in the model's `handle_spike`) closed its channels, making the others stop early:
+            // resume its own panic, rather than the generic one of the scope
+            for worker in workers {
+                if let Err(panic) = worker.join() {
+                    resume_unwind(panic);
+                }
+            }
+
+            res
+        })
+    }
+
+    /// Solve the neural network stimulated by the provided spikes, with the provided [SolveConfig].
+    /// 
+    /// The layers of the network are split in at most [max_threads](SolveConfig::max_threads) groups of consecutive layers,
+    /// each solved by its own task, unless they're too small to be worth it (see [min_neurons_for_threading](SolveConfig::min_neurons_for_threading)).
+    /// The result is the same as [solve](NN::solve).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, SolveConfig, lif::*};
+    /// # use std::num::NonZeroUsize;
+    /// # use tokio::runtime::Runtime;
+    /// # let runtime = Runtime::new().unwrap();
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// // Solve everything in a single task
+    /// let config = SolveConfig { max_threads: NonZeroUsize::new(1).unwrap(), ..Default::default() };
+    /// # runtime.block_on(async {
+    /// assert_eq!(nn.solve_with_config(spikes.clone(), config).await, vec![vec![4], vec![3]]);
+    /// # });
+    /// 
+    /// // Or directly on the calling task, since the network is so small
+    /// let config = SolveConfig { min_neurons_for_threading: 100, ..Default::default() };
+    /// # runtime.block_on(async {
+    /// assert_eq!(nn.solve_with_config(spikes, config).await, vec![vec![4], vec![3]]);
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn solve_with_config(&self, spikes: Vec<Spike>, config: SolveConfig) -> Vec<Vec<u128>> {
+        use crate::sync::{GroupManager, group_layers};
+        use self::sequential::input_events;
+        use std::{mem::replace, panic::resume_unwind, sync::Arc};
+        use tokio::{task, sync::mpsc::channel};
+
+        let max_groups = match config.max_groups(self.layers.iter().map(|layer| layer.neurons.len()).sum()) {
+            Some(max_groups) => max_groups,
+            None => return self.solve_on_current_thread(spikes)
+        };
+        
+        // These will be respectively the first group's sender and the last group's receiver
+        let (sender, mut receiver) = channel(10);
+
+        let num_inputs = self.layers[0].neurons.len();
+        
+        // Inject spikes into first layer
+        task::spawn(async move {
+            for event in input_events(num_inputs, spikes) {
+                // The first group is gone only if its task panicked, which is reported below
+                if sender.send(event).await.is_err() { return }
+            }
+
+            // Drop the first sender.
+            // This will cause a chain reaction that will ultimately lead to the last receiver being closed.
+            drop(sender);
+        });
+
+        // tokio has no scoped tasks, and dropping this future doesn't cancel the spawned ones:
+        // they own a copy of the layers, so that they can never outlive what they read
+        let layers: Arc<[Layer<M, F>]> = self.layers.clone().into();
+
+        let mut tasks = vec![];
+        for group in group_layers(&self.layers, max_groups, config.min_neurons_for_threading) {
+            let layers = Arc::clone(&layers);
+            let (group_sender, mut group_receiver) = channel(10);
+            group_receiver = replace(&mut receiver, group_receiver);
+
+            tasks.push(task::spawn(async move {
+                let mngr = GroupManager::<M, F>::new(
+                    &layers[group],
+                    group_receiver,
+                    group_sender
+                );
+
+                mngr.run().await
+            }));
+        }
+
+        // Read spikes from last layer and convert to proper format for output
+        let mut res = vec![vec![]; self.layers.last().unwrap().neurons.len()];
+        while let Some((ts, spike)) = receiver.recv().await {
+            for (neuron_id, _) in spike.iter().enumerate().filter(|(_, v)| fired::<M>(**v)) {
+                res[neuron_id].push(ts);
+            }
+        }
+
+        // A group whose task panicked (e.g. in the model's `handle_spike`) closed its channels, making the others stop early:
+        // resume its own panic, rather than a generic join error
+        for task in tasks {
+            if let Err(err) = task.await {
+                match err.try_into_panic() {
+                    Ok(panic) => resume_unwind(panic),
+                    Err(err) => panic!("{err}")
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Solve the neural network stimulated by the provided spikes on the calling thread, without spawning any thread or task.
+    /// 
+    /// The computation is the same as [solve](NN::solve), but it's fully deterministic: the same input always generates
+    /// bit-identical output, which makes this mode suited for reproducible experiments and tests.
+    /// 
+    /// This function returns the spikes generated by the output layer, sorted by timestamp like [create_terminal_vec](Spike::create_terminal_vec) does.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// assert_eq!(nn.solve_sequential(spikes), vec![Spike::new(3, 1), Spike::new(4, 0)]);
+    /// ```
+    pub fn solve_sequential(&self, spikes: Vec<Spike>) -> Vec<Spike> {
+        Spike::create_terminal_vec(
+            self.solve_on_current_thread(spikes)
+                .into_iter()
+                .enumerate()
+                .map(|(neuron_id, ts_vec)| Spike::spike_vec_for(neuron_id, ts_vec))
+                .collect()
+        )
+    }
+
+    /// Solve the neural network on the calling thread like [solve_sequential](NN::solve_sequential), stopping after the last input spike
+    /// so that the solve can be resumed later with a continuation of the input stream.
+    /// 
+    /// `state` is [None] to start a new solve, or the state returned by a previous call to resume it: the returned spikes
+    /// are only those generated by the output layer since then, and the input spikes must all come after [ts](SimulationState::ts).
+    /// Splitting the input stream in several calls yields the same spikes as a single [solve_sequential](NN::solve_sequential),
+    /// as long as spikes with the same timestamp are never split.
+    /// 
+    /// Spikes still traveling through delayed synapses are not applied beyond the last input spike: call this with no input spikes
+    /// to signal that the input stream is over, and to apply all of them.
+    /// 
+    /// `spikes` must be sorted like [create_terminal_vec](Spike::create_terminal_vec) does.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `state` comes from a network with different layers, or if any input spike is not later than [ts](SimulationState::ts).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let (first, state) = nn.solve_resumable(vec![Spike::new(1, 0), Spike::new(2, 1)], None);
+    /// assert_eq!(state.ts(), Some(2));
+    /// 
+    /// let (second, state) = nn.solve_resumable(Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![3, 4]),
+    ///     Spike::spike_vec_for(1, vec![3, 6])
+    /// ]), Some(state));
+    /// let (last, _) = nn.solve_resumable(vec![], Some(state));
+    /// 
+    /// assert!(first.is_empty() && last.is_empty());
+    /// assert_eq!(second, vec![Spike::new(3, 1), Spike::new(4, 0)]);
+    /// ```
+    pub fn solve_resumable(&self, spikes: Vec<Spike>, state: Option<SimulationState<M>>) -> (Vec<Spike>, SimulationState<M>) {
+        use self::sequential::{Pipeline, input_events, output_spikes};
+
+        let (mut pipeline, mut last_ts) = match state {
+            None => (Pipeline::new_observable(&self.layers), None),
+            Some(SimulationState { layers, ts }) => {
+                assert!(
+                    layers.len() == self.layers.len() && layers.iter().zip(&self.layers).all(|((vars, _), layer)| vars.len() == layer.neurons.len()),
+                    "the simulation state doesn't belong to this neural network"
+                );
+                if let (Some(first), Some(ts)) = (spikes.first(), ts) {
+                    assert!(first.ts > ts, "input spike at {} is not later than the simulation state ({})", first.ts, ts);
+                }
+
+                (Pipeline::resume(&self.layers, layers), ts)
+            }
+        };
+
+        let outputs = match spikes.last() {
+            Some(last) => {
+                last_ts = Some(last.ts);
+
+                input_events(self.layers[0].neurons.len(), spikes)
+                    .flat_map(|(ts, spike)| pipeline.feed(ts, spike, |_, _, _| ()))
+                    .collect()
+            },
+            // The input stream is over: the delayed spikes can be applied up to the last one
+            None => pipeline.finish_observed(|_, _, _| (), |_, _, ts, _, _| last_ts = last_ts.max(Some(ts)))
+        };
+
+        let output = output_spikes::<M>(outputs);
+
+        (Spike::create_terminal_vec(vec![output]), SimulationState { layers: pipeline.into_states(), ts: last_ts })
+    }
+
+    /// Solve the neural network on the calling thread like [solve_sequential](NN::solve_sequential), only updating the neurons that
+    /// actually receive an input.
+    ///
+    /// Every neuron keeps the timestamp of its last update in its [SolverVars](Model::SolverVars), and the models fast-forward its state
+    /// to the time of its next input (in closed form, for the [lif](crate::lif) model): the neurons that don't receive any input are thus
+    /// skipped altogether, instead of being updated with a zero input. The cost of the solve then scales with the number of
+    /// spikes and synapses they go through, rather than with the number of neurons, which pays off for large networks with sparse activity.
+    ///
+    /// The output is the same as [solve_sequential](NN::solve_sequential) for every model ignoring zero inputs (i.e. leaving the neuron
+    /// untouched and returning 0), like every built-in model does. With the `simd` feature, the neurons are never aggregated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    ///
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    ///
+    /// assert_eq!(nn.solve_event_driven(spikes), vec![Spike::new(3, 1), Spike::new(4, 0)]);
+    /// ```
+    pub fn solve_event_driven(&self, spikes: Vec<Spike>) -> Vec<Spike> {
+        use self::sequential::{Pipeline, input_events, output_spikes};
+
+        let mut pipeline = Pipeline::new_event_driven(&self.layers);
+        let outputs = input_events(self.layers[0].neurons.len(), spikes)
+            .flat_map(|(ts, spike)| pipeline.feed(ts, spike, |_, _, _| ()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .chain(pipeline.finish(|_, _, _| ()));
+
+        output_spikes::<M>(outputs)
+    }
+
+    /// Solve the neural network independently for every input of a batch, in parallel.
+    /// 
+    /// Every input is solved on a single thread like [solve_sequential](NN::solve_sequential), and the inputs are distributed
+    /// among as many threads as the [available parallelism](std::thread::available_parallelism) of the system:
+    /// this scales much better than the per-layer-group parallelism of [solve](NN::solve) when there are many small inputs, like the samples of a dataset.
+    /// 
+    /// The output for every input is the same as [solve_sequential](NN::solve_sequential) would return, in the same order as the batch,
+    /// regardless of how the inputs are scheduled among the threads.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let batches = vec![
+    ///     Spike::create_terminal_vec(vec![
+    ///         Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///         Spike::spike_vec_for(1, vec![2, 3, 6])
+    ///     ]),
+    ///     Spike::spike_vec_for(0, vec![1, 5])
+    /// ];
+    /// 
+    /// assert_eq!(nn.solve_batch(batches), vec![vec![Spike::new(3, 1), Spike::new(4, 0)], vec![]]);
+    /// ```
+    pub fn solve_batch(&self, batches: Vec<Vec<Spike>>) -> Vec<Vec<Spike>> {
+        use std::sync::{Mutex, atomic::{AtomicUsize, Ordering}};
+
+        let threads = std::thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .min(batches.len());
+
+        // Every thread takes the next unsolved input, so that long inputs don't leave the other threads idle
+        let inputs = batches.into_iter().map(|spikes| Mutex::new(Some(spikes))).collect::<Vec<_>>();
+        let next = AtomicUsize::new(0);
+
+        let mut outputs = std::thread::scope(|scope| {
+            let workers = (0..threads).map(|_| scope.spawn(|| {
+                let mut outputs = Vec::new();
+
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(input) = inputs.get(index) else { break outputs };
+
+                    let spikes = input.lock().unwrap().take().unwrap();
+                    outputs.push((index, self.solve_sequential(spikes)));
+                }
+            })).collect::<Vec<_>>();
+
+            workers.into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                .collect::<Vec<_>>()
+        });
+
+        outputs.sort_unstable_by_key(|(index, _)| *index);
+        outputs.into_iter().map(|(_, output)| output).collect()
+    }
+
+    /// Solve the neural network stimulated by the provided spikes like [solve_sequential](NN::solve_sequential),
+    /// on tokio's pool of blocking threads, so that the calling task can await the output without blocking its runtime.
+    /// 
+    /// The solve works on its own copy of the network, so dropping this future never leaves it reading a freed one
+    /// (the copy is solved to completion in the background, though). The output is the same as [solve_sequential](NN::solve_sequential).
+    /// 
+    /// # Panics
+    /// 
+    /// If the model panics while solving, the same panic is resumed here.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// # use tokio::runtime::Runtime;
+    /// # let runtime = Runtime::new().unwrap();
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// # runtime.block_on(async {
+    /// assert_eq!(nn.solve_async(spikes.clone()).await, nn.solve_sequential(spikes));
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn solve_async(&self, spikes: Vec<Spike>) -> Vec<Spike> {
+        let nn = self.clone();
+
+        match tokio::task::spawn_blocking(move || nn.solve_sequential(spikes)).await {
+            Ok(output) => output,
+            Err(err) => match err.try_into_panic() {
+                Ok(panic) => std::panic::resume_unwind(panic),
+                Err(err) => panic!("{err}")
+            }
+        }
+    }
+
+    /// Solve the neural network stimulated by the provided spikes on the calling thread, like [solve_sequential](NN::solve_sequential),
+    /// lazily yielding the spikes generated by the output layer as they're produced.
+    /// 
+    /// The network is only driven forward when the next output spike is requested, so the output is never buffered as a whole:
+    /// this keeps the memory usage bounded even for long simulations generating millions of spikes.
+    /// The spikes are yielded in the same order as [solve_sequential](NN::solve_sequential).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// let mut output = nn.solve_streaming(spikes);
+    /// 
+    /// assert_eq!(output.next(), Some(Spike::new(3, 1)));
+    /// assert_eq!(output.next(), Some(Spike::new(4, 0)));
+    /// assert_eq!(output.next(), None);
+    /// ```
+    pub fn solve_streaming(&self, spikes: Vec<Spike>) -> impl Iterator<Item = Spike> + '_ {
+        use self::sequential::{Pipeline, input_events, output_spikes};
+
+        let mut pipeline = Pipeline::new(&self.layers);
+        let mut events = input_events(self.layers[0].neurons.len(), spikes);
+        let mut buffer = VecDeque::new();
+        let mut finished = false;
+
+        std::iter::from_fn(move || loop {
+            if let Some(spike) = buffer.pop_front() {
+                return Some(spike);
+            }
+            if finished {
+                return None;
+            }
+
+            // The outputs of every input event come after those of the previous ones
+            let outputs = match events.next() {
+                Some((ts, spike)) => pipeline.feed(ts, spike, |_, _, _| ()),
+                None => {
+                    finished = true;
+                    pipeline.finish(|_, _, _| ())
+                }
+            };
+
+            buffer.extend(output_spikes::<M>(outputs));
+        })
+    }
+
+    /// Solve the neural network stimulated by the provided spikes on the calling thread, like [solve_sequential](NN::solve_sequential),
+    /// splitting the output among its heads (see [NNBuilder::head](crate::NNBuilder::head)).
+    /// 
+    /// Returns the spikes generated by every head, in order, each with the ids of the neurons relative to its own head.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer([From::from(&config), From::from(&config)], [1.0, 1.0], [[0.0, 0.0], [0.0, 0.0]])
+    ///     // Each head listens to a different neuron of the backbone
+    ///     .head([From::from(&config)], [[1.0], [0.0]], [[0.0]])
+    ///     .head([From::from(&config)], [[0.0], [1.0]], [[0.0]])
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3]),
+    ///     Spike::spike_vec_for(1, vec![2])
+    /// ]);
+    /// 
+    /// assert_eq!(
+    ///     nn.solve_multihead(spikes),
+    ///     vec![
+    ///         vec![Spike::new(1, 0), Spike::new(3, 0)],
+    ///         vec![Spike::new(2, 0)]
+    ///     ]
+    /// );
+    /// ```
+    pub fn solve_multihead(&self, spikes: Vec<Spike>) -> Vec<Vec<Spike>> {
+        let output_len = self.layers.last().unwrap().neurons.len();
+        let heads = if self.heads.is_empty() { vec![output_len] } else { self.heads.clone() };
+
+        // Start of every head in the output layer
+        let mut offsets = vec![0];
+        for size in &heads {
+            offsets.push(offsets.last().unwrap() + size);
+        }
+
+        let mut res = vec![vec![]; heads.len()];
+        for mut spike in self.solve_sequential(spikes) {
+            let head = offsets.partition_point(|offset| *offset <= spike.neuron_id) - 1;
+            spike.neuron_id -= offsets[head];
+            res[head].push(spike);
+        }
+
+        res
+    }
+
+    /// Solve the neural network on the calling thread, through the same pipeline of layers used by [solve](NN::solve).
+    pub(crate) fn solve_on_current_thread(&self, spikes: Vec<Spike>) -> Vec<Vec<u128>> {
+        use self::sequential::{Pipeline, input_events};
+
+        let mut pipeline = Pipeline::new(&self.layers);
+        let mut res = vec![vec![]; self.layers.last().unwrap().neurons.len()];
+
+        let outputs = input_events(self.layers[0].neurons.len(), spikes)
+            .flat_map(|(ts, spike)| pipeline.feed(ts, spike, |_, _, _| ()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .chain(pipeline.finish(|_, _, _| ()));
+
+        for (ts, output) in outputs {
+            for (neuron_id, _) in output.iter().enumerate().filter(|(_, v)| fired::<M>(**v)) {
+                res[neuron_id].push(ts);
+            }
+        }
+
+        res
+    }
+
+    /// Solve the neural network on the calling thread like [solve_sequential](NN::solve_sequential), after priming the layer with index `layer`
+    /// with the `context` spikes.
+    /// 
+    /// The context spikes are fed to the layer through its input synapses, as if they were generated by the neurons of the previous layer
+    /// (or as if they were input spikes, for the first layer): they set up the state of the layer, and of the following ones,
+    /// before the input spikes are processed. The returned spikes include those generated by the output layer because of the context.
+    /// Skip connections from the layers before `layer` to the following ones are ignored.
+    /// 
+    /// Both `context` and `spikes` must be sorted like [create_terminal_vec](Spike::create_terminal_vec) does.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `layer` is not a valid layer index, or if any context spike comes after the first input spike.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer([From::from(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))], [1.0], [[0.0]])
+    ///     .layer([From::from(&LifNeuronConfig::new(0.0, 0.0, 1.5, 100.0))], [[1.0]], [[0.0]])
+    ///     .build();
+    /// 
+    /// let spikes = Spike::spike_vec_for(0, vec![5]);
+    /// 
+    /// // A single input spike is not enough to make the second layer fire, unless it has been primed before
+    /// assert!(nn.solve_sequential(spikes.clone()).is_empty());
+    /// assert_eq!(nn.solve_with_context(&[Spike::new(1, 0)], 1, &spikes), vec![Spike::new(5, 0)]);
+    /// ```
+    pub fn solve_with_context(&self, context: &[Spike], layer: usize, spikes: &[Spike]) -> Vec<Spike> {
+        use self::sequential::{Pipeline, input_events, output_spikes};
+
+        assert!(layer < self.layers.len(), "the layer index is out of bounds");
+        let start = spikes.first().map_or(u128::MAX, |spike| spike.ts);
+        assert!(context.iter().all(|spike| spike.ts <= start), "the context must come before the input spikes");
+
+        // The upstream pipeline is empty when priming the first layer, in which case it forwards the input events as they are
+        let (upstream, downstream) = self.layers.split_at(layer);
+        let context_width = upstream.last().unwrap_or(&self.layers[0]).neurons.len();
+        let mut upstream = Pipeline::new(upstream);
+        let mut downstream = Pipeline::new(downstream);
+
+        let mut outputs = vec![];
+        for (ts, spike) in input_events(context_width, context.iter().copied()) {
+            outputs.extend(downstream.feed(ts, spike, |_, _, _| ()));
+        }
+
+        let forwarded = input_events(self.layers[0].neurons.len(), spikes.iter().copied())
+            .flat_map(|(ts, spike)| upstream.feed(ts, spike, |_, _, _| ()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .chain(upstream.finish(|_, _, _| ()));
+        for (ts, spike) in forwarded {
+            outputs.extend(downstream.feed(ts, spike, |_, _, _| ()));
+        }
+        outputs.extend(downstream.finish(|_, _, _| ()));
+
+        output_spikes::<M>(outputs)
+    }
+
+    /// Solve the neural network on the calling thread like [solve_sequential](NN::solve_sequential), while also injecting currents
+    /// into the neurons of the first layer.
+    ///
+    /// Every `(neuron_id, ts, current)` of `currents` is added to the input of the neuron `neuron_id` of the first layer at time `ts`,
+    /// bypassing its input weight (and delay): it's summed to the weighted input of any spike reaching the same neuron at the same time.
+    /// Sweeping a constant current injected at regular intervals, for example, traces the f-I curve of the neurons.
+    ///
+    /// Neither `spikes` nor `currents` need to be sorted, and the currents injected into the same neuron at the same time are summed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id of the neuron of any spike or current is out of bounds for the first layer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer([From::from(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))], [0.06], [[0.0]])
+    ///     .build();
+    ///
+    /// // Neither the spike nor the current alone is enough to reach the threshold, but together they are
+    /// assert_eq!(nn.solve_with_current(Spike::spike_vec_for(0, vec![5]), vec![(0, 1, 0.06)]), vec![]);
+    /// assert_eq!(nn.solve_with_current(Spike::spike_vec_for(0, vec![5]), vec![(0, 5, 0.06)]), vec![Spike::new(5, 0)]);
+    /// ```
+    pub fn solve_with_current(&self, spikes: Vec<Spike>, currents: Vec<(usize, u128, f64)>) -> Vec<Spike> {
+        use self::sequential::{Pipeline, output_spikes};
+
+        let num_inputs = self.layers[0].neurons.len();
+        let zeros = || Array2::zeros((1, num_inputs));
+
+        // Input spikes and injected currents, grouped by timestamp
+        let mut events = BTreeMap::new();
+        for Spike { ts, neuron_id, polarity } in spikes {
+            assert!(neuron_id < num_inputs, "the neuron id of a spike is out of bounds");
+            events.entry(ts).or_insert_with(|| (zeros(), zeros())).0[(0, neuron_id)] = polarity as f64;
+        }
+        for (neuron_id, ts, current) in currents {
+            assert!(neuron_id < num_inputs, "the neuron id of a current is out of bounds");
+            events.entry(ts).or_insert_with(|| (zeros(), zeros())).1[(0, neuron_id)] += current;
+        }
+
+        let mut pipeline = Pipeline::new(&self.layers);
+        let outputs = events.into_iter()
+            .flat_map(|(ts, (spike, current))| pipeline.feed_with_current(ts, spike, &current, |_, _, _| ()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .chain(pipeline.finish(|_, _, _| ()));
+
+        output_spikes::<M>(outputs)
+    }
+
+    /// Solve the neural network on the calling thread like [solve_sequential](NN::solve_sequential), but only up to `t_max` (included).
+    ///
+    /// Input spikes later than `t_max` are ignored, and so is everything that would happen after it: spikes still traveling through
+    /// delayed synapses at `t_max` are dropped, while the intra-layer cascades happening at `t_max` itself are still solved.
+    /// The output is thus the prefix of the output of [solve_sequential](NN::solve_sequential) up to `t_max`.
+    ///
+    /// The input spikes must be sorted like [create_terminal_vec](Spike::create_terminal_vec) does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    ///
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    ///
+    /// assert_eq!(nn.solve_until(spikes.clone(), 2), vec![]);
+    /// assert_eq!(nn.solve_until(spikes, 3), vec![Spike::new(3, 1)]);
+    /// ```
+    pub fn solve_until(&self, spikes: Vec<Spike>, t_max: u128) -> Vec<Spike> {
+        use self::sequential::{Pipeline, input_events, output_spikes};
+
+        let mut pipeline = Pipeline::new(&self.layers);
+        let inputs = spikes.into_iter().take_while(|spike| spike.ts <= t_max);
+        let outputs = input_events(self.layers[0].neurons.len(), inputs)
+            .flat_map(|(ts, spike)| pipeline.feed(ts, spike, |_, _, _| ()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .chain(pipeline.flush(t_max, |_, _, _| ()));
+
+        output_spikes::<M>(outputs)
+    }
+
+    /// Solve the neural network like [solve_sequential](NN::solve_sequential), and then apply a k-winners-take-all readout to its output:
+    /// only the first `k` distinct output neurons to fire are kept, and every spike of the others is dropped.
+    ///
+    /// Neurons firing first at the same timestamp are ranked by their id, lowest first. Fewer than `k` neurons are kept if fewer fire.
+    ///
+    /// This is only a post-processing of the output: the losers are not actually inhibited during the solve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    ///
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    ///
+    /// assert_eq!(nn.solve_wta(spikes.clone(), 1), vec![Spike::new(3, 1)]);
+    /// assert_eq!(nn.solve_wta(spikes, 2), vec![Spike::new(3, 1), Spike::new(4, 0)]);
+    /// ```
+    pub fn solve_wta(&self, spikes: Vec<Spike>, k: usize) -> Vec<Spike> {
+        let output = self.solve_sequential(spikes);
+
+        // The output is sorted by timestamp first, and then by neuron id
+        let mut winners = vec![];
+        for spike in &output {
+            if winners.len() == k {
+                break;
+            }
+            if !winners.contains(&spike.neuron_id) {
+                winners.push(spike.neuron_id);
+            }
+        }
+
+        output.into_iter().filter(|spike| winners.contains(&spike.neuron_id)).collect()
+    }
+
+    /// Solve the neural network like [solve_sequential](NN::solve_sequential), also counting the synaptic operations (SynOps) performed,
+    /// a common measure of the computational cost of a spiking neural network.
+    ///
+    /// A synaptic operation is counted every time a spike crosses a synapse with a non-zero weight: every input spike crosses the input synapse
+    /// of its neuron, and every spike of a neuron crosses its synapses to the same layer, to the next one and to the targets of its
+    /// [skip connections](crate::NNBuilder::skip_connection). The spikes of the output layer only cross its intra-layer synapses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    ///
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    ///
+    /// // 6 input spikes, and 2 output spikes crossing one intra-layer synapse each
+    /// assert_eq!(nn.solve_counting_ops(spikes), (vec![Spike::new(3, 1), Spike::new(4, 0)], 8));
+    /// ```
+    pub fn solve_counting_ops(&self, spikes: Vec<Spike>) -> (Vec<Spike>, usize) {
+        use self::sequential::{Pipeline, input_events, output_spikes};
+
+        let count_nonzero = |weights: ArrayView1<'_, F>| weights.iter().filter(|w| **w != F::zero()).count();
+
+        // Number of non-zero outgoing synapses of every neuron of the network
+        let fanout = self.layers.iter().enumerate().map(|(layer_id, layer)| (0..layer.neurons.len()).map(|neuron_id| {
+            let mut res = count_nonzero(layer.intra_weights.row(neuron_id));
+            if let Some(next) = self.layers.get(layer_id + 1) {
+                res += count_nonzero(next.input_weights.row(neuron_id));
+            }
+            for (to, later) in self.layers.iter().enumerate().skip(layer_id + 1) {
+                for (_, weights) in later.skips.iter().filter(|(back, _)| to - back == layer_id) {
+                    res += count_nonzero(weights.row(neuron_id));
+                }
+            }
+
+            res
+        }).collect::<Vec<_>>()).collect::<Vec<_>>();
+
+        let input_weights = self.input_weights();
+        let mut ops = spikes.iter().filter(|spike| input_weights[spike.neuron_id] != F::zero()).count();
+        let mut count = |layer_id: usize, _, output: &Array2<f64>| {
+            ops += output.iter().enumerate().filter(|(_, v)| fired::<M>(**v)).map(|(neuron_id, _)| fanout[layer_id][neuron_id]).sum::<usize>();
+        };
+
+        let mut pipeline = Pipeline::new(&self.layers);
+        let mut outputs = vec![];
+        for (ts, spike) in input_events(self.layers[0].neurons.len(), spikes) {
+            outputs.extend(pipeline.feed(ts, spike, &mut count));
+        }
+        outputs.extend(pipeline.finish(&mut count));
+
+        (output_spikes::<M>(outputs), ops)
+    }
+
+    /// Find the first spike generated by the output layer when the network is stimulated by the provided spikes,
+    /// or [None] if the output layer never fires.
+    /// 
+    /// If more than one output neuron fires at the earliest timestamp, the one with the lowest id is returned.
+    /// 
+    /// The network is solved on the calling thread, and the solve stops as soon as the first output spike is generated,
+    /// so this is usually much cheaper than a full [solve](NN::solve).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// assert_eq!(nn.first_output_spike(spikes), Some(Spike::new(3, 1)));
+    /// ```
+    pub fn first_output_spike(&self, spikes: Vec<Spike>) -> Option<Spike> {
+        use self::sequential::{Pipeline, input_events};
+
+        let output_layer = self.layers.len() - 1;
+        let mut pipeline = Pipeline::new(&self.layers);
+        let mut first = None;
+
+        let first_of = |layer_id, ts, output: &Array2<f64>| if layer_id == output_layer {
+            output.iter()
+                .position(|v| fired::<M>(*v))
+                .map(|neuron_id| Spike::new(ts, neuron_id))
+        } else {
+            None
+        };
+
+        for (ts, spike) in input_events(self.layers[0].neurons.len(), spikes) {
+            pipeline.feed(ts, spike, |layer_id, ts, output| first = first.or_else(|| first_of(layer_id, ts, output)));
+
+            if first.is_some() {
+                return first;
+            }
+        }
+        pipeline.finish(|layer_id, ts, output| first = first.or_else(|| first_of(layer_id, ts, output)));
+
+        first
+    }
+
+    /// Trace the chain of spikes that led to the first spike generated by the output layer (see [first_output_spike](NN::first_output_spike)),
+    /// from the input spike to the output one.
+    /// 
+    /// The chain is built backwards from the output spike: among the spikes that reached a neuron right when it fired
+    /// (through either its input or its intra-layer synapses, considering their delays), the one with the largest weighted contribution
+    /// is taken as the cause of its spike. The neuron ids of the returned spikes are relative to their own layer, the first spike being
+    /// an input spike: as the causes are always generated before their effects, the timestamps of the path never decrease.
+    /// 
+    /// Returns an empty [Vec] if the output layer never fires.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer([From::from(&config), From::from(&config)], [1.0, 1.0], [[0.0, 0.0], [0.0, 0.0]])
+    ///     .layer([From::from(&config)], [[1.0], [1.0]], [[0.0]])
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![3]),
+    ///     Spike::spike_vec_for(1, vec![2])
+    /// ]);
+    /// 
+    /// assert_eq!(nn.critical_path(spikes), vec![Spike::new(2, 1), Spike::new(2, 1), Spike::new(2, 0)]);
+    /// ```
+    pub fn critical_path(&self, spikes: Vec<Spike>) -> Vec<Spike> {
+        use self::sequential::{Pipeline, input_events};
+
+        /// Timestamp, order of generation and value of a spike
+        type Recorded = (u128, usize, f64);
+
+        // Spikes of every neuron, with the input spikes first
+        let mut activity = std::iter::once(self.layers[0].neurons.len())
+            .chain(self.layers.iter().map(|layer| layer.neurons.len()))
+            .map(|len| vec![Vec::<Recorded>::new(); len])
+            .collect::<Vec<_>>();
+        let mut order = 0;
+        let record = |activity: &mut [Vec<Vec<Recorded>>], order: &mut usize, layer_id: usize, ts, output: &Array2<f64>| {
+            for (neuron_id, value) in output.iter().enumerate().filter(|(_, v)| **v != 0.0 && (layer_id == 0 || fired::<M>(**v))) {
+                activity[layer_id][neuron_id].push((ts, *order, *value));
+                *order += 1;
+            }
+        };
+
+        let mut pipeline = Pipeline::new(&self.layers);
+        for (ts, spike) in input_events(self.layers[0].neurons.len(), spikes) {
+            record(&mut activity, &mut order, 0, ts, &spike);
+            pipeline.feed(ts, spike, |layer_id, ts, output| record(&mut activity, &mut order, layer_id + 1, ts, output));
+        }
+        pipeline.finish(|layer_id, ts, output| record(&mut activity, &mut order, layer_id + 1, ts, output));
+
+        let Some((mut neuron_id, &(mut ts, mut order, mut value))) = activity.last().unwrap()
+            .iter()
+            .enumerate()
+            .filter_map(|(neuron_id, spikes)| spikes.first().map(|spike| (neuron_id, spike)))
+            .min_by_key(|(neuron_id, (ts, _, _))| (*ts, *neuron_id))
+        else {
+            return vec![];
+        };
+
+        // The latest spike of `spikes` reaching a neuron at `ts` through a synapse with the given delay, generated before `order`
+        let arriving = |spikes: &[Recorded], delay: u128, ts: u128, order: usize| ts.checked_sub(delay)
+            .and_then(|sent| spikes.iter().rev().find(|(spike_ts, spike_order, _)| *spike_ts == sent && *spike_order < order).copied());
+
+        let mut path = vec![];
+        let mut layer_id = self.layers.len();
+        loop {
+            if layer_id == 0 {
+                path.push(Spike::with_polarity(ts, neuron_id, value as i8));
+                break;
+            }
+            path.push(Spike::new(ts, neuron_id));
+
+            let layer = &self.layers[layer_id - 1];
+            let inputs = activity[layer_id - 1].iter()
+                .enumerate()
+                .map(|(pre, spikes)| (layer_id - 1, pre, spikes, layer.input_weights[(pre, neuron_id)], layer.input_delays.as_ref().map_or(0, |delays| delays[(pre, neuron_id)])));
+            let intra = activity[layer_id].iter()
+                .enumerate()
+                .filter(|(pre, _)| *pre != neuron_id)
+                .map(|(pre, spikes)| (layer_id, pre, spikes, layer.intra_weights[(pre, neuron_id)], layer.intra_delays.as_ref().map_or(0, |delays| delays[(pre, neuron_id)])));
+
+            let mut cause: Option<(f64, usize, usize, Recorded)> = None;
+            for (pre_layer, pre, spikes, weight, delay) in inputs.chain(intra).filter(|(_, _, _, weight, _)| !weight.is_zero()) {
+                if let Some(spike) = arriving(spikes, delay, ts, order) {
+                    let contribution = weight.widen() * spike.2;
+                    if cause.is_none_or(|(best, _, _, _)| contribution > best) {
+                        cause = Some((contribution, pre_layer, pre, spike));
+                    }
+                }
+            }
+
+            match cause {
+                Some((_, pre_layer, pre, spike)) => {
+                    (layer_id, neuron_id) = (pre_layer, pre);
+                    (ts, order, value) = spike;
+                },
+                None => break
+            }
+        }
+
+        path.reverse();
+        path
+    }
+
+    /// Compute the response latency of the network for every input of a dataset.
+    /// 
+    /// The latency of an input is the timestamp of the first spike generated by the output layer (see [first_output_spike](NN::first_output_spike)),
+    /// or [None] if the network stays silent for that input.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let dataset = vec![
+    ///     Spike::create_terminal_vec(vec![
+    ///         Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///         Spike::spike_vec_for(1, vec![2, 3, 6])
+    ///     ]),
+    ///     Spike::spike_vec_for(0, vec![1, 5]),
+    ///     Spike::spike_vec_for(0, vec![7, 8, 9])
+    /// ];
+    /// 
+    /// assert_eq!(nn.latency_distribution(&dataset), vec![Some(3), None, Some(8)]);
+    /// ```
+    pub fn latency_distribution(&self, dataset: &[Vec<Spike>]) -> Vec<Option<u128>> {
+        dataset.iter()
+            .map(|spikes| self.first_output_spike(spikes.clone()).map(|spike| spike.ts))
+            .collect()
+    }
+
+    /// Classify the provided spikes, returning the output neuron that fired the most along with the confidence margin of this choice.
+    /// 
+    /// The first `n_classes` neurons of the output layer stand for the classes. The margin is the difference between the spike counts
+    /// of the winner and of the runner-up, normalized by the count of the winner: it's 1.0 for a clear winner, and tends to 0.0 for a tie.
+    /// In case of a tie, the winner is the neuron with the lowest index, with a margin of 0.0, which is also the case if no neuron fires at all.
+    /// 
+    /// The network is solved on the calling thread, like [solve_sequential](NN::solve_sequential).
     /// 
-    /// An unchecked variant of this functionality is provided via the [IndexMut] implementation.
+    /// # Panics
+    /// 
+    /// Panics if `n_classes` is 0 or greater than the number of neurons in the output layer.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
     ///     .layer(
     ///         [
     ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
@@ -410,35 +3434,51 @@ impl<M: Model> NN<M> {
     ///     )
     ///     .build();
     /// 
-    /// // Modify a weight
-    /// *nn.get_weight_mut((0, 1), (0, 0)).unwrap() = -0.5;
+    /// let spikes = Spike::spike_vec_for(0, vec![1, 2, 3, 4, 5, 6]);
     /// 
-    /// assert_eq!(nn[0][(1, 0)], -0.5);
+    /// assert_eq!(nn.classify_with_margin(spikes, 2), (0, 1.0));
     /// ```
-    pub fn get_weight_mut(&mut self, from: (usize, usize), to: (usize, usize)) -> Option<&mut f64> {
-        if from.0 == to.0 {
-            // Intra-layer weight
-            self.get_layer_mut(from.0)?.intra_weights.get_mut((from.1, to.1))
-        } else if from.0 + 1 == to.0 {
-            // Inter-layer weight
-            self.get_layer_mut(to.0)?.input_weights.get_mut((from.1, to.1))
-        } else {
-            None
+    pub fn classify_with_margin(&self, spikes: Vec<Spike>, n_classes: usize) -> (usize, f64) {
+        assert!(n_classes > 0, "there must be at least one class");
+        assert!(n_classes <= self.layers.last().unwrap().neurons.len(), "there can't be more classes than output neurons");
+
+        let output = self.solve_on_current_thread(spikes);
+        let counts = output[..n_classes].iter().map(Vec::len).collect::<Vec<_>>();
+
+        let mut winner = 0;
+        for (class, &count) in counts.iter().enumerate() {
+            if count > counts[winner] {
+                winner = class;
+            }
         }
+
+        let runner_up = counts.iter()
+            .enumerate()
+            .filter(|&(class, _)| class != winner)
+            .map(|(_, &count)| count)
+            .max()
+            .unwrap_or(0);
+
+        let margin = match counts[winner] {
+            0 => 0.0,
+            count => (count - runner_up) as f64 / count as f64
+        };
+
+        (winner, margin)
     }
 
-    /// Extend this`[NN] in place by appending the other provided network to it.
-    /// 
-    /// The two neural networks are merged via the provided new input weights, which will replace `other`'s.
+    /// Solve the neural network stimulated by the provided spikes, writing a chronological trace of every spike
+    /// flowing through it to `w`.
     /// 
-    /// In case of errors, `self` will be preserved.
+    /// The trace is in CSV format, with a `ts,layer,neuron` header followed by one record for every spike:
+    /// input spikes are reported with `input` as their layer, while spikes generated by the network carry the index of their layer.
+    /// The records of the last layer correspond to the output of [solve](NN::solve).
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
-    /// let mut nn1 = NNBuilder::<LeakyIntegrateFire, _>::new()
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
     ///     .layer(
     ///         [
     ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
@@ -449,64 +3489,152 @@ impl<M: Model> NN<M> {
     ///     )
     ///     .build();
     /// 
-    /// let nn2 = NNBuilder::<LeakyIntegrateFire, _>::new()
-    ///     .layer(
-    ///         [LifNeuron::new(&LifNeuronConfig::new(0.9, 0.5, 2.8, 1.4))],
-    ///         [1.3],
-    ///         [[0.0]]
-    ///     )
-    ///     .build();
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
     /// 
-    /// // Extend nn1 by concatenating nn2 to it in place
-    /// assert!(nn1.extend(&nn2, [1.3, 1.4]).is_ok());
-    /// assert_eq!(nn1.num_layers(), 2);
-    /// assert_eq!(nn1[((0, 1), (1, 0))], 1.4);
+    /// let mut trace = vec![];
+    /// nn.solve_to_trace_file(spikes, &mut trace).unwrap();
+    /// 
+    /// assert!(String::from_utf8(trace).unwrap().starts_with("ts,layer,neuron\n1,input,0\n2,input,1\n"));
     /// ```
-    pub fn extend(&mut self, other: &Self, intra_nn_weights: impl Borrow<[f64]>) -> Result<(), NNConcatError> {
-        let new_input_weights = Array2::from_shape_vec(
-            (self.layers.last().unwrap().num_neurons(), other.layers[0].num_neurons()),
-            intra_nn_weights.borrow().to_vec()
-        ).map_err(|_| NNConcatError::InvalidWeightsLen)?;
+    pub fn solve_to_trace_file<W: Write>(&self, spikes: Vec<Spike>, mut w: W) -> io::Result<()> {
+        use self::sequential::{Pipeline, input_events};
+
+        let mut pipeline = Pipeline::new(&self.layers);
+        writeln!(w, "ts,layer,neuron")?;
+
+        // The first error is kept, and stops any further write
+        let mut res = Ok(());
+        let mut write_output = |w: &mut W, layer_id, ts, output: &Array2<f64>| {
+            for (neuron_id, _) in output.iter().enumerate().filter(|(_, v)| fired::<M>(**v)) {
+                if res.is_ok() {
+                    res = writeln!(w, "{},{},{}", ts, layer_id, neuron_id);
+                }
+            }
+        };
 
-        let old_len = self.num_layers();
-        self.layers.extend_from_slice(&other.layers[..]);
-        self.layers[old_len].input_weights = new_input_weights;
+        for (ts, spike) in input_events(self.layers[0].neurons.len(), spikes) {
+            // Spikes still traveling through delayed synapses may be generated before this input
+            if ts > 0 {
+                pipeline.flush(ts - 1, |layer_id, ts, output| write_output(&mut w, layer_id, ts, output));
+            }
 
-        Ok(())
+            for (neuron_id, _) in spike.iter().enumerate().filter(|(_, v)| **v != 0.0) {
+                writeln!(w, "{},input,{}", ts, neuron_id)?;
+            }
+
+            pipeline.feed(ts, spike, |layer_id, ts, output| write_output(&mut w, layer_id, ts, output));
+        }
+        pipeline.finish(|layer_id, ts, output| write_output(&mut w, layer_id, ts, output));
+        res?;
+
+        w.flush()
     }
 
-    /// Concatenate this [NN] with another one, to obtain a new [NN].
+    /// Estimate the effective connectivity of the network when stimulated by the provided spikes, that is how much
+    /// the firing of each neuron is functionally driven by each of its presynaptic neurons, regardless of the weights of their synapses.
     /// 
-    /// The two neural networks are merged via the provided new input weights, which will replace `other`'s.
+    /// Neurons are indexed in layer order: the neurons of the first layer come first, then the ones of the second layer and so on.
+    /// The entry in position `(pre, post)` of the returned square matrix is the fraction of the spikes of neuron `post` that were generated
+    /// exactly when a spike of neuron `pre` reached it (accounting for synaptic [delays](crate::NNBuilder::layer_with_delays)).
+    /// Only `pre` neurons of the same layer as `post` or of the previous one are considered; every other entry is zero,
+    /// as are the columns of the neurons that never fire.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// // Create a sample nn
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    /// 
     /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
     ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///         [From::from(&config), From::from(&config)],
+    ///         [1.0, 1.0],
+    ///         [[0.0, 0.0], [0.0, 0.0]]
+    ///     )
+    ///     .layer(
+    ///         [From::from(&config)],
+    ///         [[1.0], [1.0]],
+    ///         [[0.0]]
     ///     )
     ///     .build();
     /// 
-    /// // Create a new NN by concatenating nn to itself
-    /// let new_nn = nn.concat(&nn, [1.2, 1.4, 1.5, 1.2]).unwrap();
+    /// // Only the first neuron of the first layer ever fires
+    /// let connectivity = nn.effective_connectivity(Spike::spike_vec_for(0, vec![1, 4, 6]));
     /// 
-    /// assert_eq!(new_nn.num_layers(), 2);
-    /// assert_eq!(new_nn[((0, 0), (1, 1))], 1.4);
+    /// assert_eq!(connectivity[(0, 2)], 1.0);
+    /// assert_eq!(connectivity[(1, 2)], 0.0);
     /// ```
-    pub fn concat(&self, other: &Self, intra_nn_weights: impl Borrow<[f64]>) -> Result<Self, NNConcatError> {
-        let mut new_nn = self.clone();
-        new_nn.extend(other, intra_nn_weights).map(|_| new_nn)
+    pub fn effective_connectivity(&self, spikes: Vec<Spike>) -> Array2<f64> {
+        use self::sequential::{Pipeline, input_events};
+
+        // Record the timestamps of the spikes of every neuron of the network
+        let mut activity = self.layers.iter().map(|layer| vec![vec![]; layer.neurons.len()]).collect::<Vec<_>>();
+        let mut pipeline = Pipeline::new(&self.layers);
+        let mut record = |layer_id: usize, ts, output: &Array2<f64>| {
+            for (neuron_id, _) in output.iter().enumerate().filter(|(_, v)| fired::<M>(**v)) {
+                activity[layer_id][neuron_id].push(ts);
+            }
+        };
+
+        for (ts, spike) in input_events(self.layers[0].neurons.len(), spikes) {
+            pipeline.feed(ts, spike, &mut record);
+        }
+        pipeline.finish(&mut record);
+
+        let offsets = self.layers.iter()
+            .scan(0, |offset, layer| {
+                let res = *offset;
+                *offset += layer.neurons.len();
+                Some(res)
+            })
+            .collect::<Vec<_>>();
+        let num_neurons = self.layers.iter().map(|layer| layer.neurons.len()).sum();
+
+        // Spikes are recorded in chronological order, so they can be binary searched
+        let coincidences = |pre_spikes: &[u128], post_spikes: &[u128], delay: u128| {
+            let count = post_spikes.iter()
+                .filter(|ts| ts.checked_sub(delay).is_some_and(|ts| pre_spikes.binary_search(&ts).is_ok()))
+                .count();
+
+            count as f64 / post_spikes.len() as f64
+        };
+
+        let mut res = Array2::zeros((num_neurons, num_neurons));
+        for (layer_id, layer) in self.layers.iter().enumerate() {
+            for (post, post_spikes) in activity[layer_id].iter().enumerate().filter(|(_, spikes)| !spikes.is_empty()) {
+                if layer_id > 0 {
+                    for (pre, pre_spikes) in activity[layer_id - 1].iter().enumerate() {
+                        let delay = layer.input_delays.as_ref().map_or(0, |delays| delays[(pre, post)]);
+                        res[(offsets[layer_id - 1] + pre, offsets[layer_id] + post)] = coincidences(pre_spikes, post_spikes, delay);
+                    }
+                }
+
+                for (pre, pre_spikes) in activity[layer_id].iter().enumerate().filter(|(pre, _)| *pre != post) {
+                    let delay = layer.intra_delays.as_ref().map_or(0, |delays| delays[(pre, post)]);
+                    res[(offsets[layer_id] + pre, offsets[layer_id] + post)] = coincidences(pre_spikes, post_spikes, delay);
+                }
+            }
+        }
+
+        res
     }
 
-    /// Returns an iterator over references of every layer
+    /// Estimate the dynamic range of the input channel `input_neuron`, returning the smallest input level at which the
+    /// firing rate of the output layer saturates.
+    /// 
+    /// The channel is driven by a constant input: it fires at every timestamp in `0..1000`, with its [input weight](NN::get_input_weight)
+    /// replaced by the input level. Levels are swept from zero to `max_input` in increments of `step`, and the returned one is the first
+    /// whose output rate (total number of output spikes per unit of time) is the highest of the whole sweep,
+    /// so that no further input up to `max_input` raises the output rate.
+    /// 
+    /// The network is solved on the calling thread, once for every level.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `input_neuron` is not a valid input channel, or if `step` is not positive.
     /// 
     /// # Examples
     /// 
@@ -514,54 +3642,140 @@ impl<M: Model> NN<M> {
     /// # use pds_spiking_nn::{NNBuilder, lif::*};
     /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
     ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///         [From::from(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2))],
+    ///         [1.5],
+    ///         [[0.0]]
     ///     )
     ///     .build();
     /// 
-    /// let mut iterator = nn.iter();
-    /// assert!(iterator.next().is_some());
-    /// assert!(iterator.next().is_none());
+    /// // From 2.3 onwards, the neuron fires on every input
+    /// let level = nn.saturation_threshold(0, 5.0, 0.1);
+    /// 
+    /// assert!((level - 2.3).abs() < 1e-9);
     /// ```
-    pub fn iter(&self) -> <&Vec<Layer<M>> as IntoIterator>::IntoIter {
-        self.into_iter()
+    pub fn saturation_threshold(&self, input_neuron: usize, max_input: f64, step: f64) -> f64 {
+        /// Duration of the constant input of every level
+        const DURATION: u128 = 1000;
+
+        assert!(input_neuron < self.layers[0].neurons.len(), "invalid input neuron");
+        assert!(step > 0.0, "the step must be positive");
+
+        let mut nn = self.clone();
+        let stimulus = Spike::spike_vec_for(input_neuron, (0..DURATION).collect());
+
+        let mut best = (0.0, 0.0);
+        // The tolerance avoids skipping max_input itself because of rounding errors
+        for i in 0..=((max_input / step) + 1e-9).floor() as usize {
+            let level = i as f64 * step;
+            *nn.get_input_weight_mut(input_neuron).unwrap() = F::narrow(level);
+
+            let rate = nn.solve_on_current_thread(stimulus.clone())
+                .iter()
+                .map(Vec::len)
+                .sum::<usize>() as f64 / DURATION as f64;
+
+            if i == 0 || rate > best.1 {
+                best = (level, rate);
+            }
+        }
+
+        best.0
     }
 
-    /// Returns an iterator over mutable references of every layer
+    /// Find the largest gain (a multiplier applied to the weights of every synapse of the network) for which the network
+    /// stimulated by the provided spikes stays stable, up to `tolerance`.
+    /// 
+    /// The network is considered unstable when its output firing rate, i.e. the average number of spikes generated by every output neuron
+    /// for each timestamp of the input spikes, reaches 1: the output layer then fires as often as it's stimulated (or more,
+    /// when intra-layer synapses make it fire multiple times at the same timestamp).
+    /// As stability is assumed to be lost for good once the gain is high enough, the critical gain is found by a binary search;
+    /// [f64::INFINITY] is returned if the network is still stable with a gain of 2^32 (and so if there are no input spikes).
+    /// 
+    /// The network is solved on the calling thread, once for every step of the search.
+    /// A network with a cycle of excitatory zero-delay synapses (see [check_zero_delay_cycles](NN::check_zero_delay_cycles)) is never solved,
+    /// since its neurons could keep exciting each other forever once its weights are scaled up: it's considered unstable at any gain, and 0 is returned.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `tolerance` is not positive.
     /// 
     /// # Examples
     /// 
     /// ```
-    /// # use pds_spiking_nn::{NNBuilder, lif::*};
-    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
     ///     .layer(
-    ///         [
-    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
-    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
-    ///         ],
-    ///         [1.5, 1.8],
-    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///         [From::from(&LifNeuronConfig::new(0.0, 0.0, 1.0, 1.0))],
+    ///         [0.5],
+    ///         [[0.0]]
     ///     )
     ///     .build();
     /// 
-    /// let mut iterator = nn.iter_mut();
+    /// // With a gain of 2 (or higher), every input spike is enough to make the neuron fire on its own
+    /// let gain = nn.critical_gain(Spike::spike_vec_for(0, (0..100).collect()), 1e-3);
     /// 
-    /// iterator.next().unwrap()[0].v_rest += 1.0;
-    /// assert!(iterator.next().is_none());
+    /// assert!((gain - 2.0).abs() < 1e-3);
     /// ```
-    pub fn iter_mut(&mut self) -> <&mut Vec<Layer<M>> as IntoIterator>::IntoIter {
-        self.into_iter()
+    pub fn critical_gain(&self, spikes: Vec<Spike>, tolerance: f64) -> f64 {
+        /// Highest gain tried before giving up on finding an unstable network
+        const MAX_GAIN: f64 = (1u64 << 32) as f64;
+
+        assert!(tolerance > 0.0, "the tolerance must be positive");
+
+        let mut timestamps = spikes.iter().map(|spike| spike.ts).collect::<Vec<_>>();
+        timestamps.sort_unstable();
+        timestamps.dedup();
+        let events = (timestamps.len() * self.layers.last().unwrap().neurons.len()) as f64;
+
+        let is_stable = |gain: f64| {
+            let mut nn = self.clone();
+            for layer in nn.layers.iter_mut() {
+                layer.input_weights.mapv_inplace(|w| F::narrow(w.widen() * gain));
+                layer.intra_weights.mapv_inplace(|w| F::narrow(w.widen() * gain));
+                for (_, weights) in &mut layer.skips {
+                    weights.mapv_inplace(|w| F::narrow(w.widen() * gain));
+                }
+            }
+
+            // A runaway cascade of intra-layer spikes would make the solve never return
+            if nn.check_zero_delay_cycles().is_err() {
+                return false;
+            }
+
+            let output_spikes = nn.solve_on_current_thread(spikes.clone()).iter().map(Vec::len).sum::<usize>();
+            output_spikes == 0 || (output_spikes as f64) < events
+        };
+
+        // Find an unstable gain first
+        let (mut stable, mut unstable) = (0.0, 1.0);
+        while is_stable(unstable) {
+            if unstable >= MAX_GAIN {
+                return f64::INFINITY;
+            }
+            (stable, unstable) = (unstable, unstable * 2.0);
+        }
+
+        while unstable - stable > tolerance {
+            let gain = (stable + unstable) / 2.0;
+            if is_stable(gain) {
+                stable = gain;
+            } else {
+                unstable = gain;
+            }
+        }
+
+        stable
     }
-}
 
-impl<M: Model> NN<M> where for<'a> &'a M::Neuron: Into<M::SolverVars> {
-    /// Solve the neural network stimulated by the provided spikes.
+    /// Solve the neural network on the calling thread like [solve_sequential](NN::solve_sequential), with every neuron silenced with probability `p`.
     /// 
-    /// This function returns a list of every spike's timestamp generated by every neuron.
+    /// Silenced neurons, drawn independently for every layer, never fire for the whole duration of the solve, mimicking neural dropout:
+    /// this helps assessing how much the output of the network depends on any single neuron.
+    /// The same `seed` always silences the same neurons, and thus produces the same output.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `p` is not in `[0, 1]`.
     /// 
     /// # Examples
     /// 
@@ -583,72 +3797,110 @@ impl<M: Model> NN<M> where for<'a> &'a M::Neuron: Into<M::SolverVars> {
     ///     Spike::spike_vec_for(1, vec![2, 3, 6])
     /// ]);
     /// 
-    /// assert_eq!(nn.solve(spikes), vec![vec![4], vec![3]]);
+    /// assert_eq!(nn.solve_with_dropout(spikes.clone(), 0.0, 42), nn.solve_sequential(spikes.clone()));
+    /// assert!(nn.solve_with_dropout(spikes, 1.0, 42).is_empty());
     /// ```
-    #[cfg(not(feature = "async"))]
-    pub fn solve(&self, spikes: Vec<Spike>) -> Vec<Vec<u128>> {
-        use crate::sync::LayerManager;
-        use std::{mem::{transmute, replace}, thread, sync::mpsc::channel};
-        
-        // These will be respectively the first layer's sender and the last layer's receiver
-        let (sender, mut receiver) = channel();
-
-        // Inject spikes into first layer
-        {
-            let mut spike_iterator = spikes.into_iter().peekable();
-            while let Some(Spike {ts, neuron_id}) = spike_iterator.next() {
-                let mut to_send = Array2::zeros((1, self.layers[0].neurons.len()));
-                to_send[(0, neuron_id)] = 1.0; // Should we validate neuron_ids?
-
-                while let Some(Spike {neuron_id, ..}) = spike_iterator.next_if(|s| s.ts == ts) {
-                    to_send[(0, neuron_id)] = 1.0;
+    pub fn solve_with_dropout(&self, spikes: Vec<Spike>, p: f64, seed: u64) -> Vec<Spike> {
+        assert!((0.0..=1.0).contains(&p), "the dropout probability must be in [0, 1]");
+
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+        let mut nn = self.clone();
+
+        // A neuron receiving no input never fires
+        for layer in nn.layers.iter_mut() {
+            for neuron in 0..layer.neurons.len() {
+                if rng.gen_bool(p) {
+                    layer.input_weights.column_mut(neuron).fill(F::zero());
+                    layer.intra_weights.column_mut(neuron).fill(F::zero());
+                    for (_, weights) in &mut layer.skips {
+                        weights.column_mut(neuron).fill(F::zero());
+                    }
                 }
-
-                sender.send((ts, to_send)).unwrap();
             }
         }
 
-        // Drop the first sender.
-        // This will cause a chain reaction that will ultimately lead to the last receiver being closed.
-        drop(sender);
+        nn.solve_sequential(spikes)
+    }
 
-        for layer in &self.layers {
-            let layer = unsafe { transmute::<_, &_>(layer) };
-            let (layer_sender, mut layer_receiver) = channel();
-            layer_receiver = replace(&mut receiver, layer_receiver);
-            
-            thread::spawn(move || {
-                let mngr = LayerManager::<M>::new(
-                    layer,
-                    layer_receiver,
-                    layer_sender
-                );
+    /// Find the input neurons that are individually necessary for the network to generate `output_spike` (from its output layer)
+    /// when stimulated by `spikes`.
+    /// 
+    /// All the spikes of each input neuron are removed in turn, and the network is solved again: an input neuron is necessary
+    /// if `output_spike` (as in, its timestamp and neuron) is then no longer generated.
+    /// Input neurons without spikes are never necessary, and if the network doesn't generate `output_spike` in the first place
+    /// no input is reported.
+    /// 
+    /// Returns the sorted ids of the necessary input neurons.
+    /// The network is solved on the calling thread, once for every input neuron with some spikes.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// // Fires on every input spike
+    /// let passthrough = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    /// 
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [From::from(&passthrough), From::from(&passthrough), From::from(&passthrough)],
+    ///         [1.0, 1.0, 1.0],
+    ///         [[0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]]
+    ///     )
+    ///     .layer(
+    ///         [From::from(&LifNeuronConfig::new(0.0, 0.0, 1.5, 1.0))],
+    ///         [[1.0], [1.0], [0.5]],
+    ///         [[0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![3]),
+    ///     Spike::spike_vec_for(1, vec![3]),
+    ///     Spike::spike_vec_for(2, vec![3])
+    /// ]);
+    /// 
+    /// // The neuron fires without the third input, but not without any of the other two
+    /// assert_eq!(nn.necessary_inputs(spikes, Spike::new(3, 0)), vec![0, 1]);
+    /// ```
+    pub fn necessary_inputs(&self, spikes: Vec<Spike>, output_spike: Spike) -> Vec<usize> {
+        let generates = |spikes: Vec<Spike>| self.solve_on_current_thread(spikes)
+            .get(output_spike.neuron_id)
+            .is_some_and(|output| output.contains(&output_spike.ts));
 
-                mngr.run();
-            });
+        if !generates(spikes.clone()) {
+            return vec![];
         }
 
-        // Read spikes from last layer and convert to proper format for output
-        let mut res = vec![vec![]; self.layers.last().unwrap().neurons.len()];
-        for (ts, spike) in receiver {
-            for (neuron_id, _) in spike.into_iter().enumerate().filter(|(_, v)| *v > 0.5) {
-                res[neuron_id].push(ts);
-            }
-        }
+        let mut inputs = spikes.iter().map(|spike| spike.neuron_id).collect::<Vec<_>>();
+        inputs.sort_unstable();
+        inputs.dedup();
 
-        res
+        inputs.into_iter()
+            .filter(|input| !generates(spikes.iter().filter(|spike| spike.neuron_id != *input).cloned().collect()))
+            .collect()
     }
+}
 
-    /// Solve the neural network stimulated by the provided spikes.
+impl<M: Model> NN<M> where for<'a> &'a M::Neuron: Into<M::SolverVars> {
+    /// Solve the neural network with the reference solver, the simple single-threaded implementation the other solvers
+    /// are checked against, returning the spikes generated by the output layer like [solve_sequential](NN::solve_sequential).
     /// 
-    /// This function returns a list of every spike's timestamp generated by every neuron.
+    /// The reference solver follows the same semantics as [solve](NN::solve), which is the canonical one: in particular,
+    /// the neurons of a layer are updated simultaneously, before any intra-layer feedback is applied.
+    /// Its output is thus the same as the other solvers', which can be cross-validated against it, e.g. when implementing a custom [Model]
+    /// or changing the solvers themselves.
+    /// It's much slower, though, so it's not meant for anything else.
+    /// 
+    /// The input spikes must be sorted like [create_terminal_vec](Spike::create_terminal_vec) does.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if any synapse of the network is delayed, or if the network has skip connections, as neither is supported by the reference solver.
     /// 
     /// # Examples
     /// 
     /// ```
     /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
-    /// # use tokio::runtime::Runtime;
-    /// # let runtime = Runtime::new().unwrap();
     /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
     ///     .layer(
     ///         [
@@ -665,84 +3917,44 @@ impl<M: Model> NN<M> where for<'a> &'a M::Neuron: Into<M::SolverVars> {
     ///     Spike::spike_vec_for(1, vec![2, 3, 6])
     /// ]);
     /// 
-    /// # runtime.block_on(async {
-    /// assert_eq!(nn.solve(spikes).await, vec![vec![4], vec![3]]);
-    /// # });
+    /// assert_eq!(nn.solve_reference(spikes.clone()), nn.solve_sequential(spikes));
     /// ```
-    #[cfg(feature = "async")]
-    pub async fn solve(&self, spikes: Vec<Spike>) -> Vec<Vec<u128>> {
-        use crate::sync::LayerManager;
-        use std::mem::{transmute, replace};
-        use tokio::{task, sync::mpsc::channel};
-        
-        // These will be respectively the first layer's sender and the last layer's receiver
-        let (sender, mut receiver) = channel(10);
-
-        let s = unsafe {transmute::<_, &Self>(self)};
-        
-        // Inject spikes into first layer
-        task::spawn(async move {
-            let mut spike_iterator = spikes.into_iter().peekable();
-            
-            while let Some(Spike {ts, neuron_id}) = spike_iterator.next() {
-                let mut to_send = Array2::zeros((1, s.layers[0].neurons.len()));
-                to_send[(0, neuron_id)] = 1.0; // Should we validate neuron_ids?
-
-                while let Some(Spike {neuron_id, ..}) = spike_iterator.next_if(|s| s.ts == ts) {
-                    to_send[(0, neuron_id)] = 1.0;
-                }
-
-                sender.send((ts, to_send)).await.unwrap();
-            }
-
-            // Drop the first sender.
-            // This will cause a chain reaction that will ultimately lead to the last receiver being closed.
-            drop(sender);
-        });
-
-        for layer in &self.layers {
-            let layer = unsafe { transmute::<_, &Layer<M>>(layer) };
-            let (layer_sender, mut layer_receiver) = channel(10);
-            layer_receiver = replace(&mut receiver, layer_receiver);
-
-            task::spawn(async move {
-                let mngr = LayerManager::<M>::new(
-                    layer,
-                    layer_receiver,
-                    layer_sender,
-                );
-
-                mngr.run().await
-            });
-        }
-
-        // Read spikes from last layer and convert to proper format for output
-        let mut res = vec![vec![]; self.layers.last().unwrap().neurons.len()];
-        while let Some((ts, spike)) = receiver.recv().await {
-            for (neuron_id, _) in spike.iter().enumerate().filter(|(_, v)| **v > 0.5) {
-                res[neuron_id].push(ts);
-            }
-        }
-
-        res
+    pub fn solve_reference(&self, spikes: Vec<Spike>) -> Vec<Spike> {
+        assert!(
+            self.layers.iter().all(|layer| layer.input_delays.is_none() && layer.intra_delays.is_none()),
+            "the reference solver doesn't support delayed synapses"
+        );
+        assert!(
+            self.layers.iter().all(|layer| layer.skips.is_empty()),
+            "the reference solver doesn't support skip connections"
+        );
+
+        Spike::create_terminal_vec(
+            solver_v1::Solver::new(spikes, self.clone())
+                .solve()
+                .into_iter()
+                .enumerate()
+                .map(|(neuron_id, ts_vec)| Spike::spike_vec_for(neuron_id, ts_vec))
+                .collect()
+        )
     }
 }
 
-impl<M: Model> Index<usize> for NN<M> {
-    type Output = Layer<M>;
+impl<M: Model, F: Float> Index<usize> for NN<M, F> {
+    type Output = Layer<M, F>;
 
     fn index(&self, index: usize) -> &Self::Output {
         &self.layers[index]
     }
 }
 
-impl<M: Model> IndexMut<usize> for NN<M> {
+impl<M: Model, F: Float> IndexMut<usize> for NN<M, F> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.layers[index]
     }
 }
 
-impl<M: Model> Index<(usize, usize)> for NN<M> {
+impl<M: Model, F: Float> Index<(usize, usize)> for NN<M, F> {
     type Output = M::Neuron;
 
     fn index(&self, index: (usize, usize)) -> &Self::Output {
@@ -750,14 +3962,14 @@ impl<M: Model> Index<(usize, usize)> for NN<M> {
     }
 }
 
-impl<M: Model> IndexMut<(usize, usize)> for NN<M> {
+impl<M: Model, F: Float> IndexMut<(usize, usize)> for NN<M, F> {
     fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
         &mut self.layers[index.0].neurons[index.1]
     }
 }
 
-impl<M: Model> Index<((usize, usize), (usize, usize))> for NN<M> {
-    type Output = f64;
+impl<M: Model, F: Float> Index<((usize, usize), (usize, usize))> for NN<M, F> {
+    type Output = F;
 
     fn index(&self, index: ((usize, usize), (usize, usize))) -> &Self::Output {
         if index.0.0 == index.1.0 {
@@ -772,7 +3984,7 @@ impl<M: Model> Index<((usize, usize), (usize, usize))> for NN<M> {
     }
 }
 
-impl<M: Model> IndexMut<((usize, usize), (usize, usize))> for NN<M> {
+impl<M: Model, F: Float> IndexMut<((usize, usize), (usize, usize))> for NN<M, F> {
     fn index_mut(&mut self, index: ((usize, usize), (usize, usize))) -> &mut Self::Output {
         if index.0.0 == index.1.0 {
             // Get intra-layer weight
@@ -786,27 +3998,83 @@ impl<M: Model> IndexMut<((usize, usize), (usize, usize))> for NN<M> {
     }
 }
 
-impl<M: Model> IntoIterator for NN<M> {
-    type Item = Layer<M>;
-    type IntoIter = <Vec<Layer<M>> as IntoIterator>::IntoIter;
+/// Summary of the structure of the network: the number of layers, their sizes and the range of the (non-zero) weights of the synapses.
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use pds_spiking_nn::{NNBuilder, lif::*};
+/// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+///     .layer(
+///         [
+///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+///         ],
+///         [1.5, 1.8],
+///         [[0.0, -0.3], [-0.2, 0.0]]
+///     )
+///     .build();
+/// 
+/// assert_eq!(nn.to_string(), "NN: 1 layers [2] neurons, -0.3..1.8 weight range");
+/// ```
+impl<M: Model, F: Float> fmt::Display for NN<M, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sizes = self.layers.iter().map(|layer| layer.neurons.len()).collect::<Vec<_>>();
+        write!(f, "NN: {} layers {:?} neurons", self.layers.len(), sizes)?;
+
+        if self.heads.len() > 1 {
+            write!(f, " (output split in {} heads {:?})", self.heads.len(), self.heads)?;
+        }
+
+        let range = self.layers.iter()
+            .flat_map(|layer| layer.input_weights.iter()
+                .chain(layer.intra_weights.iter())
+                .chain(layer.skips.iter().flat_map(|(_, weights)| weights.iter()))
+            )
+            .filter(|weight| !weight.is_zero())
+            .fold(None, |range: Option<(F, F)>, &weight| Some(match range {
+                Some((min, max)) => (min.min(weight), max.max(weight)),
+                None => (weight, weight)
+            }));
+
+        match range {
+            Some((min, max)) => write!(f, ", {}..{} weight range", min, max),
+            None => write!(f, ", no synapses")
+        }
+    }
+}
+
+/// Dump of the whole network, including the neurons and the weight (and delay) matrices of every layer.
+impl<M: Model, F: Float> fmt::Debug for NN<M, F> where M::Neuron: fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NN")
+            .field("layers", &self.layers)
+            .field("heads", &self.heads)
+            .finish()
+    }
+}
+
+impl<M: Model, F: Float> IntoIterator for NN<M, F> {
+    type Item = Layer<M, F>;
+    type IntoIter = <Vec<Layer<M, F>> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.layers.into_iter()
     }
 }
 
-impl<'a, M: Model> IntoIterator for &'a NN<M> {
-    type Item = &'a Layer<M>;
-    type IntoIter = <&'a Vec<Layer<M>> as IntoIterator>::IntoIter;
+impl<'a, M: Model, F: Float> IntoIterator for &'a NN<M, F> {
+    type Item = &'a Layer<M, F>;
+    type IntoIter = <&'a Vec<Layer<M, F>> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.layers.iter()
     }
 }
 
-impl<'a, M: Model> IntoIterator for &'a mut NN<M> {
-    type Item = &'a mut Layer<M>;
-    type IntoIter = <&'a mut Vec<Layer<M>> as IntoIterator>::IntoIter;
+impl<'a, M: Model, F: Float> IntoIterator for &'a mut NN<M, F> {
+    type Item = &'a mut Layer<M, F>;
+    type IntoIter = <&'a mut Vec<Layer<M, F>> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.layers.iter_mut()
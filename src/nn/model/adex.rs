@@ -0,0 +1,322 @@
+//! Implementation of the Adaptive Exponential Integrate and Fire (AdEx) model for spiking neural networks
+//!
+//! Unlike [lif](crate::lif) neurons, AdEx neurons have a sharp exponential spike initiation and an adaptation current,
+//! which lets them reproduce spike-frequency adaptation, bursting and the other firing patterns of real neurons.
+//!
+//! The membrane potential `v` and the adaptation current `w` of a neuron evolve as
+//!
+//! ```text
+//! c_m · dv/dt = -g_l · (v - e_l) + g_l · delta_t · exp((v - v_t) / delta_t) - w + I
+//! tau_w · dw/dt = a · (v - e_l) - w
+//! ```
+//!
+//! The neuron fires when `v` reaches `v_t + 5 · delta_t`, after which `v` is reset to `v_reset` and `w` is increased by `b`.
+//! The input `I` is made of instantaneous pulses: every weighted input `q` received by the neuron raises `v` by `q / c_m`.
+//!
+//! The equations are integrated numerically between consecutive inputs of a neuron.
+//! As a neuron is only updated when it receives some input, if its potential runs away towards the spike
+//! in the meantime, the spike is generated at its next input.
+
+use crate::Model;
+
+/// Time step (in units of the timestamps of the spikes) of the numerical integration of the AdEx equations
+const STEP: f64 = 0.05;
+
+/// Variation of the state of a neuron within a single [STEP] below which it's considered at rest
+const REST_TOLERANCE: f64 = 1e-12;
+
+/// A single AdEx neuron, see the [module](self) documentation for the meaning of its parameters.
+///
+/// # Examples
+///
+/// ```
+/// # use pds_spiking_nn::adex::*;
+/// let neuron = AdExNeuron::new(&AdExNeuronConfig::default());
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdExNeuron {
+    /// Membrane capacitance
+    pub c_m: f64,
+    /// Leak conductance
+    pub g_l: f64,
+    /// Leak reversal potential, which is also the initial potential of the neuron (unless offset by `v_init_offset`)
+    pub e_l: f64,
+    /// Threshold potential, where the exponential spike initiation takes over the leak
+    pub v_t: f64,
+    /// Slope factor of the spike initiation
+    pub delta_t: f64,
+    /// Subthreshold adaptation conductance
+    pub a: f64,
+    /// Time constant of the adaptation current
+    pub tau_w: f64,
+    /// Increment of the adaptation current after every spike
+    pub b: f64,
+    /// Reset potential
+    pub v_reset: f64,
+    /// Offset of the initial membrane potential from `e_l`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub v_init_offset: f64
+}
+
+/// A struct with variables only used in simulation (solve)
+#[derive(Clone, Debug, Default)]
+pub struct AdExSolverVars {
+    v_mem: f64,
+    w: f64,
+    ts_old: u128
+}
+
+impl From<&AdExNeuron> for AdExSolverVars {
+    fn from(neuron: &AdExNeuron) -> Self {
+        Self {
+            v_mem: neuron.e_l + neuron.v_init_offset,
+            w: 0.0,
+            ts_old: 0
+        }
+    }
+}
+
+impl AdExSolverVars {
+    /// Get the variables only used in simulation (solve) -> (v_mem, w, ts_old)
+    pub fn get_vars(&self) -> (f64, f64, u128) {
+        (self.v_mem, self.w, self.ts_old)
+    }
+}
+
+/// A struct used to create a specific configuration, simply reusable for other neurons.
+///
+/// The [Default] configuration is the tonic neuron of Brette and Gerstner (2005), with potentials in mV,
+/// capacitance in nF, conductances in µS, currents in nA and timestamps in ms: input weights are then charges in pC.
+///
+/// # Examples
+///
+/// ```
+/// # use pds_spiking_nn::adex::*;
+/// // A neuron with a stronger adaptation than the default one
+/// let config = AdExNeuronConfig { b: 0.5, ..Default::default() };
+///
+/// let neuron: AdExNeuron = From::from(&config);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdExNeuronConfig {
+    /// Membrane capacitance
+    pub c_m: f64,
+    /// Leak conductance
+    pub g_l: f64,
+    /// Leak reversal potential
+    pub e_l: f64,
+    /// Threshold potential
+    pub v_t: f64,
+    /// Slope factor of the spike initiation
+    pub delta_t: f64,
+    /// Subthreshold adaptation conductance
+    pub a: f64,
+    /// Time constant of the adaptation current
+    pub tau_w: f64,
+    /// Increment of the adaptation current after every spike
+    pub b: f64,
+    /// Reset potential
+    pub v_reset: f64
+}
+
+impl Default for AdExNeuronConfig {
+    fn default() -> Self {
+        Self {
+            c_m: 0.281,
+            g_l: 0.030,
+            e_l: -70.6,
+            v_t: -50.4,
+            delta_t: 2.0,
+            a: 0.004,
+            tau_w: 144.0,
+            b: 0.0805,
+            v_reset: -70.6
+        }
+    }
+}
+
+impl From<&AdExNeuronConfig> for AdExNeuron {
+    fn from(config: &AdExNeuronConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+/// Simd aggregate of four [AdExNeuron]s.
+///
+/// The exponential spike initiation can't be integrated in closed form, so the four neurons are solved one at a time.
+#[cfg(feature = "simd")]
+pub struct AdExNeuronx4([AdExNeuron; 4]);
+
+/// Simd aggregate of four [AdExSolverVars]
+#[cfg(feature = "simd")]
+pub struct AdExSolverVarsx4([AdExSolverVars; 4]);
+
+/// The Adaptive Exponential Integrate and Fire model.
+///
+/// Use this empty type to construct AdEx NNs, see the documentation at [NNBuilder](crate::NNBuilder) for details.
+#[derive(Clone, Copy, Debug)]
+pub struct AdaptiveExponential;
+
+impl Model for AdaptiveExponential {
+    type Neuron = AdExNeuron;
+    type SolverVars = AdExSolverVars;
+    type Config = AdExNeuronConfig;
+
+    /// Integrate the membrane potential and the adaptation current of `neuron` until `ts`, then apply the input pulse `weighted_input_val`.
+    ///
+    /// The output of this function is 1.0 iff the neuron has generated a new spike at time `ts`, or 0.0 otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{Model, adex::*};
+    /// let neuron = AdExNeuron::new(&AdExNeuronConfig::default());
+    /// let mut vars = From::from(&neuron);
+    ///
+    /// // A strong enough pulse pushes the potential beyond the spike cutoff right away
+    /// assert_eq!(AdaptiveExponential::handle_spike(&neuron, &mut vars, 10.0, 1), 1.0);
+    /// assert_eq!(AdaptiveExponential::handle_spike(&neuron, &mut vars, 1.0, 2), 0.0);
+    /// ```
+    #[inline]
+    fn handle_spike(neuron: &AdExNeuron, vars: &mut AdExSolverVars, weighted_input_val: f64, ts: u128) -> f64 {
+        // This early exit serves as a small optimization
+        if weighted_input_val == 0.0 { return 0.0 }
+
+        AdaptiveExponential::integrate(neuron, vars, ts);
+        vars.ts_old = ts;
+        vars.v_mem += weighted_input_val / neuron.c_m;
+
+        if vars.v_mem >= neuron.v_peak() {
+            vars.v_mem = neuron.v_reset;
+            vars.w += neuron.b;
+            1.
+        } else {
+            0.
+        }
+    }
+
+    #[inline]
+    fn membrane_potential(vars: &AdExSolverVars) -> f64 {
+        vars.v_mem
+    }
+
+    fn state(vars: &AdExSolverVars) -> Vec<(&'static str, f64)> {
+        vec![("v_mem", vars.v_mem), ("w", vars.w)]
+    }
+
+    /// Perturb every parameter, reflecting `c_m`, `delta_t` and `tau_w` to keep them positive
+    fn perturb(neuron: &mut AdExNeuron, mut noise: impl FnMut() -> f64) {
+        neuron.c_m = (neuron.c_m + noise()).abs();
+        neuron.g_l += noise();
+        neuron.e_l += noise();
+        neuron.v_t += noise();
+        neuron.delta_t = (neuron.delta_t + noise()).abs();
+        neuron.a += noise();
+        neuron.tau_w = (neuron.tau_w + noise()).abs();
+        neuron.b += noise();
+        neuron.v_reset += noise();
+    }
+
+    fn offset_initial_potential(neuron: &mut AdExNeuron, offset: f64) {
+        neuron.v_init_offset += offset;
+    }
+
+    #[cfg(feature = "simd")]
+    type Neuronx4 = AdExNeuronx4;
+    #[cfg(feature = "simd")]
+    type SolverVarsx4 = AdExSolverVarsx4;
+
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn neuron_x4_from_neurons(neurons: &[AdExNeuron]) -> AdExNeuronx4 {
+        AdExNeuronx4([neurons[0].clone(), neurons[1].clone(), neurons[2].clone(), neurons[3].clone()])
+    }
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn vars_x4_from_vars(vars: &[AdExSolverVars]) -> AdExSolverVarsx4 {
+        AdExSolverVarsx4([vars[0].clone(), vars[1].clone(), vars[2].clone(), vars[3].clone()])
+    }
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn handle_spike_x4(neurons: &Self::Neuronx4, vars: &mut Self::SolverVarsx4, weighted_input_vals: packed_simd::f64x4, ts: u128) -> packed_simd::f64x4 {
+        let output = |i: usize| AdaptiveExponential::handle_spike(&neurons.0[i], &mut vars.0[i], weighted_input_vals.extract(i), ts);
+
+        packed_simd::f64x4::new(output(0), output(1), output(2), output(3))
+    }
+}
+
+impl AdaptiveExponential {
+    /// Integrate the state of `neuron` from its last update until `ts`, without any input.
+    ///
+    /// The integration stops early if the neuron settles at rest, or if its potential reaches the spike cutoff.
+    fn integrate(neuron: &AdExNeuron, vars: &mut AdExSolverVars, ts: u128) {
+        let v_peak = neuron.v_peak();
+        let mut remaining = (ts - vars.ts_old) as f64;
+
+        while remaining > 0.0 {
+            let dt = remaining.min(STEP);
+            let dv = (
+                -neuron.g_l * (vars.v_mem - neuron.e_l)
+                + neuron.g_l * neuron.delta_t * ((vars.v_mem - neuron.v_t) / neuron.delta_t).exp()
+                - vars.w
+            ) / neuron.c_m * dt;
+            let dw = (neuron.a * (vars.v_mem - neuron.e_l) - vars.w) / neuron.tau_w * dt;
+
+            vars.v_mem += dv;
+            vars.w += dw;
+            remaining -= dt;
+
+            if vars.v_mem >= v_peak {
+                vars.v_mem = v_peak;
+                break;
+            }
+            if dv.abs() < REST_TOLERANCE && dw.abs() < REST_TOLERANCE {
+                break;
+            }
+        }
+    }
+}
+
+impl AdExNeuron {
+    /// Create a new [AdExNeuron] from a reference to an [AdExNeuronConfig].
+    ///
+    /// The same conversion can be obtained via the impl of `From<&AdExNeuronConfig> for AdExNeuron`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `c_m`, `delta_t` or `tau_w` are not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::adex::*;
+    /// let neuron = AdExNeuron::new(&AdExNeuronConfig::default());
+    ///
+    /// assert!((neuron.v_peak() + 40.4).abs() < 1e-9);
+    /// ```
+    pub fn new(config: &AdExNeuronConfig) -> AdExNeuron {
+        assert!(config.c_m > 0.0, "the membrane capacitance must be positive");
+        assert!(config.delta_t > 0.0, "the slope factor must be positive");
+        assert!(config.tau_w > 0.0, "the adaptation time constant must be positive");
+
+        AdExNeuron {
+            c_m: config.c_m,
+            g_l: config.g_l,
+            e_l: config.e_l,
+            v_t: config.v_t,
+            delta_t: config.delta_t,
+            a: config.a,
+            tau_w: config.tau_w,
+            b: config.b,
+            v_reset: config.v_reset,
+            v_init_offset: 0.0
+        }
+    }
+
+    /// Potential at which the neuron fires, which is `v_t + 5 · delta_t`
+    pub fn v_peak(&self) -> f64 {
+        self.v_t + 5.0 * self.delta_t
+    }
+}
@@ -0,0 +1,318 @@
+//! Implementation of the conductance-based Leaky Integrate and Fire (COBA) model for spiking neural networks
+//!
+//! In the [lif](crate::lif) model, every input spike injects a fixed amount of charge into the membrane, whatever its potential.
+//! Here, instead, an input spike opens a synaptic conductance which decays exponentially, and drives a current proportional
+//! to the distance between the membrane potential and the reversal potential of the synapse.
+//! An excitatory input can thus never push the potential above its reversal potential, and an inhibitory input whose reversal potential
+//! is close to the resting one shunts the excitatory inputs without lowering the potential by itself.
+//!
+//! The membrane potential `v` and the excitatory and inhibitory conductances `g_e` and `g_i` of a neuron evolve as
+//!
+//! ```text
+//! tau_m · dv/dt = (v_rest - v) + g_e · (e_exc - v) + g_i · (e_inh - v)
+//! tau_syn · dg_e/dt = -g_e
+//! tau_syn · dg_i/dt = -g_i
+//! ```
+//!
+//! Conductances are expressed relative to the leak conductance of the membrane.
+//! Every weighted input `q` received by a neuron raises `g_e` by `q` if it's positive, or `g_i` by `-q` if it's negative:
+//! the weights of the synapses are thus their peak conductances, and their sign selects the reversal potential.
+//! Note that the inputs reaching a neuron at the same timestamp are summed before this choice.
+//!
+//! The equations are integrated numerically (with the exponential Euler method) between consecutive inputs of a neuron,
+//! and the neuron fires if its potential has reached the threshold by the time it receives an input.
+//! As the conductance opened by an input only charges the membrane over time, a neuron never fires at the same timestamp of the
+//! input that drives it beyond the threshold, but at its next one.
+
+use crate::Model;
+
+/// Time step (in units of the timestamps of the spikes) of the numerical integration of the COBA equations,
+/// during which the conductances are considered constant
+const STEP: f64 = 0.05;
+
+/// Conductance below which a synapse is considered closed
+const CLOSED_TOLERANCE: f64 = 1e-12;
+
+/// A single COBA neuron, see the [module](self) documentation for the meaning of its parameters.
+///
+/// # Examples
+///
+/// ```
+/// # use pds_spiking_nn::coba::*;
+/// let neuron = CobaNeuron::new(&CobaNeuronConfig::default());
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CobaNeuron {
+    /// Resting potential, which is also the initial potential of the neuron (unless offset by `v_init_offset`)
+    pub v_rest: f64,
+    /// Reset potential
+    pub v_reset: f64,
+    /// Threshold potential
+    pub v_threshold: f64,
+    /// Time constant of the membrane
+    pub tau_m: f64,
+    /// Time constant of the synaptic conductances
+    pub tau_syn: f64,
+    /// Reversal potential of the excitatory synapses
+    pub e_exc: f64,
+    /// Reversal potential of the inhibitory synapses
+    pub e_inh: f64,
+    /// Offset of the initial membrane potential from `v_rest`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub v_init_offset: f64
+}
+
+/// A struct with variables only used in simulation (solve)
+#[derive(Clone, Debug, Default)]
+pub struct CobaSolverVars {
+    v_mem: f64,
+    g_exc: f64,
+    g_inh: f64,
+    ts_old: u128
+}
+
+impl From<&CobaNeuron> for CobaSolverVars {
+    fn from(neuron: &CobaNeuron) -> Self {
+        Self {
+            v_mem: neuron.v_rest + neuron.v_init_offset,
+            g_exc: 0.0,
+            g_inh: 0.0,
+            ts_old: 0
+        }
+    }
+}
+
+impl CobaSolverVars {
+    /// Get the variables only used in simulation (solve) -> (v_mem, g_exc, g_inh, ts_old)
+    pub fn get_vars(&self) -> (f64, f64, f64, u128) {
+        (self.v_mem, self.g_exc, self.g_inh, self.ts_old)
+    }
+}
+
+/// A struct used to create a specific configuration, simply reusable for other neurons.
+///
+/// The [Default] configuration is a typical cortical neuron, with potentials in mV and timestamps in ms.
+///
+/// # Examples
+///
+/// ```
+/// # use pds_spiking_nn::coba::*;
+/// // A neuron whose inhibitory synapses only shunt the excitatory ones
+/// let config = CobaNeuronConfig { e_inh: -65.0, ..Default::default() };
+///
+/// let neuron: CobaNeuron = From::from(&config);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CobaNeuronConfig {
+    /// Resting potential
+    pub v_rest: f64,
+    /// Reset potential
+    pub v_reset: f64,
+    /// Threshold potential
+    pub v_threshold: f64,
+    /// Time constant of the membrane
+    pub tau_m: f64,
+    /// Time constant of the synaptic conductances
+    pub tau_syn: f64,
+    /// Reversal potential of the excitatory synapses
+    pub e_exc: f64,
+    /// Reversal potential of the inhibitory synapses
+    pub e_inh: f64
+}
+
+impl Default for CobaNeuronConfig {
+    fn default() -> Self {
+        Self {
+            v_rest: -65.0,
+            v_reset: -65.0,
+            v_threshold: -50.0,
+            tau_m: 20.0,
+            tau_syn: 5.0,
+            e_exc: 0.0,
+            e_inh: -80.0
+        }
+    }
+}
+
+impl From<&CobaNeuronConfig> for CobaNeuron {
+    fn from(config: &CobaNeuronConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+/// Simd aggregate of four [CobaNeuron]s.
+///
+/// The potential and the conductances are integrated numerically, so the four neurons are solved one at a time.
+#[cfg(feature = "simd")]
+pub struct CobaNeuronx4([CobaNeuron; 4]);
+
+/// Simd aggregate of four [CobaSolverVars]
+#[cfg(feature = "simd")]
+pub struct CobaSolverVarsx4([CobaSolverVars; 4]);
+
+/// The conductance-based Leaky Integrate and Fire model.
+///
+/// Use this empty type to construct COBA NNs, see the documentation at [NNBuilder](crate::NNBuilder) for details.
+#[derive(Clone, Copy, Debug)]
+pub struct ConductanceBased;
+
+impl Model for ConductanceBased {
+    type Neuron = CobaNeuron;
+    type SolverVars = CobaSolverVars;
+    type Config = CobaNeuronConfig;
+
+    /// Integrate the membrane potential and the conductances of `neuron` until `ts`, then open the synapses by `weighted_input_val`.
+    ///
+    /// The output of this function is 1.0 iff the neuron has generated a new spike at time `ts`, or 0.0 otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{Model, coba::*};
+    /// let neuron = CobaNeuron::new(&CobaNeuronConfig::default());
+    /// let mut vars = From::from(&neuron);
+    ///
+    /// // The first input opens the excitatory synapses, which charge the membrane beyond the threshold by the next one
+    /// assert_eq!(ConductanceBased::handle_spike(&neuron, &mut vars, 5.0, 1), 0.0);
+    /// assert_eq!(ConductanceBased::handle_spike(&neuron, &mut vars, 5.0, 3), 1.0);
+    /// ```
+    #[inline]
+    fn handle_spike(neuron: &CobaNeuron, vars: &mut CobaSolverVars, weighted_input_val: f64, ts: u128) -> f64 {
+        // This early exit serves as a small optimization
+        if weighted_input_val == 0.0 { return 0.0 }
+
+        ConductanceBased::integrate(neuron, vars, ts);
+        vars.ts_old = ts;
+
+        if weighted_input_val > 0.0 {
+            vars.g_exc += weighted_input_val;
+        } else {
+            vars.g_inh -= weighted_input_val;
+        }
+
+        if vars.v_mem >= neuron.v_threshold {
+            vars.v_mem = neuron.v_reset;
+            1.
+        } else {
+            0.
+        }
+    }
+
+    #[inline]
+    fn membrane_potential(vars: &CobaSolverVars) -> f64 {
+        vars.v_mem
+    }
+
+    fn state(vars: &CobaSolverVars) -> Vec<(&'static str, f64)> {
+        vec![("v_mem", vars.v_mem), ("g_exc", vars.g_exc), ("g_inh", vars.g_inh)]
+    }
+
+    /// Perturb every parameter, reflecting the time constants to keep them positive
+    fn perturb(neuron: &mut CobaNeuron, mut noise: impl FnMut() -> f64) {
+        neuron.v_rest += noise();
+        neuron.v_reset += noise();
+        neuron.v_threshold += noise();
+        neuron.tau_m = (neuron.tau_m + noise()).abs();
+        neuron.tau_syn = (neuron.tau_syn + noise()).abs();
+        neuron.e_exc += noise();
+        neuron.e_inh += noise();
+    }
+
+    fn offset_initial_potential(neuron: &mut CobaNeuron, offset: f64) {
+        neuron.v_init_offset += offset;
+    }
+
+    #[cfg(feature = "simd")]
+    type Neuronx4 = CobaNeuronx4;
+    #[cfg(feature = "simd")]
+    type SolverVarsx4 = CobaSolverVarsx4;
+
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn neuron_x4_from_neurons(neurons: &[CobaNeuron]) -> CobaNeuronx4 {
+        CobaNeuronx4([neurons[0].clone(), neurons[1].clone(), neurons[2].clone(), neurons[3].clone()])
+    }
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn vars_x4_from_vars(vars: &[CobaSolverVars]) -> CobaSolverVarsx4 {
+        CobaSolverVarsx4([vars[0].clone(), vars[1].clone(), vars[2].clone(), vars[3].clone()])
+    }
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn handle_spike_x4(neurons: &Self::Neuronx4, vars: &mut Self::SolverVarsx4, weighted_input_vals: packed_simd::f64x4, ts: u128) -> packed_simd::f64x4 {
+        let output = |i: usize| ConductanceBased::handle_spike(&neurons.0[i], &mut vars.0[i], weighted_input_vals.extract(i), ts);
+
+        packed_simd::f64x4::new(output(0), output(1), output(2), output(3))
+    }
+}
+
+impl ConductanceBased {
+    /// Integrate the state of `neuron` from its last update until `ts`, without any input.
+    ///
+    /// Once the synapses are closed, the potential decays towards the resting one in closed form, like in the lif model.
+    /// The integration stops early if the potential reaches the threshold.
+    fn integrate(neuron: &CobaNeuron, vars: &mut CobaSolverVars, ts: u128) {
+        let mut remaining = (ts - vars.ts_old) as f64;
+
+        while remaining > 0.0 {
+            if vars.g_exc < CLOSED_TOLERANCE && vars.g_inh < CLOSED_TOLERANCE {
+                vars.g_exc = 0.0;
+                vars.g_inh = 0.0;
+                vars.v_mem = neuron.v_rest + (vars.v_mem - neuron.v_rest) * (-remaining / neuron.tau_m).exp();
+                break;
+            }
+
+            // Within a step the conductances are constant, so the potential relaxes exponentially towards the weighted mean
+            // of the reversal potentials: unlike the plain Euler method, this stays stable even for very large conductances
+            let dt = remaining.min(STEP);
+            let g_tot = 1.0 + vars.g_exc + vars.g_inh;
+            let v_inf = (neuron.v_rest + vars.g_exc * neuron.e_exc + vars.g_inh * neuron.e_inh) / g_tot;
+            let decay = (-dt / neuron.tau_syn).exp();
+
+            vars.v_mem = v_inf + (vars.v_mem - v_inf) * (-dt * g_tot / neuron.tau_m).exp();
+            vars.g_exc *= decay;
+            vars.g_inh *= decay;
+            remaining -= dt;
+
+            if vars.v_mem >= neuron.v_threshold {
+                break;
+            }
+        }
+    }
+}
+
+impl CobaNeuron {
+    /// Create a new [CobaNeuron] from a reference to a [CobaNeuronConfig].
+    ///
+    /// The same conversion can be obtained via the impl of `From<&CobaNeuronConfig> for CobaNeuron`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tau_m` or `tau_syn` are not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::coba::*;
+    /// let neuron = CobaNeuron::new(&CobaNeuronConfig { tau_syn: 2.0, ..Default::default() });
+    ///
+    /// assert_eq!(neuron.tau_syn, 2.0);
+    /// ```
+    pub fn new(config: &CobaNeuronConfig) -> CobaNeuron {
+        assert!(config.tau_m > 0.0, "the membrane time constant must be positive");
+        assert!(config.tau_syn > 0.0, "the synaptic time constant must be positive");
+
+        CobaNeuron {
+            v_rest: config.v_rest,
+            v_reset: config.v_reset,
+            v_threshold: config.v_threshold,
+            tau_m: config.tau_m,
+            tau_syn: config.tau_syn,
+            e_exc: config.e_exc,
+            e_inh: config.e_inh,
+            v_init_offset: 0.0
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Combination of two models, to build networks with neurons of different kinds
+//!
+//! A [NN](crate::NN) is solved for a single [Model], whose neurons are all of the same kind.
+//! The [Hybrid] model wraps two models, so that every neuron of the network can be of either one:
+//! for example, a network can have a first layer of [lif](crate::lif) neurons, and an output layer of [adex](crate::adex) neurons.
+//! Weights are shared by both models, so they are still of the [Float](crate::Float) type of the network.
+//!
+//! More than two models can be combined by nesting [Hybrid]s.
+
+use std::{marker::PhantomData, fmt};
+use crate::Model;
+
+/// A neuron of either one of the two models of a [Hybrid] model
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(
+    serialize = "A::Neuron: serde::Serialize, B::Neuron: serde::Serialize",
+    deserialize = "A::Neuron: serde::Deserialize<'de>, B::Neuron: serde::Deserialize<'de>"
+)))]
+pub enum HybridNeuron<A: Model, B: Model> {
+    /// A neuron of the first model
+    First(A::Neuron),
+    /// A neuron of the second model
+    Second(B::Neuron)
+}
+
+impl<A: Model, B: Model> Clone for HybridNeuron<A, B> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::First(neuron) => Self::First(neuron.clone()),
+            Self::Second(neuron) => Self::Second(neuron.clone())
+        }
+    }
+}
+
+/// The variables only used in simulation (solve) by a [HybridNeuron], which are those of its own model
+pub enum HybridSolverVars<A: Model, B: Model> {
+    /// Variables of a neuron of the first model
+    First(A::SolverVars),
+    /// Variables of a neuron of the second model
+    Second(B::SolverVars)
+}
+
+impl<A: Model, B: Model> Default for HybridSolverVars<A, B> {
+    fn default() -> Self {
+        Self::First(Default::default())
+    }
+}
+
+impl<A: Model, B: Model> From<&HybridNeuron<A, B>> for HybridSolverVars<A, B>
+where for<'a> &'a A::Neuron: Into<A::SolverVars>, for<'a> &'a B::Neuron: Into<B::SolverVars> {
+    fn from(neuron: &HybridNeuron<A, B>) -> Self {
+        match neuron {
+            HybridNeuron::First(neuron) => Self::First(neuron.into()),
+            HybridNeuron::Second(neuron) => Self::Second(neuron.into())
+        }
+    }
+}
+
+/// Configuration of a [HybridNeuron], which is the configuration of a neuron of either one of the two models.
+///
+/// # Examples
+///
+/// ```
+/// # use pds_spiking_nn::{lif::*, adex::*, hybrid::*};
+/// let config = HybridNeuronConfig::<LeakyIntegrateFire, AdaptiveExponential>::Second(AdExNeuronConfig::default());
+/// let neuron: HybridNeuron<_, _> = From::from(&config);
+///
+/// assert!(matches!(neuron, HybridNeuron::Second(_)));
+/// ```
+pub enum HybridNeuronConfig<A: Model, B: Model> {
+    /// Configuration of a neuron of the first model
+    First(A::Config),
+    /// Configuration of a neuron of the second model
+    Second(B::Config)
+}
+
+impl<A: Model, B: Model> From<&HybridNeuronConfig<A, B>> for HybridNeuron<A, B>
+where for<'a> &'a A::Config: Into<A::Neuron>, for<'a> &'a B::Config: Into<B::Neuron> {
+    fn from(config: &HybridNeuronConfig<A, B>) -> Self {
+        match config {
+            HybridNeuronConfig::First(config) => Self::First(config.into()),
+            HybridNeuronConfig::Second(config) => Self::Second(config.into())
+        }
+    }
+}
+
+/// Simd aggregate of four [HybridNeuron]s, which are solved one at a time
+#[cfg(feature = "simd")]
+pub struct HybridNeuronx4<A: Model, B: Model>([HybridNeuron<A, B>; 4]);
+
+/// Simd aggregate of four [HybridSolverVars].
+///
+/// The variables can't be cloned, so they're built from their neurons at their first update.
+#[cfg(feature = "simd")]
+pub struct HybridSolverVarsx4<A: Model, B: Model>([Option<HybridSolverVars<A, B>>; 4]);
+
+/// Model whose neurons can be of either model `A` or model `B`.
+///
+/// Use this empty type to construct NNs with neurons of different kinds, see the documentation at [NNBuilder](crate::NNBuilder) for details.
+///
+/// # Examples
+///
+/// ```
+/// # use pds_spiking_nn::{NNBuilder, Spike, lif::*, adex::*, hybrid::*};
+/// type LifAdEx = Hybrid<LeakyIntegrateFire, AdaptiveExponential>;
+///
+/// let lif = HybridNeuron::First(LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0)));
+/// let adex = HybridNeuron::Second(AdExNeuron::new(&AdExNeuronConfig::default()));
+///
+/// // A lif input layer, and an adex output layer
+/// let nn = NNBuilder::<LifAdEx, _>::new()
+///     .layer([lif], [1.0], [[0.0]])
+///     .layer([adex], [[20.0]], [[0.0]])
+///     .build();
+///
+/// assert_eq!(nn.solve_sequential(Spike::spike_vec_for(0, vec![1])), vec![Spike::new(1, 0)]);
+/// ```
+pub struct Hybrid<A: Model, B: Model>(PhantomData<(A, B)>);
+
+impl<A: Model, B: Model> Clone for Hybrid<A, B> {
+    fn clone(&self) -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: Model, B: Model> fmt::Debug for Hybrid<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Hybrid").field(&PhantomData::<A>).field(&PhantomData::<B>).finish()
+    }
+}
+
+impl<A: Model, B: Model> Model for Hybrid<A, B>
+where
+    for<'a> &'a A::Neuron: Into<A::SolverVars>,
+    for<'a> &'a B::Neuron: Into<B::SolverVars>,
+    for<'a> &'a A::Config: Into<A::Neuron>,
+    for<'a> &'a B::Config: Into<B::Neuron>
+{
+    type Neuron = HybridNeuron<A, B>;
+    type SolverVars = HybridSolverVars<A, B>;
+    type Config = HybridNeuronConfig<A, B>;
+
+    /// The threshold shared by both models: mixing models that disagree on it fails to compile
+    const FIRING_THRESHOLD: f64 = {
+        assert!(A::FIRING_THRESHOLD == B::FIRING_THRESHOLD, "the models of a hybrid must share their firing threshold");
+        A::FIRING_THRESHOLD
+    };
+
+    /// Dispatch the input to the model of `neuron`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `vars` are not the variables of a neuron of the same model as `neuron`.
+    #[inline]
+    fn handle_spike(neuron: &Self::Neuron, vars: &mut Self::SolverVars, weighted_input_val: f64, ts: u128) -> f64 {
+        match (neuron, vars) {
+            (HybridNeuron::First(neuron), HybridSolverVars::First(vars)) => A::handle_spike(neuron, vars, weighted_input_val, ts),
+            (HybridNeuron::Second(neuron), HybridSolverVars::Second(vars)) => B::handle_spike(neuron, vars, weighted_input_val, ts),
+            _ => panic!("the solver variables belong to a neuron of a different model")
+        }
+    }
+
+    #[inline]
+    fn membrane_potential(vars: &Self::SolverVars) -> f64 {
+        match vars {
+            HybridSolverVars::First(vars) => A::membrane_potential(vars),
+            HybridSolverVars::Second(vars) => B::membrane_potential(vars)
+        }
+    }
+
+    fn state(vars: &Self::SolverVars) -> Vec<(&'static str, f64)> {
+        match vars {
+            HybridSolverVars::First(vars) => A::state(vars),
+            HybridSolverVars::Second(vars) => B::state(vars)
+        }
+    }
+
+    fn perturb(neuron: &mut Self::Neuron, noise: impl FnMut() -> f64) {
+        match neuron {
+            HybridNeuron::First(neuron) => A::perturb(neuron, noise),
+            HybridNeuron::Second(neuron) => B::perturb(neuron, noise)
+        }
+    }
+
+    fn offset_initial_potential(neuron: &mut Self::Neuron, offset: f64) {
+        match neuron {
+            HybridNeuron::First(neuron) => A::offset_initial_potential(neuron, offset),
+            HybridNeuron::Second(neuron) => B::offset_initial_potential(neuron, offset)
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    type Neuronx4 = HybridNeuronx4<A, B>;
+    #[cfg(feature = "simd")]
+    type SolverVarsx4 = HybridSolverVarsx4<A, B>;
+
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn neuron_x4_from_neurons(neurons: &[Self::Neuron]) -> Self::Neuronx4 {
+        HybridNeuronx4([neurons[0].clone(), neurons[1].clone(), neurons[2].clone(), neurons[3].clone()])
+    }
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn vars_x4_from_vars(_: &[Self::SolverVars]) -> Self::SolverVarsx4 {
+        // The solvers only aggregate variables in their initial state, which are rebuilt from the neurons
+        HybridSolverVarsx4([None, None, None, None])
+    }
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn handle_spike_x4(neurons: &Self::Neuronx4, vars: &mut Self::SolverVarsx4, weighted_input_vals: packed_simd::f64x4, ts: u128) -> packed_simd::f64x4 {
+        let mut output = |i: usize| Self::handle_spike(
+            &neurons.0[i],
+            vars.0[i].get_or_insert_with(|| (&neurons.0[i]).into()),
+            weighted_input_vals.extract(i),
+            ts
+        );
+
+        packed_simd::f64x4::new(output(0), output(1), output(2), output(3))
+    }
+}
@@ -1,6 +1,7 @@
 //! Implementation of the Leaky Integrate and Fire (LIF) model for spiking neural networks
 
-use crate::Model;
+use thiserror::Error;
+use crate::{Model, NN, Spike, Float, nn::sequential::{Pipeline, input_events, output_spikes}};
 
 /// A struct for a single Neuron of the SNN.
 /// Each Neuron has its own parameters such as _current membrane tension_, _threshold tension_ etc...
@@ -18,6 +19,7 @@ use crate::Model;
 /// let neuron = LifNeuron::new(&nc);
 /// ```
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LifNeuron {
     /// Rest potential
     pub v_rest: f64,
@@ -27,20 +29,37 @@ pub struct LifNeuron {
     pub v_threshold: f64,
     /// Membrane's time constant. This is the product of its capacity and resistance
     pub tau: f64,
+    /// Absolute refractory period: after firing at `ts`, the neuron can't fire again before `ts + refractory_period`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub refractory_period: u128,
+    /// Amount the threshold is raised by every time the neuron fires
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub threshold_adaptation: f64,
+    /// Time constant of the relaxation of the raised threshold back to `v_threshold`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tau_threshold: f64,
+    /// Offset of the initial membrane potential from `v_rest`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub v_init_offset: f64
 }
 
 /// A struct with variables only used in simulation (solve)
 #[derive(Clone, Debug, Default)]
 pub struct LifSolverVars {
     v_mem: f64,
-    ts_old: u128,  
+    ts_old: u128,
+    last_spike_ts: Option<u128>,
+    /// How much the threshold is currently raised above `v_threshold`, as of `ts_old`
+    threshold_offset: f64
 }
 
 impl From<&LifNeuron> for LifSolverVars {
     fn from(neuron: &LifNeuron) -> Self {
         Self {
-            v_mem: neuron.v_rest,
-            ts_old: 0
+            v_mem: neuron.v_rest + neuron.v_init_offset,
+            ts_old: 0,
+            last_spike_ts: None,
+            threshold_offset: 0.0
         }
     }
 }
@@ -52,6 +71,14 @@ impl LifSolverVars {
 
         (self.v_mem, self.ts_old)
     }
+
+    /// Get the current threshold of `neuron`, as of the last update of these variables.
+    /// 
+    /// This is the neuron's [v_threshold](LifNeuron::v_threshold), raised by its [threshold adaptation](LifNeuronConfig::with_threshold_adaptation)
+    /// if it fired recently.
+    pub fn threshold(&self, neuron: &LifNeuron) -> f64 {
+        neuron.v_threshold + self.threshold_offset
+    }
 }
 
 /// A struct used to create a specific configuration, simply reusable for other neurons
@@ -68,12 +95,19 @@ impl LifSolverVars {
 /// // ...
 /// let neuron_four = LifNeuron::new(&config_two);
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LifNeuronConfig {
     v_rest: f64,
     v_reset: f64,
     v_threshold: f64,
-    tau: f64
+    tau: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    refractory_period: u128,
+    #[cfg_attr(feature = "serde", serde(default))]
+    threshold_adaptation: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tau_threshold: f64
 }
 
 impl From<&LifNeuronConfig> for LifNeuron {
@@ -82,20 +116,45 @@ impl From<&LifNeuronConfig> for LifNeuron {
     }
 }
 
+/// Error for the fallible constructors of the lif model's types, like [LifNeuron::try_new_vec]
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum LifConfigError {
+    #[error("Number of configurations ({configs}) and number of neurons ({neurons}) differ")]
+    ConfigCountMismatch {
+        configs: usize,
+        neurons: usize
+    },
+    #[error("Parameter {0} is not finite")]
+    NonFiniteParameter(&'static str),
+    #[error("Time constant ({0}) is not positive")]
+    NonPositiveTau(f64),
+    #[error("Threshold potential ({v_threshold}) is not above the reset potential ({v_reset})")]
+    ThresholdNotAboveReset {
+        v_threshold: f64,
+        v_reset: f64
+    }
+}
+
 /// Simd aggregate of four [LifNeuron]s
 #[cfg(feature = "simd")]
 pub struct LifNeuronx4 {
     v_rest: packed_simd::f64x4,
     v_reset: packed_simd::f64x4,
     v_threshold: packed_simd::f64x4,
-    tau: packed_simd::f64x4
+    tau: packed_simd::f64x4,
+    refractory_period: packed_simd::f64x4,
+    threshold_adaptation: packed_simd::f64x4,
+    tau_threshold: packed_simd::f64x4
 }
 
 /// Simd aggregate of four [LifSolverVars]
 #[cfg(feature = "simd")]
 pub struct LifSolverVarsx4 {
     v_mem: packed_simd::f64x4,
-    ts_old: packed_simd::f64x4
+    ts_old: packed_simd::f64x4,
+    /// Negative infinity for the neurons that never fired
+    last_spike_ts: packed_simd::f64x4,
+    threshold_offset: packed_simd::f64x4
 }
 
 /// Model provided by this library as example.
@@ -140,20 +199,49 @@ impl Model for LeakyIntegrateFire {
         // This early exit serves as a small optimization
         if weighted_input_val == 0.0 { return 0.0 }
         
-        let delta_t: f64 = (ts - vars.ts_old) as f64;
+        // compute the new v_mem value, and let the threshold relax in the meantime
+        vars.v_mem = LeakyIntegrateFire::integrate(neuron, vars, weighted_input_val, ts);
+        vars.threshold_offset = LeakyIntegrateFire::threshold_offset(neuron, vars, ts);
         vars.ts_old = ts;
 
-        // compute the new v_mem value
-        vars.v_mem = neuron.v_rest + (vars.v_mem - neuron.v_rest) * (-delta_t / neuron.tau).exp() + weighted_input_val;
-
-        if vars.v_mem > neuron.v_threshold {
+        if vars.v_mem > vars.threshold(neuron) {
             vars.v_mem = neuron.v_reset;
-            1. 
+
+            // Within the refractory period the membrane is still reset, but no spike is generated
+            if LeakyIntegrateFire::is_refractory(neuron, vars, ts) {
+                0.
+            } else {
+                vars.last_spike_ts = Some(ts);
+                vars.threshold_offset += neuron.threshold_adaptation;
+                1.
+            }
         } else {
             0.
         }
     }
 
+    #[inline]
+    fn membrane_potential(vars: &LifSolverVars) -> f64 {
+        vars.v_mem
+    }
+
+    fn state(vars: &LifSolverVars) -> Vec<(&'static str, f64)> {
+        vec![("v_mem", vars.v_mem), ("threshold_offset", vars.threshold_offset)]
+    }
+
+    /// Perturb the potentials and the time constant of the membrane, which is reflected to stay positive.
+    /// The refractory period and the threshold adaptation are left untouched.
+    fn perturb(neuron: &mut LifNeuron, mut noise: impl FnMut() -> f64) {
+        neuron.v_rest += noise();
+        neuron.v_reset += noise();
+        neuron.v_threshold += noise();
+        neuron.tau = (neuron.tau + noise()).abs();
+    }
+
+    fn offset_initial_potential(neuron: &mut LifNeuron, offset: f64) {
+        neuron.v_init_offset += offset;
+    }
+
     #[cfg(feature = "simd")]
     type Neuronx4 = LifNeuronx4;
     #[cfg(feature = "simd")]
@@ -166,7 +254,20 @@ impl Model for LeakyIntegrateFire {
             v_rest: From::from([neurons[0].v_rest, neurons[1].v_rest, neurons[2].v_rest, neurons[3].v_rest]),
             v_reset: From::from([neurons[0].v_reset, neurons[1].v_reset, neurons[2].v_reset, neurons[3].v_reset]),
             v_threshold: From::from([neurons[0].v_threshold, neurons[1].v_threshold, neurons[2].v_threshold, neurons[3].v_threshold]),
-            tau: From::from([neurons[0].tau, neurons[1].tau, neurons[2].tau, neurons[3].tau])
+            tau: From::from([neurons[0].tau, neurons[1].tau, neurons[2].tau, neurons[3].tau]),
+            refractory_period: From::from([
+                neurons[0].refractory_period as _,
+                neurons[1].refractory_period as _,
+                neurons[2].refractory_period as _,
+                neurons[3].refractory_period as _
+            ]),
+            threshold_adaptation: From::from([
+                neurons[0].threshold_adaptation,
+                neurons[1].threshold_adaptation,
+                neurons[2].threshold_adaptation,
+                neurons[3].threshold_adaptation
+            ]),
+            tau_threshold: From::from([neurons[0].tau_threshold, neurons[1].tau_threshold, neurons[2].tau_threshold, neurons[3].tau_threshold])
         }
     }
     #[cfg(feature = "simd")]
@@ -174,7 +275,19 @@ impl Model for LeakyIntegrateFire {
     fn vars_x4_from_vars(vars: &[LifSolverVars]) -> LifSolverVarsx4 {
         LifSolverVarsx4 {
             v_mem: From::from([vars[0].v_mem, vars[1].v_mem, vars[2].v_mem, vars[3].v_mem]),
-            ts_old: From::from([vars[0].ts_old as _, vars[1].ts_old as _, vars[2].ts_old as _, vars[3].ts_old as _])
+            ts_old: From::from([vars[0].ts_old as _, vars[1].ts_old as _, vars[2].ts_old as _, vars[3].ts_old as _]),
+            last_spike_ts: From::from([
+                vars[0].last_spike_ts.map_or(f64::NEG_INFINITY, |ts| ts as _),
+                vars[1].last_spike_ts.map_or(f64::NEG_INFINITY, |ts| ts as _),
+                vars[2].last_spike_ts.map_or(f64::NEG_INFINITY, |ts| ts as _),
+                vars[3].last_spike_ts.map_or(f64::NEG_INFINITY, |ts| ts as _)
+            ]),
+            threshold_offset: From::from([
+                vars[0].threshold_offset,
+                vars[1].threshold_offset,
+                vars[2].threshold_offset,
+                vars[3].threshold_offset
+            ])
         }
     }
     #[cfg(feature = "simd")]
@@ -188,11 +301,27 @@ impl Model for LeakyIntegrateFire {
         let dt: f64x4 = ts - vars.ts_old;
         vars.ts_old = ts;
         
+        // Like in the scalar version, no time elapsed means no decay, and an underflown decay settles the membrane at v_rest.
         // The exp() right here is the only reason why I went with packed_simd instead of the portable_simd in std
-        vars.v_mem = neurons.v_rest + (vars.v_mem - neurons.v_rest) * (-dt / neurons.tau).exp() + weighted_input_vals;
+        let decay = (-dt / neurons.tau).exp();
+        let decayed = (decay.gt(f64x4::splat(0.0)) & decay.lt(f64x4::splat(f64::INFINITY)))
+            .select(neurons.v_rest + (vars.v_mem - neurons.v_rest) * decay, neurons.v_rest);
+        vars.v_mem = dt.eq(f64x4::splat(0.0)).select(vars.v_mem, decayed) + weighted_input_vals;
+
+        // The raised threshold relaxes the same way, skipping the lanes that aren't raised at all
+        let threshold_decay = (-dt / neurons.tau_threshold).exp();
+        let relaxed = (threshold_decay.gt(f64x4::splat(0.0)) & threshold_decay.lt(f64x4::splat(f64::INFINITY)))
+            .select(vars.threshold_offset * threshold_decay, f64x4::splat(0.0));
+        vars.threshold_offset = (dt.eq(f64x4::splat(0.0)) | vars.threshold_offset.eq(f64x4::splat(0.0)))
+            .select(vars.threshold_offset, relaxed);
 
-        let fired = vars.v_mem.gt(neurons.v_threshold);
-        vars.v_mem = fired.select(neurons.v_reset, vars.v_mem);
+        let crossed = vars.v_mem.gt(neurons.v_threshold + vars.threshold_offset);
+        vars.v_mem = crossed.select(neurons.v_reset, vars.v_mem);
+
+        // Within the refractory period the membrane is still reset, but no spike is generated
+        let fired = crossed & ts.ge(vars.last_spike_ts + neurons.refractory_period);
+        vars.last_spike_ts = fired.select(ts, vars.last_spike_ts);
+        vars.threshold_offset = fired.select(vars.threshold_offset + neurons.threshold_adaptation, vars.threshold_offset);
 
         fired.select(f64x4::splat(1.0), f64x4::splat(0.0))
     }
@@ -219,9 +348,37 @@ impl LifNeuron {
             v_reset:  nc.v_reset ,
             v_threshold:  nc.v_threshold ,
             tau:  nc.tau,
+            refractory_period: nc.refractory_period,
+            threshold_adaptation: nc.threshold_adaptation,
+            tau_threshold: nc.tau_threshold,
+            v_init_offset: 0.0
         }
     }
 
+    /// Get the [LifNeuronConfig] this neuron can be built from, with all of its model parameters.
+    /// 
+    /// This is the inverse of [new](LifNeuron::new), which is useful to inspect (or log) the parameters of the neurons
+    /// of a network once it's built, e.g. after deserializing it.
+    /// The [v_init_offset](LifNeuron::v_init_offset) is not part of the configuration, since it's the state the neuron starts from
+    /// (see [NNBuilder::with_random_init](crate::NNBuilder::with_random_init)): a neuron rebuilt from its config starts from `v_rest`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::lif::*;
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0)
+    ///     .with_refractory_period(2)
+    ///     .with_threshold_adaptation(0.5, 10.0);
+    /// let neuron = LifNeuron::new(&config);
+    /// 
+    /// assert_eq!(neuron.config(), config);
+    /// ```
+    pub fn config(&self) -> LifNeuronConfig {
+        LifNeuronConfig::new(self.v_rest, self.v_reset, self.v_threshold, self.tau)
+            .with_refractory_period(self.refractory_period)
+            .with_threshold_adaptation(self.threshold_adaptation, self.tau_threshold)
+    }
+
     /// Create a new array of [LifNeuron] structs, starting from a given array of [LifNeuronConfig].
     /// 
     /// If _ncs_ contains a single element, it will be used for 
@@ -269,6 +426,31 @@ impl LifNeuron {
     /// let neurons = LifNeuron::new_vec(configs, 10); // Panic! expected 3, received 10
     /// ```
     pub fn new_vec(ncs: Vec<LifNeuronConfig>, dim: usize) -> Vec<LifNeuron>{
+        Self::try_new_vec(ncs, dim)
+            .unwrap_or_else(|_| panic!("--> X  Error: Number of configuration and number of Neurons differ!"))
+    }
+
+    /// Same as [new_vec](LifNeuron::new_vec), but returns an error instead of panicking.
+    /// 
+    /// This function can fail with [LifConfigError::ConfigCountMismatch] iff _ncs_ has a length (greater than one)
+    /// which differs from _dim_.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::lif::*;
+    /// let configs = vec![
+    ///     LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0),
+    ///     LifNeuronConfig::new(1.1, 0.4, 2.1, 0.9)
+    /// ];
+    /// 
+    /// assert_eq!(LifNeuron::try_new_vec(configs.clone(), 2).unwrap().len(), 2);
+    /// assert!(matches!(
+    ///     LifNeuron::try_new_vec(configs, 10),
+    ///     Err(LifConfigError::ConfigCountMismatch { configs: 2, neurons: 10 })
+    /// ));
+    /// ```
+    pub fn try_new_vec(ncs: Vec<LifNeuronConfig>, dim: usize) -> Result<Vec<LifNeuron>, LifConfigError> {
         let mut res: Vec<LifNeuron> = Vec::with_capacity(dim);
 
         // you can specify a single NeuronConfig block 
@@ -283,20 +465,310 @@ impl LifNeuron {
         //or you can specify an array of NeuronConfig, one for each neuron
         else {
             if ncs.len() != dim{
-                panic!("--> X  Error: Number of configuration and number of Neurons differ!")
+                return Err(LifConfigError::ConfigCountMismatch { configs: ncs.len(), neurons: dim });
             }
 
             res = ncs.iter().map(|cfg| cfg.into()).collect();
         }
 
-        res
+        Ok(res)
+    }
+
+}
+
+impl LeakyIntegrateFire {
+    /// Membrane potential of `neuron` at time `ts` after receiving `weighted_input_val`, before any reset
+    /// 
+    /// Once the decay underflows, after intervals much longer than `tau`, the membrane simply settles at `v_rest`,
+    /// so that the result is always finite, even for a null `tau`.
+    fn integrate(neuron: &LifNeuron, vars: &LifSolverVars, weighted_input_val: f64, ts: u128) -> f64 {
+        if ts == vars.ts_old {
+            return vars.v_mem + weighted_input_val;
+        }
+
+        let delta_t: f64 = (ts - vars.ts_old) as f64;
+        let decay = (-delta_t / neuron.tau).exp();
+
+        if decay > 0.0 && decay.is_finite() {
+            neuron.v_rest + (vars.v_mem - neuron.v_rest) * decay + weighted_input_val
+        } else {
+            neuron.v_rest + weighted_input_val
+        }
+    }
+
+    /// How much the threshold of `neuron` is raised above `v_threshold` at time `ts`, before any new spike
+    /// 
+    /// Like the membrane in [integrate](LeakyIntegrateFire::integrate), the raised threshold relaxes back to `v_threshold`
+    /// until the decay underflows, so that the result is always finite, even for a null `tau_threshold`.
+    fn threshold_offset(neuron: &LifNeuron, vars: &LifSolverVars, ts: u128) -> f64 {
+        if vars.threshold_offset == 0.0 || ts == vars.ts_old {
+            return vars.threshold_offset;
+        }
+
+        let decay = (-((ts - vars.ts_old) as f64) / neuron.tau_threshold).exp();
+        
+        if decay > 0.0 && decay.is_finite() {
+            vars.threshold_offset * decay
+        } else {
+            0.0
+        }
+    }
+
+    /// Whether a membrane potential of `v_mem` at time `ts` makes `neuron` reach its (possibly raised) threshold
+    fn crosses_threshold(neuron: &LifNeuron, vars: &LifSolverVars, v_mem: f64, ts: u128) -> bool {
+        v_mem > neuron.v_threshold + LeakyIntegrateFire::threshold_offset(neuron, vars, ts)
+    }
+
+    /// Whether `neuron` is still in its refractory period at time `ts`, and thus can't fire
+    fn is_refractory(neuron: &LifNeuron, vars: &LifSolverVars, ts: u128) -> bool {
+        vars.last_spike_ts.is_some_and(|last_spike_ts| ts < last_spike_ts + neuron.refractory_period)
     }
+}
+
+impl<F: Float> NN<LeakyIntegrateFire, F> {
+    /// Solve the neural network stimulated by the provided spikes, reporting the membrane potential
+    /// every neuron reached right before firing, before being reset.
+    /// 
+    /// The report has a `(layer, neuron, v_mem)` entry for every spike generated by any neuron of the network, in chronological order.
+    /// Comparing `v_mem` with the neuron's [v_threshold](LifNeuron::v_threshold) shows how far it overshot its threshold,
+    /// which the binary output of the neuron doesn't reflect.
+    /// 
+    /// The network is solved on the calling thread.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// let report = nn.overshoot_report(spikes);
+    /// 
+    /// assert_eq!(report.len(), 2);
+    /// assert_eq!((report[0].0, report[0].1), (0, 1));
+    /// assert!(report.iter().all(|&(layer, neuron, v_mem)| v_mem > nn[(layer, neuron)].v_threshold));
+    /// ```
+    pub fn overshoot_report(&self, spikes: Vec<Spike>) -> Vec<(usize, usize, f64)> {
+        let mut pipeline = Pipeline::new_observable(&self.layers);
+        let mut report = vec![];
+
+        let mut on_update = |layer_id: usize, neuron_id: usize, ts, weighted_input_val, vars: &LifSolverVars| {
+            // Null inputs leave the neuron untouched
+            if weighted_input_val != 0.0 {
+                let neuron = &self.layers[layer_id].neurons[neuron_id];
+                let v_mem = LeakyIntegrateFire::integrate(neuron, vars, weighted_input_val, ts);
+
+                if LeakyIntegrateFire::crosses_threshold(neuron, vars, v_mem, ts) && !LeakyIntegrateFire::is_refractory(neuron, vars, ts) {
+                    report.push((layer_id, neuron_id, v_mem));
+                }
+            }
+        };
+
+        for (ts, spike) in input_events(self.layers[0].neurons.len(), spikes) {
+            pipeline.feed_observed(ts, spike, |_, _, _| (), &mut on_update);
+        }
+        pipeline.finish_observed(|_, _, _| (), &mut on_update);
+
+        report
+    }
+
+    /// Solve the neural network stimulated by the provided spikes, probing the membrane potential of some of its neurons along the way.
+    /// 
+    /// Every probe is a `(layer, neuron)` pair. Along with the spikes generated by the output layer (sorted like [solve_sequential](NN::solve_sequential)),
+    /// this returns a trace for every probe, in the same order: a `(ts, v_mem)` entry for every update of the probed neuron,
+    /// where `v_mem` is its membrane potential right after the update (so after the reset, if it fired).
+    /// 
+    /// The network is solved on the calling thread.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if any probe refers to a neuron that doesn't exist.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [From::from(&LifNeuronConfig::new(0.0, 0.0, 1.0, 10.0))],
+    ///         [0.4],
+    ///         [[0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let (output, traces) = nn.solve_with_probe(Spike::spike_vec_for(0, vec![1, 2, 3, 4]), &[(0, 0)]);
+    /// 
+    /// // The membrane charges up until the neuron fires at 3, and then it's reset
+    /// assert_eq!(output, vec![Spike::new(3, 0)]);
+    /// assert_eq!(traces[0].iter().map(|(ts, _)| *ts).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    /// assert!(traces[0][0].1 < traces[0][1].1 && traces[0][2].1 == 0.0);
+    /// ```
+    pub fn solve_with_probe(&self, spikes: Vec<Spike>, probes: &[(usize, usize)]) -> (Vec<Spike>, Vec<Vec<(u128, f64)>>) {
+        assert!(
+            probes.iter().all(|&(layer, neuron)| self.get_neuron(layer, neuron).is_some()),
+            "invalid probe"
+        );
+
+        let mut pipeline = Pipeline::new_observable(&self.layers);
+        let mut traces = vec![vec![]; probes.len()];
+        let mut output = vec![];
+
+        let mut on_update = |layer_id: usize, neuron_id: usize, ts, weighted_input_val, vars: &LifSolverVars| {
+            // Null inputs leave the neuron untouched
+            if weighted_input_val == 0.0 {
+                return;
+            }
+
+            for (probe, trace) in probes.iter().zip(traces.iter_mut()) {
+                if *probe == (layer_id, neuron_id) {
+                    let neuron = &self.layers[layer_id].neurons[neuron_id];
+                    let v_mem = LeakyIntegrateFire::integrate(neuron, vars, weighted_input_val, ts);
+
+                    trace.push((ts, if LeakyIntegrateFire::crosses_threshold(neuron, vars, v_mem, ts) { neuron.v_reset } else { v_mem }));
+                }
+            }
+        };
+
+        for (ts, spike) in input_events(self.layers[0].neurons.len(), spikes) {
+            output.extend(pipeline.feed_observed(ts, spike, |_, _, _| (), &mut on_update));
+        }
+        output.extend(pipeline.finish_observed(|_, _, _| (), &mut on_update));
+
+        (output_spikes::<LeakyIntegrateFire>(output), traces)
+    }
+
+    /// Solve the neural network stimulated by the provided spikes, writing the membrane traces of all of its neurons to `w`
+    /// as a [Parquet](https://parquet.apache.org/) file.
+    /// 
+    /// The file has the `layer`, `neuron`, `ts` (all unsigned 64 bit integers) and `v_mem` (64 bit float) columns,
+    /// with a row for every update of every neuron, in chronological order.
+    /// `v_mem` is the membrane potential the neuron reached at `ts`, before being reset if it fired.
+    /// 
+    /// The network is solved on the calling thread, and the rows are written in batches as they are generated,
+    /// so the whole trace is never kept in memory.
+    /// 
+    /// Requires the `arrow` feature.
+    /// 
+    /// # Errors
+    /// 
+    /// Fails if `w` can't be written to, or if any timestamp doesn't fit in 64 bits.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer(
+    ///         [
+    ///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+    ///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+    ///         ],
+    ///         [1.5, 1.8],
+    ///         [[0.0, -0.3], [-0.2, 0.0]]
+    ///     )
+    ///     .build();
+    /// 
+    /// let spikes = Spike::create_terminal_vec(vec![
+    ///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+    ///     Spike::spike_vec_for(1, vec![2, 3, 6])
+    /// ]);
+    /// 
+    /// let mut trace = vec![];
+    /// nn.solve_traces_to_parquet(spikes, &mut trace).unwrap();
+    /// 
+    /// assert!(trace.starts_with(b"PAR1"));
+    /// ```
+    #[cfg(feature = "arrow")]
+    pub fn solve_traces_to_parquet<W: std::io::Write + Send>(&self, spikes: Vec<Spike>, w: W) -> parquet::errors::Result<()> {
+        use std::sync::Arc;
+        use arrow_array::{RecordBatch, ArrayRef, UInt64Array, Float64Array};
+        use arrow_schema::{Schema, Field, DataType};
+        use parquet::{arrow::ArrowWriter, errors::ParquetError};
+
+        /// Number of rows written to the file at a time
+        const BATCH_SIZE: usize = 8192;
+        /// Columns of the rows not written yet
+        type Rows = (Vec<u64>, Vec<u64>, Vec<u64>, Vec<f64>);
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("layer", DataType::UInt64, false),
+            Field::new("neuron", DataType::UInt64, false),
+            Field::new("ts", DataType::UInt64, false),
+            Field::new("v_mem", DataType::Float64, false)
+        ]));
+        let mut writer = ArrowWriter::try_new(w, schema.clone(), None)?;
+
+        let mut rows: Rows = Default::default();
+        let write_rows = |writer: &mut ArrowWriter<W>, rows: &mut Rows| {
+            let columns: Vec<ArrayRef> = vec![
+                Arc::new(UInt64Array::from(std::mem::take(&mut rows.0))),
+                Arc::new(UInt64Array::from(std::mem::take(&mut rows.1))),
+                Arc::new(UInt64Array::from(std::mem::take(&mut rows.2))),
+                Arc::new(Float64Array::from(std::mem::take(&mut rows.3)))
+            ];
+
+            writer.write(&RecordBatch::try_new(schema.clone(), columns)?)
+        };
 
+        let mut pipeline = Pipeline::new_observable(&self.layers);
+
+        // The first error is kept, and stops any further write
+        let mut res = Ok(());
+        let mut on_update = |layer_id: usize, neuron_id: usize, ts: u128, weighted_input_val, vars: &LifSolverVars| {
+            // Null inputs leave the neuron untouched
+            if weighted_input_val == 0.0 || res.is_err() {
+                return;
+            }
+
+            let neuron = &self.layers[layer_id].neurons[neuron_id];
+            match u64::try_from(ts) {
+                Ok(ts) => {
+                    rows.0.push(layer_id as u64);
+                    rows.1.push(neuron_id as u64);
+                    rows.2.push(ts);
+                    rows.3.push(LeakyIntegrateFire::integrate(neuron, vars, weighted_input_val, ts as u128));
+                },
+                Err(_) => res = Err(ParquetError::General(format!("timestamp {} doesn't fit in 64 bits", ts)))
+            }
+
+            if rows.0.len() >= BATCH_SIZE {
+                res = write_rows(&mut writer, &mut rows);
+            }
+        };
+
+        for (ts, spike) in input_events(self.layers[0].neurons.len(), spikes) {
+            pipeline.feed_observed(ts, spike, |_, _, _| (), &mut on_update);
+        }
+        pipeline.finish_observed(|_, _, _| (), &mut on_update);
+        res?;
+
+        if !rows.0.is_empty() {
+            write_rows(&mut writer, &mut rows)?;
+        }
+        writer.close()?;
+
+        Ok(())
+    }
 }
 
 impl LifNeuronConfig {
     /// Create a new [LifNeuronConfig], which can be used to build one or more identical neurons.
     /// 
+    /// The parameters are not validated: see [try_new](LifNeuronConfig::try_new) for that.
+    /// 
     /// # Examples
     /// 
     /// ```
@@ -315,7 +787,248 @@ impl LifNeuronConfig {
             v_rest,
             v_reset,
             v_threshold,
-            tau
+            tau,
+            refractory_period: 0,
+            threshold_adaptation: 0.0,
+            tau_threshold: 0.0
         }
     }
+
+    /// Create a new [LifNeuronConfig] like [new](LifNeuronConfig::new), validating its parameters first.
+    /// 
+    /// This function can fail with:
+    /// - [LifConfigError::NonFiniteParameter] if any of the parameters is NaN or infinite;
+    /// - [LifConfigError::NonPositiveTau] if `tau` is not positive, which would make the decay of the membrane meaningless;
+    /// - [LifConfigError::ThresholdNotAboveReset] if `v_threshold` is not greater than `v_reset`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::lif::*;
+    /// assert!(LifNeuronConfig::try_new(1.0, 0.5, 2.0, 1.0).is_ok());
+    /// 
+    /// assert_eq!(
+    ///     LifNeuronConfig::try_new(1.0, 0.5, 2.0, 0.0).unwrap_err(),
+    ///     LifConfigError::NonPositiveTau(0.0)
+    /// );
+    /// ```
+    pub fn try_new(
+        v_rest: f64,
+        v_reset: f64,
+        v_threshold: f64,
+        tau: f64
+    ) -> Result<LifNeuronConfig, LifConfigError>
+    {
+        for (name, value) in [("v_rest", v_rest), ("v_reset", v_reset), ("v_threshold", v_threshold), ("tau", tau)] {
+            if !value.is_finite() {
+                return Err(LifConfigError::NonFiniteParameter(name));
+            }
+        }
+
+        if tau <= 0.0 {
+            return Err(LifConfigError::NonPositiveTau(tau));
+        }
+
+        if v_threshold <= v_reset {
+            return Err(LifConfigError::ThresholdNotAboveReset { v_threshold, v_reset });
+        }
+
+        Ok(LifNeuronConfig::new(v_rest, v_reset, v_threshold, tau))
+    }
+
+    /// Set the absolute refractory period of the neurons built from this config, which is 0 by default.
+    /// 
+    /// After firing at `ts`, a neuron can't fire again before `ts + refractory_period`: inputs received in the meantime
+    /// are still integrated, but the membrane is reset without generating a spike if it reaches the threshold.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::lif::*;
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0).with_refractory_period(3);
+    /// let neuron = LifNeuron::new(&config);
+    /// 
+    /// assert_eq!(neuron.refractory_period, 3);
+    /// ```
+    pub fn with_refractory_period(mut self, refractory_period: u128) -> Self {
+        self.refractory_period = refractory_period;
+        self
+    }
+
+    /// Make the threshold of the neurons built from this config adaptive, which models spike-frequency adaptation.
+    /// 
+    /// Every time a neuron fires, its threshold is raised by `threshold_adaptation`, and then it relaxes exponentially
+    /// back to `v_threshold` with time constant `tau_threshold`. Raises accumulate, so a neuron firing at a high rate
+    /// takes longer and longer to fire again.
+    /// 
+    /// Both parameters are 0 by default, which leaves the threshold fixed.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+    /// let config = LifNeuronConfig::new(0.0, 0.0, 1.0, 5.0).with_threshold_adaptation(1.0, 20.0);
+    /// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer([From::from(&config)], [0.6], [[0.0]])
+    ///     .build();
+    /// 
+    /// let output = nn.solve_sequential(Spike::spike_vec_for(0, (1..=30).collect()));
+    /// let isis = output.windows(2).map(|w| w[1].ts - w[0].ts).collect::<Vec<_>>();
+    /// 
+    /// // Without adaptation, the neuron would fire every other input
+    /// assert_eq!(output[0].ts, 2);
+    /// assert!(isis.windows(2).all(|w| w[0] <= w[1]) && isis.last() > isis.first());
+    /// ```
+    pub fn with_threshold_adaptation(mut self, threshold_adaptation: f64, tau_threshold: f64) -> Self {
+        self.threshold_adaptation = threshold_adaptation;
+        self.tau_threshold = tau_threshold;
+        self
+    }
+
+    /// Create a new [LifNeuronConfig] from physical quantities.
+    ///
+    /// Potentials are expressed in mV and are used as they are, so that 1.0 in the model (e.g. the weight of a synapse) is 1 mV.
+    /// Time is expressed in ms: `dt_ms` is the duration of a single step of the `u128` timestamps of [Spike](crate::Spike)s,
+    /// so the time constant of the membrane becomes `tau_ms / dt_ms` timestamp steps.
+    ///
+    /// Note that, unlike [new](LifNeuronConfig::new), the threshold potential comes before the reset one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt_ms` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::lif::*;
+    /// // A typical cortical neuron, simulated with a resolution of 0.5 ms
+    /// let config = LifNeuronConfig::from_physical(-65.0, -50.0, -70.0, 20.0, 0.5);
+    /// let neuron = LifNeuron::new(&config);
+    ///
+    /// assert_eq!(neuron.v_threshold, -50.0);
+    /// assert_eq!(neuron.tau, 40.0);
+    /// ```
+    pub fn from_physical(v_rest_mv: f64, v_thresh_mv: f64, v_reset_mv: f64, tau_ms: f64, dt_ms: f64) -> LifNeuronConfig {
+        assert!(dt_ms > 0.0, "the timestamp step must be positive");
+
+        LifNeuronConfig::new(v_rest_mv, v_reset_mv, v_thresh_mv, tau_ms / dt_ms)
+    }
+
+    /// Convert this config back to physical quantities, given the duration in ms of a timestamp step, as in [from_physical](LifNeuronConfig::from_physical).
+    ///
+    /// Returns `(v_rest_mv, v_thresh_mv, v_reset_mv, tau_ms)`.
+    /// The refractory period and the threshold adaptation are not included.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt_ms` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::lif::*;
+    /// let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 4.0);
+    ///
+    /// assert_eq!(config.to_physical(0.25), (1.0, 2.0, 0.5, 1.0));
+    /// ```
+    pub fn to_physical(&self, dt_ms: f64) -> (f64, f64, f64, f64) {
+        assert!(dt_ms > 0.0, "the timestamp step must be positive");
+
+        (self.v_rest, self.v_threshold, self.v_reset, self.tau * dt_ms)
+    }
+
+    /// Create a new [LifNeuronConfigBuilder], to set the parameters of a [LifNeuronConfig] by name instead of by position.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::lif::*;
+    /// let config = LifNeuronConfig::builder()
+    ///     .v_rest(1.0)
+    ///     .v_reset(0.5)
+    ///     .v_threshold(2.0)
+    ///     .build();
+    /// 
+    /// assert_eq!(config, LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0));
+    /// ```
+    pub fn builder() -> LifNeuronConfigBuilder {
+        LifNeuronConfigBuilder::default()
+    }
+}
+
+/// Helper type that implements the builder pattern for [LifNeuronConfig], see [LifNeuronConfig::builder].
+/// 
+/// Every parameter is set by name, so that adjacent parameters like `v_rest` and `v_reset` can't be swapped by mistake.
+/// Parameters that are not set keep their default value: `v_rest`, `v_reset` and `v_threshold` are 0, 0 and 1 (a normalized neuron),
+/// `tau` is 1, and the refractory period and the threshold adaptation are disabled, like in [LifNeuronConfig::new].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LifNeuronConfigBuilder {
+    /// Config being built
+    config: LifNeuronConfig
+}
+
+impl Default for LifNeuronConfigBuilder {
+    fn default() -> Self {
+        Self { config: LifNeuronConfig::new(0.0, 0.0, 1.0, 1.0) }
+    }
+}
+
+impl LifNeuronConfigBuilder {
+    /// Set the rest potential
+    pub fn v_rest(mut self, v_rest: f64) -> Self {
+        self.config.v_rest = v_rest;
+        self
+    }
+
+    /// Set the reset potential
+    pub fn v_reset(mut self, v_reset: f64) -> Self {
+        self.config.v_reset = v_reset;
+        self
+    }
+
+    /// Set the threshold potential
+    pub fn v_threshold(mut self, v_threshold: f64) -> Self {
+        self.config.v_threshold = v_threshold;
+        self
+    }
+
+    /// Set the membrane's time constant
+    pub fn tau(mut self, tau: f64) -> Self {
+        self.config.tau = tau;
+        self
+    }
+
+    /// Set the absolute refractory period, see [LifNeuronConfig::with_refractory_period]
+    pub fn refractory_period(mut self, refractory_period: u128) -> Self {
+        self.config = self.config.with_refractory_period(refractory_period);
+        self
+    }
+
+    /// Make the threshold adaptive, see [LifNeuronConfig::with_threshold_adaptation]
+    pub fn threshold_adaptation(mut self, threshold_adaptation: f64, tau_threshold: f64) -> Self {
+        self.config = self.config.with_threshold_adaptation(threshold_adaptation, tau_threshold);
+        self
+    }
+
+    /// Build the [LifNeuronConfig], without validating its parameters
+    pub fn build(self) -> LifNeuronConfig {
+        self.config
+    }
+
+    /// Build the [LifNeuronConfig], validating its parameters like [LifNeuronConfig::try_new] does
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use pds_spiking_nn::lif::*;
+    /// let config = LifNeuronConfig::builder().v_reset(0.5).v_threshold(0.2).try_build();
+    /// 
+    /// assert_eq!(config, Err(LifConfigError::ThresholdNotAboveReset { v_threshold: 0.2, v_reset: 0.5 }));
+    /// ```
+    pub fn try_build(self) -> Result<LifNeuronConfig, LifConfigError> {
+        let LifNeuronConfig { v_rest, v_reset, v_threshold, tau, .. } = self.config;
+        LifNeuronConfig::try_new(v_rest, v_reset, v_threshold, tau)?;
+
+        Ok(self.config)
+    }
 }
@@ -1,6 +1,10 @@
-//! Main `Model` trait for expanding this library to work with other models. Leaky integrate and fire is built in.
+//! Main `Model` trait for expanding this library to work with other models. Leaky integrate and fire, adaptive exponential integrate and fire, conductance-based leaky integrate and fire and stochastic leaky integrate and fire are built in, and they can be mixed in the same network through the hybrid model.
 
 pub mod lif;
+pub mod adex;
+pub mod coba;
+pub mod stochastic;
+pub mod hybrid;
 
 use std::fmt::Debug;
 
@@ -8,7 +12,7 @@ use std::fmt::Debug;
 pub trait Model: 'static + Debug + Clone {
     /// A struct for a single Neuron of the SNN.
     /// Each Neuron has its own parameters such as _current membrane tension_, _threshold tension_ etc...
-    type Neuron: 'static + Sized + Clone + Sync + RefInto<Self::SolverVars>;
+    type Neuron: 'static + Sized + Clone + Send + Sync + RefInto<Self::SolverVars>;
 
     /// Contains the dynamic variables for each Neuron used by the solver
     type SolverVars: Default + Send + Sync;
@@ -16,13 +20,55 @@ pub trait Model: 'static + Debug + Clone {
     /// Helper type to build neurons
     type Config: RefInto<Self::Neuron>;
 
+    /// Output of [handle_spike](Model::handle_spike) above which a neuron is considered to have fired.
+    /// 
+    /// Every solver compares the outputs against this value, both to decide whether a layer fired and to extract its spikes.
+    /// The default of 0.5 suits binary models; models with graded outputs can pick their own.
+    const FIRING_THRESHOLD: f64 = 0.5;
+
     /// Receive the incoming spike and update the vars for the given neuron.
     /// 
     /// _weighted_input_vals_ is the sum of every input weight to the neuron that is spiking.
     /// 
-    /// This function must return either 1.0 in case the neuron generated a spike, or 0.0 otherwise.
+    /// This function returns the output of the neuron, which is considered a spike if it's greater than [FIRING_THRESHOLD](Model::FIRING_THRESHOLD):
+    /// binary models return either 1.0 in case the neuron generated a spike, or 0.0 otherwise.
+    /// When at least one neuron of a layer spikes, the outputs of all of its neurons are propagated through the synapses as they are,
+    /// so models with graded outputs are weighted by their actual value.
     fn handle_spike(neuron: &Self::Neuron, vars: &mut Self::SolverVars, weighted_input_val: f64, ts: u128) -> f64;
 
+    /// Read the membrane potential of a neuron from its solver variables, so that tools can inspect any model generically.
+    /// 
+    /// This is the potential as of the last input handled by [handle_spike](Model::handle_spike) (after any reset),
+    /// without the decay it may have undergone since then. The default implementation returns 0, for models without a membrane.
+    #[allow(unused_variables)]
+    fn membrane_potential(vars: &Self::SolverVars) -> f64 {
+        0.0
+    }
+
+    /// Export the state variables of a neuron as a list of `(name, value)` pairs, so that they can be logged
+    /// or inspected without knowing the model (e.g. `[("v_mem", 0.7), ("threshold_offset", 0.1)]` for LIF).
+    /// 
+    /// Names and order are fixed for a given model. Like [membrane_potential](Model::membrane_potential),
+    /// the values are as of the last input handled by [handle_spike](Model::handle_spike).
+    /// The default implementation exports no variables.
+    #[allow(unused_variables)]
+    fn state(vars: &Self::SolverVars) -> Vec<(&'static str, f64)> {
+        vec![]
+    }
+
+    /// Randomly perturb the parameters of `neuron`, adding to each of them a sample of `noise`, as done by [NN::perturbed](crate::NN::perturbed).
+    /// 
+    /// `noise` yields a new sample every time it's called. Models choose which of their parameters are perturbed,
+    /// and keep the ones that must be positive (e.g. time constants) so. The default implementation leaves the neuron unchanged.
+    #[allow(unused_variables)]
+    fn perturb(neuron: &mut Self::Neuron, noise: impl FnMut() -> f64) { }
+
+    /// Shift the membrane potential `neuron` starts from by `offset`, as done by [NNBuilder::with_random_init](crate::NNBuilder::with_random_init).
+    /// 
+    /// Offsets add up. The default implementation ignores them, for models without a membrane.
+    #[allow(unused_variables)]
+    fn offset_initial_potential(neuron: &mut Self::Neuron, offset: f64) { }
+
     /// Structure that's responsible for 4 consecutive neurons of the same layer
     #[cfg(feature = "simd")]
     type Neuronx4: Send;
@@ -52,6 +98,15 @@ pub trait Model: 'static + Debug + Clone {
     fn handle_spike_x4(neurons: &Self::Neuronx4, vars: &mut Self::SolverVarsx4, weighted_input_val: packed_simd::f64x4, ts: u128) -> packed_simd::f64x4;
 }
 
+/// Whether `output`, as returned by [handle_spike](Model::handle_spike) of `M`, is a spike.
+/// 
+/// Every solver goes through this, both to decide whether a layer fired and to extract the spikes from its output,
+/// so that the two can never disagree.
+#[inline]
+pub(crate) fn fired<M: Model>(output: f64) -> bool {
+    output > M::FIRING_THRESHOLD
+}
+
 /// A type is [RefInto<T>] if its reference can be converted to `T`.
 /// 
 /// Unfortunately, the Rust compiler currently has trouble keeping track of bounds of this kind,
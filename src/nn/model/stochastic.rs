@@ -0,0 +1,297 @@
+//! Implementation of a Leaky Integrate and Fire model with stochastic firing
+//!
+//! The membrane of a stochastic LIF neuron evolves like the one of a [lif](crate::lif) neuron, but it has no hard threshold:
+//! every time it receives an input, the neuron fires with probability
+//!
+//! ```text
+//! p = 1 / (1 + exp(-(v_mem - v_threshold) / temperature))
+//! ```
+//!
+//! so it's likely to fire once its potential is above the threshold, and it may still fire a little below it.
+//! The lower the `temperature`, the sharper the sigmoid: a null temperature gives back the hard threshold.
+//!
+//! The random draws are made from a generator held in the [StochasticLifSolverVars] of the neuron, which is seeded with its
+//! [seed](StochasticLifNeuron::seed) at the start of every solve, so a network always generates the same spikes for the same input,
+//! whatever the solver. Neurons with the same seed make the same draws: see [StochasticLifNeuron::new_vec] to seed them apart.
+
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use crate::Model;
+
+/// A single stochastic LIF neuron, see the [module](self) documentation for the meaning of its parameters.
+///
+/// # Examples
+///
+/// ```
+/// # use pds_spiking_nn::stochastic::*;
+/// let neuron = StochasticLifNeuron::new(&StochasticLifNeuronConfig { seed: 7, ..Default::default() });
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StochasticLifNeuron {
+    /// Rest potential
+    pub v_rest: f64,
+    /// Reset potential
+    pub v_reset: f64,
+    /// Threshold potential, at which the neuron fires with probability 1/2
+    pub v_threshold: f64,
+    /// Membrane's time constant
+    pub tau: f64,
+    /// Width of the sigmoid of the firing probability
+    pub temperature: f64,
+    /// Seed of the random generator of the neuron
+    pub seed: u64,
+    /// Offset of the initial membrane potential from `v_rest`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub v_init_offset: f64
+}
+
+/// A struct with variables only used in simulation (solve), including the random generator of the neuron
+#[derive(Clone, Debug)]
+pub struct StochasticLifSolverVars {
+    v_mem: f64,
+    ts_old: u128,
+    rng: Pcg64Mcg
+}
+
+impl Default for StochasticLifSolverVars {
+    fn default() -> Self {
+        Self {
+            v_mem: 0.0,
+            ts_old: 0,
+            rng: Pcg64Mcg::seed_from_u64(0)
+        }
+    }
+}
+
+impl From<&StochasticLifNeuron> for StochasticLifSolverVars {
+    fn from(neuron: &StochasticLifNeuron) -> Self {
+        Self {
+            v_mem: neuron.v_rest + neuron.v_init_offset,
+            ts_old: 0,
+            rng: Pcg64Mcg::seed_from_u64(neuron.seed)
+        }
+    }
+}
+
+impl StochasticLifSolverVars {
+    /// Get the variables only used in simulation (solve) -> (v_mem, ts_old)
+    pub fn get_vars(&self) -> (f64, u128) {
+        (self.v_mem, self.ts_old)
+    }
+}
+
+/// A struct used to create a specific configuration, simply reusable for other neurons.
+///
+/// The [Default] configuration has a rest (and reset) potential of 0, a threshold of 1, a unit time constant and a temperature of 0.1.
+///
+/// # Examples
+///
+/// ```
+/// # use pds_spiking_nn::stochastic::*;
+/// // A neuron that fires almost deterministically
+/// let config = StochasticLifNeuronConfig { temperature: 0.01, ..Default::default() };
+///
+/// let neuron: StochasticLifNeuron = From::from(&config);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StochasticLifNeuronConfig {
+    /// Rest potential
+    pub v_rest: f64,
+    /// Reset potential
+    pub v_reset: f64,
+    /// Threshold potential
+    pub v_threshold: f64,
+    /// Membrane's time constant
+    pub tau: f64,
+    /// Width of the sigmoid of the firing probability
+    pub temperature: f64,
+    /// Seed of the random generator of the neuron
+    pub seed: u64
+}
+
+impl Default for StochasticLifNeuronConfig {
+    fn default() -> Self {
+        Self {
+            v_rest: 0.0,
+            v_reset: 0.0,
+            v_threshold: 1.0,
+            tau: 1.0,
+            temperature: 0.1,
+            seed: 0
+        }
+    }
+}
+
+impl From<&StochasticLifNeuronConfig> for StochasticLifNeuron {
+    fn from(config: &StochasticLifNeuronConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+/// Simd aggregate of four [StochasticLifNeuron]s.
+///
+/// Every neuron draws from its own generator, so the four neurons are solved one at a time.
+#[cfg(feature = "simd")]
+pub struct StochasticLifNeuronx4([StochasticLifNeuron; 4]);
+
+/// Simd aggregate of four [StochasticLifSolverVars]
+#[cfg(feature = "simd")]
+pub struct StochasticLifSolverVarsx4([StochasticLifSolverVars; 4]);
+
+/// The Leaky Integrate and Fire model with stochastic firing.
+///
+/// Use this empty type to construct stochastic LIF NNs, see the documentation at [NNBuilder](crate::NNBuilder) for details.
+#[derive(Clone, Copy, Debug)]
+pub struct StochasticLif;
+
+impl Model for StochasticLif {
+    type Neuron = StochasticLifNeuron;
+    type SolverVars = StochasticLifSolverVars;
+    type Config = StochasticLifNeuronConfig;
+
+    /// Let the membrane potential of `neuron` decay until `ts`, add the input `weighted_input_val`, then draw whether the neuron fires.
+    ///
+    /// The output of this function is 1.0 iff the neuron has generated a new spike at time `ts`, or 0.0 otherwise.
+    /// Null inputs are ignored, without any draw.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{Model, stochastic::*};
+    /// let neuron = StochasticLifNeuron::new(&StochasticLifNeuronConfig::default());
+    /// let mut vars = From::from(&neuron);
+    ///
+    /// // Far above the threshold, the neuron fires for sure
+    /// assert_eq!(StochasticLif::handle_spike(&neuron, &mut vars, 5.0, 1), 1.0);
+    /// ```
+    #[inline]
+    fn handle_spike(neuron: &StochasticLifNeuron, vars: &mut StochasticLifSolverVars, weighted_input_val: f64, ts: u128) -> f64 {
+        // This early exit serves as a small optimization, and keeps the draws independent of the updates with no input
+        if weighted_input_val == 0.0 { return 0.0 }
+
+        let decay = (-((ts - vars.ts_old) as f64) / neuron.tau).exp();
+        vars.v_mem = neuron.v_rest + (vars.v_mem - neuron.v_rest) * decay + weighted_input_val;
+        vars.ts_old = ts;
+
+        if vars.rng.gen::<f64>() < neuron.firing_probability(vars.v_mem) {
+            vars.v_mem = neuron.v_reset;
+            1.
+        } else {
+            0.
+        }
+    }
+
+    #[inline]
+    fn membrane_potential(vars: &StochasticLifSolverVars) -> f64 {
+        vars.v_mem
+    }
+
+    fn state(vars: &StochasticLifSolverVars) -> Vec<(&'static str, f64)> {
+        vec![("v_mem", vars.v_mem)]
+    }
+
+    /// Perturb every potential, the time constant and the temperature, reflecting the last two to keep them positive
+    fn perturb(neuron: &mut StochasticLifNeuron, mut noise: impl FnMut() -> f64) {
+        neuron.v_rest += noise();
+        neuron.v_reset += noise();
+        neuron.v_threshold += noise();
+        neuron.tau = (neuron.tau + noise()).abs();
+        neuron.temperature = (neuron.temperature + noise()).abs();
+    }
+
+    fn offset_initial_potential(neuron: &mut StochasticLifNeuron, offset: f64) {
+        neuron.v_init_offset += offset;
+    }
+
+    #[cfg(feature = "simd")]
+    type Neuronx4 = StochasticLifNeuronx4;
+    #[cfg(feature = "simd")]
+    type SolverVarsx4 = StochasticLifSolverVarsx4;
+
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn neuron_x4_from_neurons(neurons: &[StochasticLifNeuron]) -> StochasticLifNeuronx4 {
+        StochasticLifNeuronx4([neurons[0].clone(), neurons[1].clone(), neurons[2].clone(), neurons[3].clone()])
+    }
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn vars_x4_from_vars(vars: &[StochasticLifSolverVars]) -> StochasticLifSolverVarsx4 {
+        StochasticLifSolverVarsx4([vars[0].clone(), vars[1].clone(), vars[2].clone(), vars[3].clone()])
+    }
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn handle_spike_x4(neurons: &Self::Neuronx4, vars: &mut Self::SolverVarsx4, weighted_input_vals: packed_simd::f64x4, ts: u128) -> packed_simd::f64x4 {
+        let mut output = |i: usize| StochasticLif::handle_spike(&neurons.0[i], &mut vars.0[i], weighted_input_vals.extract(i), ts);
+
+        packed_simd::f64x4::new(output(0), output(1), output(2), output(3))
+    }
+}
+
+impl StochasticLifNeuron {
+    /// Create a new [StochasticLifNeuron] from a reference to a [StochasticLifNeuronConfig].
+    ///
+    /// The same conversion can be obtained via the impl of `From<&StochasticLifNeuronConfig> for StochasticLifNeuron`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the time constant is not positive, or if the temperature is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::stochastic::*;
+    /// let neuron = StochasticLifNeuron::new(&StochasticLifNeuronConfig { tau: 2.0, ..Default::default() });
+    ///
+    /// assert_eq!(neuron.tau, 2.0);
+    /// ```
+    pub fn new(config: &StochasticLifNeuronConfig) -> StochasticLifNeuron {
+        assert!(config.tau > 0.0, "the membrane time constant must be positive");
+        assert!(config.temperature >= 0.0, "the temperature can't be negative");
+
+        StochasticLifNeuron {
+            v_rest: config.v_rest,
+            v_reset: config.v_reset,
+            v_threshold: config.v_threshold,
+            tau: config.tau,
+            temperature: config.temperature,
+            seed: config.seed,
+            v_init_offset: 0.0
+        }
+    }
+
+    /// Create `dim` neurons from `config`, with consecutive seeds starting from the one of `config`,
+    /// so that they don't make the same draws.
+    ///
+    /// Use configurations with different seeds for different layers, for their neurons not to share their seeds either.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::stochastic::*;
+    /// let neurons = StochasticLifNeuron::new_vec(&StochasticLifNeuronConfig { seed: 10, ..Default::default() }, 3);
+    ///
+    /// assert_eq!(neurons.iter().map(|neuron| neuron.seed).collect::<Vec<_>>(), vec![10, 11, 12]);
+    /// ```
+    pub fn new_vec(config: &StochasticLifNeuronConfig, dim: usize) -> Vec<StochasticLifNeuron> {
+        (0..dim as u64)
+            .map(|i| StochasticLifNeuron::new(&StochasticLifNeuronConfig { seed: config.seed.wrapping_add(i), ..config.clone() }))
+            .collect()
+    }
+
+    /// Probability that the neuron fires with its membrane at potential `v_mem`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::stochastic::*;
+    /// let neuron = StochasticLifNeuron::new(&StochasticLifNeuronConfig::default());
+    ///
+    /// assert_eq!(neuron.firing_probability(1.0), 0.5);
+    /// assert!(neuron.firing_probability(0.5) < 0.01);
+    /// ```
+    pub fn firing_probability(&self, v_mem: f64) -> f64 {
+        1.0 / (1.0 + (-(v_mem - self.v_threshold) / self.temperature).exp())
+    }
+}
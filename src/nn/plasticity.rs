@@ -0,0 +1,204 @@
+//! Synaptic plasticity rules, used to train a [NN] online with [solve_with_learning](NN::solve_with_learning).
+
+use ndarray::Array2;
+use crate::{Model, NN, Spike, Float};
+use super::{sequential::input_events, model::fired, layer::weigh};
+
+/// A spike-timing-dependent plasticity rule, which adjusts the weight of a synapse
+/// according to the timing of the spikes of the neurons at its two ends.
+pub trait Plasticity {
+    /// Compute the new weight of a synapse currently weighing `weight`,
+    /// given a spike of its presynaptic neuron at `pre_ts` and one of its postsynaptic neuron at `post_ts`.
+    fn update(&self, weight: f64, pre_ts: u128, post_ts: u128) -> f64;
+}
+
+/// Classic pair-based STDP rule with exponential timing windows.
+///
+/// A presynaptic spike preceding (or coinciding with) a postsynaptic one by `Δt` increases the weight by `a_plus * exp(-Δt / tau_plus)`,
+/// while a presynaptic spike following a postsynaptic one by `Δt` decreases it by `a_minus * exp(-Δt / tau_minus)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExponentialStdp {
+    /// Maximum potentiation of a synapse
+    pub a_plus: f64,
+    /// Maximum depression of a synapse
+    pub a_minus: f64,
+    /// Time constant of the potentiation window
+    pub tau_plus: f64,
+    /// Time constant of the depression window
+    pub tau_minus: f64
+}
+
+impl ExponentialStdp {
+    /// Create a new [ExponentialStdp] rule
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::plasticity::{ExponentialStdp, Plasticity};
+    /// let stdp = ExponentialStdp::new(0.1, 0.12, 20.0, 20.0);
+    ///
+    /// assert!(stdp.update(1.0, 3, 5) > 1.0);
+    /// assert!(stdp.update(1.0, 5, 3) < 1.0);
+    /// ```
+    pub fn new(a_plus: f64, a_minus: f64, tau_plus: f64, tau_minus: f64) -> Self {
+        Self { a_plus, a_minus, tau_plus, tau_minus }
+    }
+}
+
+impl Plasticity for ExponentialStdp {
+    fn update(&self, weight: f64, pre_ts: u128, post_ts: u128) -> f64 {
+        if pre_ts <= post_ts {
+            weight + self.a_plus * (-((post_ts - pre_ts) as f64) / self.tau_plus).exp()
+        } else {
+            weight - self.a_minus * (-((pre_ts - post_ts) as f64) / self.tau_minus).exp()
+        }
+    }
+}
+
+impl<M: Model, F: Float> NN<M, F> where for<'a> &'a M::Neuron: Into<M::SolverVars> {
+    /// Solve the neural network stimulated by the provided spikes like [solve](NN::solve),
+    /// while adjusting the weights of the synapses between consecutive layers according to `rule`.
+    ///
+    /// Every time a neuron fires, the synapses from the previous layer are updated pairing its spike with the latest spike of each presynaptic neuron;
+    /// every time a spike reaches a layer, its synapses are updated pairing it with the latest spike of each postsynaptic neuron.
+    /// The new weights take effect immediately, for the rest of the solve.
+    ///
+    /// The input weights of the entry layer, the intra-layer weights and the weights of the skip connections are left untouched.
+    /// The network is solved on the calling thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any layer has [delays](crate::NNBuilder::layer_with_delays) on its synapses, which aren't supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pds_spiking_nn::{NNBuilder, Spike, lif::*, plasticity::ExponentialStdp};
+    /// let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    ///
+    /// let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+    ///     .layer([From::from(&config)], [1.0], [[0.0]])
+    ///     .layer([From::from(&config)], [[1.0]], [[0.0]])
+    ///     .build();
+    ///
+    /// let output = nn.solve_with_learning(
+    ///     Spike::spike_vec_for(0, vec![1, 4]),
+    ///     &ExponentialStdp::new(0.1, 0.12, 20.0, 20.0)
+    /// );
+    ///
+    /// assert_eq!(output, vec![vec![1, 4]]);
+    /// assert!(nn[((0, 0), (1, 0))] > 1.0);
+    /// ```
+    pub fn solve_with_learning(&mut self, spikes: Vec<Spike>, rule: &impl Plasticity) -> Vec<Vec<u128>> {
+        assert!(
+            self.layers.iter().all(|layer| layer.input_delays.is_none() && layer.intra_delays.is_none()),
+            "delayed synapses are not supported while learning"
+        );
+
+        let mut vars = self.layers
+            .iter()
+            .map(|layer| layer.neurons.iter().map(|neuron| neuron.into()).collect::<Vec<M::SolverVars>>())
+            .collect::<Vec<_>>();
+        let mut last_spikes = self.layers
+            .iter()
+            .map(|layer| vec![None; layer.neurons.len()])
+            .collect::<Vec<Vec<Option<u128>>>>();
+        let mut res = vec![vec![]; self.layers.last().unwrap().neurons.len()];
+        let has_skips = self.layers.iter().any(|layer| !layer.skips.is_empty());
+
+        for (ts, spike) in input_events(self.layers[0].neurons.len(), spikes) {
+            let mut current = vec![spike];
+            // Outputs of every layer so far, only kept for the skip connections
+            let mut outputs: Vec<Vec<Array2<f64>>> = vec![];
+
+            for layer_id in 0..self.layers.len() {
+                let mut next = vec![];
+
+                // Like the other solvers, the inputs of the skip connections are applied along with the first spike reaching the layer,
+                // or on their own if there's none
+                let mut skip_inputs = self.layers[layer_id].skips.iter()
+                    .filter_map(|(back, weights)| outputs.len().checked_sub(*back).map(|i| (&outputs[i], weights)))
+                    .flat_map(|(source, weights)| source.iter().map(|output| weigh(output, weights)))
+                    .reduce(|sum, inputs| sum + inputs);
+                let mut events = current.iter().map(Some).collect::<Vec<_>>();
+                if events.is_empty() && skip_inputs.is_some() {
+                    events.push(None);
+                }
+
+                for spike in events {
+                    if let (Some(spike), true) = (spike, layer_id > 0) {
+                        depress::<M, _>(&mut self.layers[layer_id].input_weights, spike, ts, &last_spikes[layer_id], rule);
+                    }
+
+                    let layer = &self.layers[layer_id];
+                    let mut weighted_inputs = spike.map_or_else(|| Array2::zeros((1, layer.neurons.len())), |spike| layer.weigh_inputs(spike));
+                    if let Some(skip_inputs) = skip_inputs.take() {
+                        weighted_inputs += &skip_inputs;
+                    }
+
+                    loop {
+                        let output = Array2::from_shape_fn((1, layer.neurons.len()), |(_, neuron_id)| {
+                            M::handle_spike(&layer.neurons[neuron_id], &mut vars[layer_id][neuron_id], weighted_inputs[(0, neuron_id)], ts)
+                        });
+
+                        if output.iter().all(|o| !fired::<M>(*o)) {
+                            break;
+                        }
+
+                        for (neuron_id, _) in output.iter().enumerate().filter(|(_, o)| fired::<M>(**o)) {
+                            last_spikes[layer_id][neuron_id] = Some(ts);
+                        }
+                        weighted_inputs = layer.weigh_intra(&output);
+                        next.push(output);
+                    }
+                }
+
+                for output in &next {
+                    if layer_id > 0 {
+                        potentiate::<M, _>(&mut self.layers[layer_id].input_weights, output, ts, &last_spikes[layer_id - 1], rule);
+                    }
+                }
+
+                if has_skips {
+                    outputs.push(next.clone());
+                }
+
+                current = next;
+                if current.is_empty() && !has_skips {
+                    break;
+                }
+            }
+
+            for output in &current {
+                for (neuron_id, _) in output.iter().enumerate().filter(|(_, o)| fired::<M>(**o)) {
+                    res[neuron_id].push(ts);
+                }
+            }
+        }
+
+        res
+    }
+}
+
+/// Update the `weights` of the synapses carrying `spike` at time `ts`, pairing it with the latest spike of every postsynaptic neuron
+fn depress<M: Model, F: Float>(weights: &mut Array2<F>, spike: &Array2<f64>, ts: u128, post_spikes: &[Option<u128>], rule: &impl Plasticity) {
+    for (pre, _) in spike.iter().enumerate().filter(|(_, s)| fired::<M>(**s)) {
+        for (post, post_ts) in post_spikes.iter().enumerate() {
+            if let Some(post_ts) = post_ts.filter(|post_ts| *post_ts < ts) {
+                weights[(pre, post)] = F::narrow(rule.update(weights[(pre, post)].widen(), ts, post_ts));
+            }
+        }
+    }
+}
+
+/// Update the `weights` of the synapses leading to the neurons that fired in `output` at time `ts`,
+/// pairing their spike with the latest spike of every presynaptic neuron
+fn potentiate<M: Model, F: Float>(weights: &mut Array2<F>, output: &Array2<f64>, ts: u128, pre_spikes: &[Option<u128>], rule: &impl Plasticity) {
+    for (post, _) in output.iter().enumerate().filter(|(_, o)| fired::<M>(**o)) {
+        for (pre, pre_ts) in pre_spikes.iter().enumerate() {
+            if let Some(pre_ts) = pre_ts {
+                weights[(pre, post)] = F::narrow(rule.update(weights[(pre, post)].widen(), *pre_ts, ts));
+            }
+        }
+    }
+}
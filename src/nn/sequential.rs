@@ -0,0 +1,203 @@
+//! Single-threaded counterpart of the layer pipeline run by [NN::solve](crate::NN::solve).
+//!
+//! A [Pipeline] chains the [LayerManager]s of consecutive layers together, so that a single
+//! input event can be pushed through all of them on the calling thread.
+//! This is useful whenever the solve needs to be observed or interrupted (e.g. to stop at the first output spike),
+//! and it's also the unit of work of every thread of the parallel solver.
+
+use ndarray::Array2;
+use crate::{sync::{LayerManager, LayerState}, Model, Spike};
+use super::model::fired;
+use super::{layer::Layer, Float};
+
+/// Chain of [LayerManager]s, one for each of a sequence of consecutive layers
+pub(crate) struct Pipeline<'a, M: Model, F: Float> {
+    managers: Vec<LayerManager<'a, M, F>>,
+    /// Whether any layer has skip connections, so that the outputs of every layer must be kept for the later ones
+    has_skips: bool
+}
+
+impl<'a, M: Model, F: Float> Pipeline<'a, M, F> where for<'b> &'b M::Neuron: Into<M::SolverVars> {
+    /// Build a new [Pipeline] for the given consecutive layers, with every neuron in its initial state
+    pub fn new(layers: &'a [Layer<M, F>]) -> Self {
+        Self::with_managers(layers, LayerManager::new)
+    }
+
+    /// Build a new [Pipeline] like [new](Pipeline::new), but whose neurons can all be observed by [feed_observed](Pipeline::feed_observed)
+    pub fn new_observable(layers: &'a [Layer<M, F>]) -> Self {
+        Self::with_managers(layers, LayerManager::new_scalar)
+    }
+
+    /// Build a new [Pipeline] like [new](Pipeline::new), but which only updates the neurons receiving a nonzero input,
+    /// see [LayerManager::new_event_driven]
+    pub fn new_event_driven(layers: &'a [Layer<M, F>]) -> Self {
+        Self::with_managers(layers, LayerManager::new_event_driven)
+    }
+
+    /// Build a new [Pipeline] like [new_observable](Pipeline::new_observable), resuming the solve from the `states` of its layers
+    /// returned by [into_states](Pipeline::into_states)
+    pub fn resume(layers: &'a [Layer<M, F>], states: Vec<LayerState<M>>) -> Self {
+        let mut states = states.into_iter();
+
+        Self::with_managers(layers, |layer| LayerManager::resume(layer, states.next().unwrap()))
+    }
+
+    /// Take the state of every layer out of this pipeline, which must have been built by [new_observable](Pipeline::new_observable)
+    /// (or [resume](Pipeline::resume)), to resume the solve later
+    pub fn into_states(self) -> Vec<LayerState<M>> {
+        self.managers.into_iter().map(LayerManager::into_state).collect()
+    }
+
+    /// Build a new [Pipeline] for the given consecutive layers, with a [LayerManager] built by `manager` for each of them
+    fn with_managers(layers: &'a [Layer<M, F>], manager: impl FnMut(&'a Layer<M, F>) -> LayerManager<'a, M, F>) -> Self {
+        Self {
+            managers: layers.iter().map(manager).collect(),
+            has_skips: layers.iter().any(|layer| !layer.skips.is_empty())
+        }
+    }
+
+    /// Push an input event at time `ts` through all the layers.
+    ///
+    /// `on_output` is called with the index of the layer (relative to the first layer of the pipeline), the timestamp and the output array
+    /// for every spike generated by any layer, in the same order in which the parallel solver would
+    /// generate them.
+    ///
+    /// Returns the outputs generated by the last layer, along with their timestamps.
+    /// These can be earlier than `ts` when some layers have delayed input synapses.
+    pub fn feed(&mut self, ts: u128, spike: Array2<f64>, on_output: impl FnMut(usize, u128, &Array2<f64>)) -> Vec<(u128, Array2<f64>)> {
+        self.propagate(vec![(ts, spike)], None, on_output, |_, _, _, _, _| ())
+    }
+
+    /// Same as [feed](Pipeline::feed), but `current` is also injected straight into the neurons of the first layer at time `ts`,
+    /// adding to the inputs they receive from `spike`.
+    pub fn feed_with_current(
+        &mut self,
+        ts: u128,
+        spike: Array2<f64>,
+        current: &Array2<f64>,
+        on_output: impl FnMut(usize, u128, &Array2<f64>)
+    ) -> Vec<(u128, Array2<f64>)> {
+        if let Some(first) = self.managers.first_mut() {
+            first.inject(ts, current);
+        }
+
+        self.feed(ts, spike, on_output)
+    }
+
+    /// Same as [feed](Pipeline::feed), but `on_update` is also called with the index of the layer, the id of the neuron, the timestamp,
+    /// the weighted input and the [SolverVars](Model::SolverVars) of the neuron right before every update of any neuron.
+    ///
+    /// With the `simd` feature, only the neurons of pipelines built by [new_observable](Pipeline::new_observable) are all observed.
+    pub fn feed_observed(
+        &mut self,
+        ts: u128,
+        spike: Array2<f64>,
+        on_output: impl FnMut(usize, u128, &Array2<f64>),
+        on_update: impl FnMut(usize, usize, u128, f64, &M::SolverVars)
+    ) -> Vec<(u128, Array2<f64>)> {
+        self.propagate(vec![(ts, spike)], None, on_output, on_update)
+    }
+
+    /// Apply every input still traveling through delayed synapses that arrives until `until` (included), like [feed](Pipeline::feed).
+    ///
+    /// No input event earlier than `until` must be fed afterwards.
+    pub fn flush(&mut self, until: u128, on_output: impl FnMut(usize, u128, &Array2<f64>)) -> Vec<(u128, Array2<f64>)> {
+        self.propagate(vec![], Some(until), on_output, |_, _, _, _, _| ())
+    }
+
+    /// Apply every input still traveling through delayed synapses, like [feed](Pipeline::feed).
+    ///
+    /// This must be called once all the input events have been fed.
+    pub fn finish(&mut self, on_output: impl FnMut(usize, u128, &Array2<f64>)) -> Vec<(u128, Array2<f64>)> {
+        self.flush(u128::MAX, on_output)
+    }
+
+    /// Same as [finish](Pipeline::finish), but observing every update like [feed_observed](Pipeline::feed_observed)
+    pub fn finish_observed(
+        &mut self,
+        on_output: impl FnMut(usize, u128, &Array2<f64>),
+        on_update: impl FnMut(usize, usize, u128, f64, &M::SolverVars)
+    ) -> Vec<(u128, Array2<f64>)> {
+        self.propagate(vec![], Some(u128::MAX), on_output, on_update)
+    }
+
+    /// Push the `current` events through all the layers, also flushing the inputs of every layer queued until `until` if provided
+    fn propagate(
+        &mut self,
+        mut current: Vec<(u128, Array2<f64>)>,
+        until: Option<u128>,
+        mut on_output: impl FnMut(usize, u128, &Array2<f64>),
+        mut on_update: impl FnMut(usize, usize, u128, f64, &M::SolverVars)
+    ) -> Vec<(u128, Array2<f64>)> {
+        // With skip connections, every layer is brought up to the latest input, so that none of them can lag behind
+        // the layers it feeds through a skip connection (which never receive their inputs earlier than that)
+        let until = if self.has_skips { until.or_else(|| current.iter().map(|(ts, _)| *ts).max()) } else { until };
+        // Outputs of every layer so far, only kept for the skip connections
+        let mut outputs = vec![];
+
+        for (layer_id, manager) in self.managers.iter_mut().enumerate() {
+            let mut next = vec![];
+            let mut observer = |ts, neuron_id, weighted_input, vars: &M::SolverVars| on_update(layer_id, neuron_id, ts, weighted_input, vars);
+
+            if self.has_skips {
+                manager.inject_skips(&outputs);
+            }
+            for (ts, spike) in &current {
+                manager.feed(*ts, spike, &mut next, &mut observer);
+            }
+            if let Some(until) = until {
+                manager.flush(until, &mut next, &mut observer);
+            }
+
+            for (ts, output) in &next {
+                on_output(layer_id, *ts, output);
+            }
+
+            if self.has_skips {
+                outputs.push(next.clone());
+            }
+
+            current = next;
+            if current.is_empty() && until.is_none() {
+                break;
+            }
+        }
+
+        current
+    }
+}
+
+/// Group the (sorted) input spikes by timestamp, producing the input events for the entry layer.
+///
+/// Each input carries the [polarity](Spike::polarity) of its spike.
+pub(crate) fn input_events(num_inputs: usize, spikes: impl IntoIterator<Item = Spike>) -> impl Iterator<Item = (u128, Array2<f64>)> {
+    let mut spike_iterator = spikes.into_iter().peekable();
+
+    std::iter::from_fn(move || {
+        let Spike {ts, neuron_id, polarity} = spike_iterator.next()?;
+        let mut to_send = Array2::zeros((1, num_inputs));
+        to_send[(0, neuron_id)] = polarity as f64;
+
+        while let Some(Spike {neuron_id, polarity, ..}) = spike_iterator.next_if(|s| s.ts == ts) {
+            to_send[(0, neuron_id)] = polarity as f64;
+        }
+
+        Some((ts, to_send))
+    })
+}
+
+/// Turn the outputs of the last layer, as returned by [Pipeline::feed] and the like, into the spikes they carry, sorted
+/// like the output of [solve_sequential](crate::NN::solve_sequential).
+pub(crate) fn output_spikes<M: Model>(outputs: impl IntoIterator<Item = (u128, Array2<f64>)>) -> Vec<Spike> {
+    let mut res = outputs.into_iter()
+        .flat_map(|(ts, output)| output.into_iter()
+            .enumerate()
+            .filter(|(_, v)| fired::<M>(*v))
+            .map(move |(neuron_id, _)| Spike::new(ts, neuron_id))
+            .collect::<Vec<_>>()
+        )
+        .collect::<Vec<_>>();
+    res.sort();
+
+    res
+}
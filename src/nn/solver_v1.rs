@@ -1,9 +1,16 @@
-use crate::{nn::{Spike, NN}, Model};
+use crate::{nn::{Spike, NN, model::fired}, Model};
 use ndarray::{Array2, OwnedRepr, ArrayBase, Dim};
 
 
 /// This struct is used to manage the input spikes given a NN,
 /// to generate the output spikes.
+/// 
+/// Synaptic delays are not supported: every spike reaches the next layer at the same timestamp it's generated at.
+/// 
+/// The neurons of a layer are updated simultaneously, in steps: in every step, all of them are updated with the inputs
+/// generated by the previous step (or by the previous layer, in the first one), and only then the spikes they generate
+/// are applied to the layer through the intra-layer weights, in the next step. So, whatever their index, neurons firing
+/// at the same step never see each other's intra-layer feedback before firing.
 pub struct Solver<M: Model>{
     input_spikes: Vec<Spike>,
     network: NN<M>,
@@ -34,7 +41,7 @@ impl<M: Model> SimulatedNN<M> {
     ///Build a new instance of [SimulatedNN].
     fn new() -> Self{
         Self { 
-            ///Vector that contains all the layers inside the NN.
+            //Vector that contains all the layers inside the NN.
             layers: Vec::new(),
         }
     }
@@ -57,27 +64,42 @@ where for <'a> &'a M::Neuron: Into<M::SolverVars> {
     }
 
     /// Each spike of the input_spike vec is sent to the corresponding neuron 
-    /// of the input layer, one by one.
+    /// of the input layer. Spikes with the same timestamp are applied together, in a single step.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics with a descriptive message if the network has no layers, if the shape of the weight matrices of any layer
+    /// doesn't match the size of the layers (e.g. for a network generated programmatically and deserialized), or if any input spike
+    /// refers to a neuron beyond the size of the entry layer.
     pub fn solve(&mut self) -> Vec<Vec<u128>>{
 
+        Self::check_network(&self.network);
+
         //Inizialization of Neuron variables
         let mut sim_network = Self::init_neuron_vars(&(self.network));
         let mut nn_output: Vec<Vec<u128>> = Vec::new();
         
+        //Input dimension taken from layers 0 (1st Layer)
+        let dim_input = self.network.layers[0].neurons.len();
 
         //Iteration over the spikes input vector
-        for spike in self.input_spikes.iter() {
-
-            //Input dimension taken from layers 0 (1st Layer)
-            let dim_input = self.network.layers[0].neurons.len();
+        let mut spike_iterator = self.input_spikes.iter().peekable();
+        while let Some(spike) = spike_iterator.next() {
 
             //Spike array creation, involved in a multiplication with the first (diagonal) weight matrix (input matrix).
-            let spike_array = single_spike_to_vec(spike.neuron_id, dim_input);
+            check_input_spike(spike, dim_input);
+            let mut spike_array = single_spike_to_vec(spike.neuron_id, dim_input) * spike.polarity as f64;
+
+            //Simultaneous spikes are merged in the same array, so that the intra-layer feedback is only applied once
+            while let Some(other) = spike_iterator.next_if(|s| s.ts == spike.ts) {
+                check_input_spike(other, dim_input);
+                spike_array[[0, other.neuron_id]] = other.polarity as f64;
+            }
 
             //Propagation of spikes inside the network
             let res = Solver::infer_spike_vec(&self.network, &mut sim_network, spike_array, spike.ts);
         
-            nn_output.push(res);
+            nn_output.extend(res);
         }
     
 
@@ -92,6 +114,32 @@ where for <'a> &'a M::Neuron: Into<M::SolverVars> {
         output
     }
 
+    /// _*--> (Internal Use Only)*_
+    /// 
+    /// Check that the network has at least one layer, and that the weight matrices of every layer match the size of the layers,
+    /// panicking with a descriptive message otherwise
+    fn check_network(network: &NN<M>) {
+        assert!(!network.layers.is_empty(), "network has no layers");
+
+        let mut len_prev_layer = network.layers[0].neurons.len();
+        for (layer_id, layer) in network.layers.iter().enumerate() {
+            let n = layer.neurons.len();
+
+            assert!(
+                layer.input_weights.dim() == (len_prev_layer, n),
+                "input weights of layer {} are {:?}, but its previous layer (or input) has {} neurons and it has {}",
+                layer_id, layer.input_weights.dim(), len_prev_layer, n
+            );
+            assert!(
+                layer.intra_weights.dim() == (n, n),
+                "intra weights of layer {} are {:?}, but it has {} neurons",
+                layer_id, layer.intra_weights.dim(), n
+            );
+
+            len_prev_layer = n;
+        }
+    }
+
     /// _*--> (Internal Use Only)*_
     /// 
     /// Create a temporary NN, parallel to the real one passed as a parameter
@@ -115,83 +163,78 @@ where for <'a> &'a M::Neuron: Into<M::SolverVars> {
         sim_nn
     }
 
-    /// Propagate Spikes inside the network and then creates a Vec of spikes.
+    /// Propagate Spikes inside the network and then creates a Vec of spikes for every output of the last layer.
     fn infer_spike_vec(
                 network: & NN<M>, 
                 sim_network: &mut SimulatedNN<M>, 
                 spike_vec: ArrayBase<OwnedRepr<f64>, 
                 Dim<[usize; 2]>>, 
-                ts: u128) -> Vec<u128> {
-
-        //Creation of the output spikes vector
-        let mut out_spikes: Vec<u128> = Vec::new();
-
-        //Creation of output vector containing the spikes generated by neurons
-        let mut output_vec: Vec<f64> = Vec::new();
+                ts: u128) -> Vec<Vec<u128>> {
 
         //Creation of vector that contains the variables of the i-th simulatedLayer
         let mut neuron_vars: &mut Vec<SimulatedNeuron<M>> ;
         
-        let mut current_spike_vec = spike_vec;
+        //Spike vectors generated by the previous layer, one for every step of its intra-layer feedback
+        let mut current_spike_vecs = vec![spike_vec];
 
         // We compute for each neuron inside the layer its output (if it generates a spike or not)
-        for (cnt, (layer, sim_layer)) in network.layers.iter().zip(&mut sim_network.layers).enumerate(){
+        for (layer, sim_layer) in network.layers.iter().zip(&mut sim_network.layers) {
             
             //Variables of i-th layer
             neuron_vars = sim_layer;
 
-    
-            //We use `current_spike_vec` (vector containing the spikes generated by the previous layer)
-            //to compute the weighted spikes received to the current layer, we use a dot product.
-            let weighted_input_val = current_spike_vec.dot(&layer.input_weights);
-
-        
-            // For each neuron in the layer, we use the `handle_spike` function given the neuron parameters and variables and 
-            // the previously computed input. We can obtain a spike (`1`) or not (`0`) 
-            for (i, neuron) in layer.neurons.iter().enumerate(){
-                
-                let res = M::handle_spike(neuron, 
-                    &mut neuron_vars[i].vars, 
-                    weighted_input_val[[0,i]], 
-                    ts);
-                output_vec.push(res);
-            }
-
-            //We update the `current_spike_vec` with the obtained results
-            current_spike_vec =  Array2::from_shape_vec([1, output_vec.len()], output_vec.clone()).unwrap();
-
-            // Check if the current layer is the last layer.
-            if cnt == network.layers.len() - 1 {
-                out_spikes = to_u128_vec(&output_vec, ts);  
+            //Spike vectors generated by the current layer
+            let mut next_spike_vecs = Vec::new();
+
+            for current_spike_vec in current_spike_vecs {
+                //We use `current_spike_vec` (vector containing the spikes generated by the previous layer)
+                //to compute the weighted spikes received to the current layer, we use a dot product.
+                let mut weighted_input_val = current_spike_vec.dot(&layer.input_weights);
+
+                loop {
+                    // For each neuron in the layer, we use the `handle_spike` function given the neuron parameters and variables and 
+                    // the previously computed input. We can obtain a spike (`1`) or not (`0`).
+                    // The inputs of every neuron are computed before this step, so the order of the updates doesn't matter
+                    let output_vec = layer.neurons.iter().zip(neuron_vars.iter_mut()).enumerate().map(|(i, (neuron, sim_neuron))| {
+                        M::handle_spike(neuron, 
+                            &mut sim_neuron.vars, 
+                            weighted_input_val[[0,i]], 
+                            ts)
+                    }).collect::<Vec<_>>();
+
+                    // Stop as soon as no neuron fires anymore. Like in the parallel solver, a neuron fires if its output is a spike (see `fired`),
+                    // but the whole output is then propagated as it is, so that graded outputs are weighted by their actual value
+                    if output_vec.iter().all(|&v| !fired::<M>(v)) {
+                        break;
+                    }
+
+                    let output_vec = Array2::from_shape_vec([1, output_vec.len()], output_vec).unwrap();
+
+                    // Creation of the input vector with values recived by neurons in the same layers. We use a dot product.
+                    // Neurons firing because of the intra-layer links generate new spikes too, which are handled in the next iteration.
+                    weighted_input_val = output_vec.dot(&layer.intra_weights);
+                    next_spike_vecs.push(output_vec);
+                }
             }
 
-            // Empty the spike vector generated by neurons to make 
-            // the vector ready for the next iterations over the layers
-            output_vec.clear();
-
-            // Creation of the input vector with values recived by neurons in the same layers. We use a dot product.
-            let intra_layer_input_val = current_spike_vec.dot(&layer.intra_weights);
-
-            // For each neuron in the layer, we use the `handle_spike` function given the neuron parameters and variables and 
-            // the computed input considering the intra-layer links. We can obtain a spike (`1`) or not (`0`).
-            // Here, using `handle_spike`, we update the internal `vars` 
-            for (i, neuron) in layer.neurons.iter().enumerate(){
-                
-                M::handle_spike(neuron, 
-                    &mut neuron_vars[i].vars, 
-                    intra_layer_input_val[[0,i]], 
-                    ts);
-            }
+            current_spike_vecs = next_spike_vecs;
         }
 
-        out_spikes
-        
-
-
+        // The spike vectors left are the ones generated by the last layer
+        current_spike_vecs.iter().map(|spike_vec| to_u128_vec::<M, _>(spike_vec, ts)).collect()
     }
 
 }
 
+    /// Panic with a descriptive message if `spike` refers to a neuron beyond the `dim_input` neurons of the entry layer
+    fn check_input_spike(spike: &Spike, dim_input: usize) {
+        assert!(
+            spike.neuron_id < dim_input,
+            "neuron index {} of the input spike at {} exceeds layer size {}",
+            spike.neuron_id, spike.ts, dim_input
+        );
+    }
+
     /// Create a zero array, but with a single '1' in the neuron_id-th position
     /// 
     /// # Example 
@@ -215,16 +258,16 @@ where for <'a> &'a M::Neuron: Into<M::SolverVars> {
         Array2::from_shape_vec([1, dim], res).unwrap()
     }
 
-    /// Create a vec of u128 (val_to_set) starting from a f64 array and a val to use if the f64 is greater than 0 
+    /// Create a vec of u128 (val_to_set) starting from a f64 array and a val to use if the f64 is a spike (greater than the firing threshold of the model)
     /// 
-    /// If in the i-th position the val of he input vec is greater than 0, the new vec will have 'val_to_set in that position, otherwise it will have a 0
-    fn to_u128_vec<'a, T>(vec: T, val_to_set: u128) -> Vec<u128>
+    /// If in the i-th position the val of he input vec is a spike, the new vec will have 'val_to_set in that position, otherwise it will have [u128::MAX]
+    fn to_u128_vec<'a, M: Model, T>(vec: T, val_to_set: u128) -> Vec<u128>
     where T: IntoIterator<Item = &'a f64>
     {
         let mut res: Vec<u128> =  Vec::new();
 
         for &val in vec {
-            if val > 0.0 { res.push(val_to_set) }
+            if fired::<M>(val) { res.push(val_to_set) }
             else { res.push(u128::MAX) };
         }   
         res 
@@ -234,7 +277,7 @@ where for <'a> &'a M::Neuron: Into<M::SolverVars> {
 mod tests {
     
 
-    use crate::{lif::{LifNeuronConfig, LeakyIntegrateFire, LifNeuron, LifSolverVars}, Spike, NNBuilder, nn::{solver_v1::Solver}};
+    use crate::{lif::{LifNeuronConfig, LeakyIntegrateFire, LifNeuron, LifSolverVars}, Spike, NN, NNBuilder, nn::{solver_v1::Solver}};
 
     #[test]
     fn test_init_simulated_nn() {
@@ -386,4 +429,315 @@ mod tests {
     );
 
     }
+
+    #[test]
+    fn test_simultaneous_intra_layer_updates() {
+        let config = LifNeuronConfig::new(0.0, 0.0, 0.5, 1.0);
+
+        // Only the first neuron inhibits the other one, and only the last neuron inhibits the other one
+        let inhibitions = [
+            [[0.0, -2.0], [0.0, 0.0]],
+            [[0.0, 0.0], [-2.0, 0.0]]
+        ];
+        let expected = [
+            vec![vec![1, 2], vec![1]],
+            vec![vec![1], vec![1, 2]]
+        ];
+
+        for (intra_weights, expected) in inhibitions.into_iter().zip(expected) {
+            let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+                .layer([From::from(&config), From::from(&config)], [1.0, 1.0], intra_weights)
+                .build();
+
+            // The inhibited neuron fires together with the other one at 1, whatever its index, and is only inhibited at 2
+            let spikes = Spike::create_terminal_vec(vec![
+                Spike::spike_vec_for(0, vec![1, 2]),
+                Spike::spike_vec_for(1, vec![1, 2])
+            ]);
+
+            assert_eq!(Solver::new(spikes, nn).solve(), expected);
+        }
+    }
+
+    #[test]
+    fn test_simultaneous_input_spikes() {
+        let config = LifNeuronConfig::new(0.0, 0.0, 0.5, 1.0);
+
+        let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+            .layer(
+                [
+                    From::from(&config),
+                    From::from(&config)
+                ],
+                [
+                    1.0, 1.0
+                ],
+                [
+                    [0.0, -2.0],
+                    [-2.0, 0.0]
+                ]
+            )
+            .build();
+
+        // Both neurons must fire before inhibiting each other
+        let spikes = Spike::create_terminal_vec(
+            vec![
+                Spike::spike_vec_for(0, vec![1]),
+                Spike::spike_vec_for(1, vec![1])
+            ]
+        );
+
+        let mut solver = Solver::new(spikes, nn);
+
+        assert_eq!(
+            solver.solve(),
+            vec![
+                vec![1],
+                vec![1]
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "network has no layers")]
+    fn test_empty_network() {
+        let nn = crate::NN::<LeakyIntegrateFire> { layers: vec![], heads: vec![] };
+
+        Solver::new(vec![Spike::new(1, 0)], nn).solve();
+    }
+
+    #[test]
+    #[should_panic(expected = "neuron index 2 of the input spike at 3 exceeds layer size 2")]
+    fn test_input_spike_out_of_range() {
+        let config = LifNeuronConfig::new(0.0, 0.0, 0.5, 1.0);
+        let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+            .layer([From::from(&config), From::from(&config)], [1.0, 1.0], [[0.0, 0.0], [0.0, 0.0]])
+            .build();
+
+        // The out of range spike is merged with a valid one
+        Solver::new(vec![Spike::new(3, 0), Spike::new(3, 2)], nn).solve();
+    }
+
+    #[test]
+    #[should_panic(expected = "input weights of layer 1 are (3, 1)")]
+    fn test_mismatched_weights() {
+        let config = LifNeuronConfig::new(0.0, 0.0, 0.5, 1.0);
+        let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+            .layer([From::from(&config), From::from(&config)], [1.0, 1.0], [[0.0, 0.0], [0.0, 0.0]])
+            .layer([From::from(&config)], [[1.0], [1.0]], [[0.0]])
+            .build();
+        nn.layers[1].input_weights = ndarray::Array2::zeros((3, 1));
+
+        Solver::new(vec![Spike::new(1, 0)], nn).solve();
+    }
+
+    /// A stateless model whose output is its input scaled by a gain, used to check graded outputs
+    #[derive(Clone, Debug)]
+    struct Graded;
+
+    #[derive(Clone, Debug)]
+    struct GradedNeuron(f64);
+
+    #[derive(Default)]
+    struct GradedVars;
+
+    impl From<&GradedNeuron> for GradedVars {
+        fn from(_: &GradedNeuron) -> Self {
+            GradedVars
+        }
+    }
+
+    impl From<&GradedNeuron> for GradedNeuron {
+        fn from(neuron: &GradedNeuron) -> Self {
+            neuron.clone()
+        }
+    }
+
+    impl crate::Model for Graded {
+        type Neuron = GradedNeuron;
+        type SolverVars = GradedVars;
+        type Config = GradedNeuron;
+
+        fn handle_spike(neuron: &GradedNeuron, _: &mut GradedVars, weighted_input_val: f64, _: u128) -> f64 {
+            assert!(!weighted_input_val.is_nan(), "graded neuron with a NaN input");
+
+            neuron.0 * weighted_input_val
+        }
+
+        fn perturb(neuron: &mut GradedNeuron, mut noise: impl FnMut() -> f64) {
+            neuron.0 += noise();
+        }
+
+        #[cfg(feature = "simd")]
+        type Neuronx4 = [GradedNeuron; 4];
+        #[cfg(feature = "simd")]
+        type SolverVarsx4 = [GradedVars; 4];
+
+        #[cfg(feature = "simd")]
+        fn neuron_x4_from_neurons(neurons: &[GradedNeuron]) -> [GradedNeuron; 4] {
+            [neurons[0].clone(), neurons[1].clone(), neurons[2].clone(), neurons[3].clone()]
+        }
+        #[cfg(feature = "simd")]
+        fn vars_x4_from_vars(_: &[GradedVars]) -> [GradedVars; 4] {
+            [GradedVars, GradedVars, GradedVars, GradedVars]
+        }
+        #[cfg(feature = "simd")]
+        fn handle_spike_x4(neurons: &[GradedNeuron; 4], vars: &mut [GradedVars; 4], weighted_input_vals: packed_simd::f64x4, ts: u128) -> packed_simd::f64x4 {
+            let output = |i: usize| Graded::handle_spike(&neurons[i], &mut vars[i], weighted_input_vals.extract(i), ts);
+
+            packed_simd::f64x4::new(output(0), output(1), output(2), output(3))
+        }
+    }
+
+    #[test]
+    fn test_graded_outputs() {
+        let nn = NNBuilder::<Graded, _>::new()
+            .layer([GradedNeuron(3.0), GradedNeuron(0.4)], [1.0, 1.0], [[0.0, 0.0], [0.0, 0.0]])
+            .layer([GradedNeuron(1.0)], [[0.25], [0.5]], [[0.0]])
+            .build();
+
+        // The first neuron fires with an output of 3.0, which is weighted enough to make the second layer fire (unlike a spike of 1.0),
+        // while the output of the second neuron is not a spike, but it's still propagated along with the spike of the first one
+        let spikes = Spike::create_terminal_vec(vec![
+            Spike::spike_vec_for(0, vec![1]),
+            Spike::spike_vec_for(1, vec![1, 2])
+        ]);
+
+        let expected = vec![vec![1]];
+        assert_eq!(nn.solve_on_current_thread(spikes.clone()), expected);
+        assert_eq!(Solver::new(spikes, nn).solve(), expected);
+    }
+
+    #[test]
+    fn test_sub_threshold_outputs() {
+        let nn = NNBuilder::<Graded, _>::new()
+            .layer([GradedNeuron(1.0), GradedNeuron(0.3)], [1.0, 1.0], [[0.0, 0.0], [0.0, 0.0]])
+            .layer([GradedNeuron(0.3), GradedNeuron(1.0)], [[1.0, 0.0], [0.0, 10.0]], [[0.0, 0.0], [0.0, 0.0]])
+            .build();
+
+        // An output of 0.3 is never a spike: the second neuron of the first layer can't make the next layer fire on its own,
+        // and the first neuron of the output layer never shows up in the output, whichever solver is used
+        let spikes = Spike::create_terminal_vec(vec![
+            Spike::spike_vec_for(0, vec![1]),
+            Spike::spike_vec_for(1, vec![2])
+        ]);
+
+        let expected = vec![vec![], vec![]];
+        assert_eq!(nn.solve_on_current_thread(spikes.clone()), expected);
+        assert_eq!(nn.solve_event_driven(spikes.clone()), vec![]);
+        assert_eq!(Solver::new(spikes, nn).solve(), expected);
+    }
+
+    /// A [Graded] model that considers any positive output a spike
+    #[derive(Clone, Debug)]
+    struct Sensitive;
+
+    impl crate::Model for Sensitive {
+        type Neuron = GradedNeuron;
+        type SolverVars = GradedVars;
+        type Config = GradedNeuron;
+
+        const FIRING_THRESHOLD: f64 = 0.0;
+
+        fn handle_spike(neuron: &GradedNeuron, vars: &mut GradedVars, weighted_input_val: f64, ts: u128) -> f64 {
+            Graded::handle_spike(neuron, vars, weighted_input_val, ts)
+        }
+
+        #[cfg(feature = "simd")]
+        type Neuronx4 = [GradedNeuron; 4];
+        #[cfg(feature = "simd")]
+        type SolverVarsx4 = [GradedVars; 4];
+
+        #[cfg(feature = "simd")]
+        fn neuron_x4_from_neurons(neurons: &[GradedNeuron]) -> [GradedNeuron; 4] {
+            Graded::neuron_x4_from_neurons(neurons)
+        }
+        #[cfg(feature = "simd")]
+        fn vars_x4_from_vars(vars: &[GradedVars]) -> [GradedVars; 4] {
+            Graded::vars_x4_from_vars(vars)
+        }
+        #[cfg(feature = "simd")]
+        fn handle_spike_x4(neurons: &[GradedNeuron; 4], vars: &mut [GradedVars; 4], weighted_input_vals: packed_simd::f64x4, ts: u128) -> packed_simd::f64x4 {
+            Graded::handle_spike_x4(neurons, vars, weighted_input_vals, ts)
+        }
+    }
+
+    #[test]
+    fn test_model_firing_threshold() {
+        let spikes = Spike::create_terminal_vec(vec![
+            Spike::spike_vec_for(0, vec![1]),
+            Spike::spike_vec_for(1, vec![2])
+        ]);
+
+        // Same network as in `test_sub_threshold_outputs`, whose outputs of 0.3 are now spikes
+        let nn = NNBuilder::<Sensitive, _>::new()
+            .layer([GradedNeuron(1.0), GradedNeuron(0.3)], [1.0, 1.0], [[0.0, 0.0], [0.0, 0.0]])
+            .layer([GradedNeuron(0.3), GradedNeuron(1.0)], [[1.0, 0.0], [0.0, 10.0]], [[0.0, 0.0], [0.0, 0.0]])
+            .build();
+
+        let expected = vec![vec![1], vec![2]];
+        assert_eq!(nn.solve_on_current_thread(spikes.clone()), expected);
+        assert_eq!(nn.solve_event_driven(spikes.clone()), vec![Spike::new(1, 0), Spike::new(2, 1)]);
+        assert_eq!(Solver::new(spikes, nn).solve(), expected);
+    }
+
+    /// Chain of graded layers of two neurons, the last of which has a NaN synapse
+    fn create_faulty_chain() -> NN<Graded> {
+        let mut builder = NNBuilder::<Graded, _>::new_dynamic()
+            .layer(vec![GradedNeuron(1.0); 2], [1.0, 1.0], [0.0; 4]).unwrap();
+
+        for _ in 0..3 {
+            builder = builder.layer(vec![GradedNeuron(1.0); 2], [1.0, 0.0, 0.0, 1.0], [0.0; 4]).unwrap();
+        }
+
+        builder.layer(vec![GradedNeuron(1.0); 2], [f64::NAN, 0.0, 0.0, 1.0], [0.0; 4]).unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn panic_message(panic: Box<dyn std::any::Any + Send>) -> String {
+        panic.downcast_ref::<&str>().map(|msg| msg.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn test_worker_panic_sync() {
+        let nn = create_faulty_chain();
+        let spikes = Spike::create_terminal_vec(vec![
+            Spike::spike_vec_for(0, (1..50).collect()),
+            Spike::spike_vec_for(1, (1..50).collect())
+        ]);
+
+        // The panic of the last group is the one reported, not the failed sends of the previous groups
+        for max_threads in 1..=5 {
+            let config = crate::SolveConfig { max_threads: max_threads.try_into().unwrap(), min_neurons_for_threading: 0 };
+            let panic = std::panic::catch_unwind(|| nn.solve_with_config(spikes.clone(), config)).unwrap_err();
+
+            assert_eq!(panic_message(panic), "graded neuron with a NaN input");
+        }
+
+        let panic = std::panic::catch_unwind(|| nn.solve_batch(vec![spikes.clone(); 3])).unwrap_err();
+        assert_eq!(panic_message(panic), "graded neuron with a NaN input");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_worker_panic_async() {
+        let nn = create_faulty_chain();
+        let spikes = Spike::create_terminal_vec(vec![
+            Spike::spike_vec_for(0, (1..50).collect()),
+            Spike::spike_vec_for(1, (1..50).collect())
+        ]);
+
+        // The panic of the last group is the one reported, not the failed sends of the previous groups
+        for max_threads in 1..=5 {
+            let config = crate::SolveConfig { max_threads: max_threads.try_into().unwrap(), min_neurons_for_threading: 0 };
+            let (nn, spikes) = (nn.clone(), spikes.clone());
+            let err = tokio::spawn(async move { nn.solve_with_config(spikes, config).await }).await.unwrap_err();
+
+            assert_eq!(panic_message(err.into_panic()), "graded neuron with a NaN input");
+        }
+    }
 }
@@ -1,7 +1,8 @@
 use std::{ops::Range, num::NonZeroUsize};
 use rand::prelude::*;
 use rand_pcg::Pcg64Mcg;
-use crate::{nn::{Spike, solver_v1::Solver}, NNBuilder, lif::LeakyIntegrateFire, lif::LifNeuronConfig, NN, lif::LifNeuron};
+use ndarray::Array2;
+use crate::{nn::{Spike, SolveConfig, solver_v1::Solver, layer::weigh}, sync::group_layers, testing::assert_solvers_agree, NNBuilder, lif::LeakyIntegrateFire, lif::LifNeuronConfig, NN, lif::LifNeuron};
 
 fn random_lif_neuron<Rng: RngCore>(rng: &mut Rng) -> LifNeuron {
     let v_rest = rng.gen_range(0.8..2.5);
@@ -25,9 +26,9 @@ fn create_random_lif_nn(seed: u64, num_layers: NonZeroUsize, layer_size_range: R
         let layer_size = rng.gen_range(layer_size_range.clone());
         
         builder = builder.layer(
-            (0..layer_size).into_iter().map(|_| random_lif_neuron(&mut rng)).collect::<Vec<_>>(),
-            (0..last_layer_size*layer_size).into_iter().map(|_| rng.gen_range(0.5..2.5)).collect::<Vec<_>>(),
-            (0..layer_size*layer_size).into_iter().enumerate().map(|(i, _)| if i % (layer_size + 1) == 0 { 0.0 } else { rng.gen_range(-1.0..-0.05) }).collect::<Vec<_>>()
+            (0..layer_size).map(|_| random_lif_neuron(&mut rng)).collect::<Vec<_>>(),
+            (0..last_layer_size*layer_size).map(|_| rng.gen_range(0.5..2.5)).collect::<Vec<_>>(),
+            (0..layer_size*layer_size).enumerate().map(|(i, _)| if i % (layer_size + 1) == 0 { 0.0 } else { rng.gen_range(-1.0..-0.05) }).collect::<Vec<_>>()
         ).unwrap();
 
         last_layer_size = layer_size;
@@ -191,14 +192,815 @@ async fn test_huge_async() {
     assert_eq!(solver.solve(), nn.solve(spikes).await);
 }
 
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_fuzz_solvers_agree_sync() {
+    for seed in 0..20 {
+        let (nn, spikes) = create_random_lif_nn(
+            seed,
+            5.try_into().unwrap(),
+            1.try_into().unwrap()..10.try_into().unwrap(),
+            30
+        );
+
+        assert_solvers_agree(&nn, &spikes);
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_fuzz_solvers_agree_async() {
+    for seed in 0..20 {
+        let (nn, spikes) = create_random_lif_nn(
+            seed,
+            5.try_into().unwrap(),
+            1.try_into().unwrap()..10.try_into().unwrap(),
+            30
+        );
+
+        assert_solvers_agree(&nn, &spikes).await;
+    }
+}
+
+/// Give random delays to the input synapses of every layer but the entry one
+fn add_random_delays(nn: &mut NN<LeakyIntegrateFire>, seed: u64, max_delay: u128) {
+    let mut rng = Pcg64Mcg::seed_from_u64(seed);
+
+    for layer in nn.layers.iter_mut().skip(1) {
+        layer.input_delays = Some(layer.input_weights.map(|_| rng.gen_range(0..=max_delay)));
+        layer.intra_delays = Some(layer.intra_weights.map(|_| rng.gen_range(0..=max_delay)));
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_fuzz_solvers_agree_with_delays_sync() {
+    for seed in 0..20 {
+        let (mut nn, spikes) = create_random_lif_nn(
+            seed,
+            5.try_into().unwrap(),
+            1.try_into().unwrap()..10.try_into().unwrap(),
+            30
+        );
+
+        // Null delays must not change the output
+        let expected = nn.solve(spikes.clone());
+        add_random_delays(&mut nn, seed, 0);
+        assert_eq!(nn.solve(spikes.clone()), expected);
+
+        add_random_delays(&mut nn, seed, 10);
+        assert_solvers_agree(&nn, &spikes);
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_fuzz_solvers_agree_with_delays_async() {
+    for seed in 0..20 {
+        let (mut nn, spikes) = create_random_lif_nn(
+            seed,
+            5.try_into().unwrap(),
+            1.try_into().unwrap()..10.try_into().unwrap(),
+            30
+        );
+
+        // Null delays must not change the output
+        let expected = nn.solve(spikes.clone()).await;
+        add_random_delays(&mut nn, seed, 0);
+        assert_eq!(nn.solve(spikes.clone()).await, expected);
+
+        add_random_delays(&mut nn, seed, 10);
+        assert_solvers_agree(&nn, &spikes).await;
+    }
+}
+
+#[test]
+fn test_group_layers() {
+    let (nn, _) = create_random_lif_nn(
+        543513,
+        50.try_into().unwrap(),
+        10.try_into().unwrap()..20.try_into().unwrap(),
+        75
+    );
+
+    for max_groups in [1, 2, 7, 49, 50, 51, 1000] {
+        let groups = group_layers(&nn.layers, max_groups, 0);
+
+        assert_eq!(groups.len(), max_groups.min(nn.layers.len()));
+        assert_eq!(groups.first().unwrap().start, 0);
+        assert_eq!(groups.last().unwrap().end, nn.layers.len());
+        assert!(groups.iter().all(|group| !group.is_empty()));
+        assert!(groups.windows(2).all(|w| w[0].end == w[1].start));
+    }
+}
+
+#[test]
+fn test_group_layers_with_skips() {
+    let (mut nn, _) = create_random_lif_nn(
+        543513,
+        50.try_into().unwrap(),
+        10.try_into().unwrap()..20.try_into().unwrap(),
+        75
+    );
+
+    for (from, to) in [(3, 10), (28, 30), (40, 49)] {
+        let weights = Array2::zeros((nn.layers[from].neurons.len(), nn.layers[to].neurons.len()));
+        nn.layers[to].skips.push((to - from, weights));
+    }
+
+    for max_groups in [1, 2, 7, 49, 50, 51, 1000] {
+        let groups = group_layers(&nn.layers, max_groups, 0);
+
+        assert!(groups.len() <= max_groups.min(nn.layers.len()));
+        assert_eq!(groups.first().unwrap().start, 0);
+        assert_eq!(groups.last().unwrap().end, nn.layers.len());
+        assert!(groups.iter().all(|group| !group.is_empty()));
+        assert!(groups.windows(2).all(|w| w[0].end == w[1].start));
+        assert!(groups.iter().all(|group| [(3, 10), (28, 30), (40, 49)].iter().all(|(from, to)| group.contains(from) == group.contains(to))));
+    }
+}
+
+/// Give a skip connection with random weights to every layer but the first two, from a random earlier layer
+fn add_random_skips(nn: &mut NN<LeakyIntegrateFire>, seed: u64, weight_range: Range<f64>) {
+    let mut rng = Pcg64Mcg::seed_from_u64(seed);
+
+    for to in 2..nn.layers.len() {
+        let from = rng.gen_range(0..to-1);
+        let weights = Array2::from_shape_simple_fn(
+            (nn.layers[from].neurons.len(), nn.layers[to].neurons.len()),
+            || if weight_range.is_empty() { weight_range.start } else { rng.gen_range(weight_range.clone()) }
+        );
+
+        nn.layers[to].skips = vec![(to - from, weights)];
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_fuzz_solvers_agree_with_skips_sync() {
+    for seed in 0..20 {
+        let (mut nn, spikes) = create_random_lif_nn(
+            seed,
+            6.try_into().unwrap(),
+            1.try_into().unwrap()..10.try_into().unwrap(),
+            30
+        );
+
+        // Null skip weights must not change the output
+        let expected = nn.solve(spikes.clone());
+        add_random_skips(&mut nn, seed, 0.0..0.0);
+        assert_eq!(nn.solve(spikes.clone()), expected);
+
+        add_random_skips(&mut nn, seed, 0.5..2.5);
+        assert_solvers_agree(&nn, &spikes);
+
+        add_random_delays(&mut nn, seed, 10);
+        assert_solvers_agree(&nn, &spikes);
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_fuzz_solvers_agree_with_skips_async() {
+    for seed in 0..20 {
+        let (mut nn, spikes) = create_random_lif_nn(
+            seed,
+            6.try_into().unwrap(),
+            1.try_into().unwrap()..10.try_into().unwrap(),
+            30
+        );
+
+        // Null skip weights must not change the output
+        let expected = nn.solve(spikes.clone()).await;
+        add_random_skips(&mut nn, seed, 0.0..0.0);
+        assert_eq!(nn.solve(spikes.clone()).await, expected);
+
+        add_random_skips(&mut nn, seed, 0.5..2.5);
+        assert_solvers_agree(&nn, &spikes).await;
+
+        add_random_delays(&mut nn, seed, 10);
+        assert_solvers_agree(&nn, &spikes).await;
+    }
+}
+
+/// Build a chain of pass-through layers of a single neuron, where the input synapse of the i-th layer has a delay of `delays[i]`
+fn create_delayed_chain(delays: &[u128]) -> NN<LeakyIntegrateFire> {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    delays.iter()
+        .try_fold(NNBuilder::<LeakyIntegrateFire, _>::new_dynamic(), |builder, delay| {
+            builder.layer_with_delays([LifNeuron::new(&config)], [1.0], [0.0], [*delay])
+        })
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_staggered_layer_completion_sync() {
+    // Every group receives its last spike long after the previous one has closed its sender
+    let delays = [0, 40, 3, 25, 0, 60, 1, 10];
+    let nn = create_delayed_chain(&delays);
+    let spikes = Spike::spike_vec_for(0, vec![1, 2, 5, 30]);
+
+    let total_delay = delays.iter().sum::<u128>();
+    let expected = vec![spikes.iter().map(|spike| spike.ts + total_delay).collect::<Vec<_>>()];
+
+    for max_threads in 1..=delays.len() {
+        let config = SolveConfig { max_threads: max_threads.try_into().unwrap(), ..Default::default() };
+        assert_eq!(nn.solve_with_config(spikes.clone(), config), expected);
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_staggered_layer_completion_async() {
+    // Every group receives its last spike long after the previous one has closed its sender
+    let delays = [0, 40, 3, 25, 0, 60, 1, 10];
+    let nn = create_delayed_chain(&delays);
+    let spikes = Spike::spike_vec_for(0, vec![1, 2, 5, 30]);
+
+    let total_delay = delays.iter().sum::<u128>();
+    let expected = vec![spikes.iter().map(|spike| spike.ts + total_delay).collect::<Vec<_>>()];
+
+    for max_threads in 1..=delays.len() {
+        let config = SolveConfig { max_threads: max_threads.try_into().unwrap(), ..Default::default() };
+        assert_eq!(nn.solve_with_config(spikes.clone(), config).await, expected);
+    }
+}
+
+// Small enough to also run under Miri (`cargo +nightly miri test --lib test_solve_scoped_threads`), to check the worker threads
+// for undefined behavior while they borrow the layers
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_solve_scoped_threads() {
+    let delays = [0, 2, 1];
+    let spikes = Spike::spike_vec_for(0, vec![1, 4]);
+
+    let output = {
+        let nn = create_delayed_chain(&delays);
+        let config = SolveConfig { max_threads: delays.len().try_into().unwrap(), ..Default::default() };
+
+        nn.solve_with_config(spikes, config)
+    };
+
+    assert_eq!(output, vec![vec![4, 7]]);
+}
+
+// Like `test_solve_scoped_threads`, for the tasks of the async solver
+// (`cargo +nightly miri test --lib --features async test_solve_tasks`)
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_solve_tasks_async() {
+    let delays = [0, 2, 1];
+    let spikes = Spike::spike_vec_for(0, vec![1, 4]);
+
+    let output = {
+        let nn = create_delayed_chain(&delays);
+        let config = SolveConfig { max_threads: delays.len().try_into().unwrap(), ..Default::default() };
+
+        nn.solve_with_config(spikes, config).await
+    };
+
+    assert_eq!(output, vec![vec![4, 7]]);
+}
+
+// Dropping the solve doesn't stop the tasks it spawned, which must not read the network once it's gone:
+// run under Miri to check for use-after-free (`cargo +nightly miri test --lib --features async test_solve_tasks`)
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_solve_tasks_cancelled_async() {
+    use std::{future::{Future, poll_fn}, pin::pin, task::Poll};
+
+    let delays = [0, 2, 1];
+    let nn = create_delayed_chain(&delays);
+    let config = SolveConfig { max_threads: delays.len().try_into().unwrap(), ..Default::default() };
+
+    // A single poll spawns the tasks, which can't run before this one yields
+    {
+        let mut solve = pin!(nn.solve_with_config(Spike::spike_vec_for(0, vec![1, 4]), config));
+        assert!(poll_fn(|cx| Poll::Ready(solve.as_mut().poll(cx).is_pending())).await);
+    }
+    drop(nn);
+
+    for _ in 0..100 {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_solve_async() {
+    let (nn, spikes) = create_random_lif_nn(
+        8721,
+        10.try_into().unwrap(),
+        5.try_into().unwrap()..20.try_into().unwrap(),
+        75
+    );
+
+    let expected = nn.solve_sequential(spikes.clone());
+    assert!(!expected.is_empty());
+    assert_eq!(nn.solve_async(spikes).await, expected);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_solve_with_config_sync() {
+    let (nn, spikes) = create_random_lif_nn(
+        543513,
+        50.try_into().unwrap(),
+        10.try_into().unwrap()..20.try_into().unwrap(),
+        75
+    );
+
+    let mut solver = Solver::new(spikes.clone(), nn.clone());
+    let expected = solver.solve();
+
+    for max_threads in [1, 3, 50, 64] {
+        let config = SolveConfig { max_threads: max_threads.try_into().unwrap(), ..Default::default() };
+        assert_eq!(nn.solve_with_config(spikes.clone(), config), expected);
+    }
+}
+
+#[test]
+fn test_min_neurons_for_threading_groups() {
+    let config = |min_neurons_for_threading| SolveConfig { max_threads: 8.try_into().unwrap(), min_neurons_for_threading };
+
+    assert_eq!(config(0).max_groups(5_000), Some(8));
+    assert_eq!(config(100).max_groups(250), Some(2));
+    assert_eq!(config(100).max_groups(10_000), Some(8));
+    assert_eq!(config(100).max_groups(100), Some(1));
+    assert_eq!(config(100).max_groups(99), None);
+}
+
+#[test]
+fn test_min_neurons_for_threading_mixed_layers() {
+    let config = LifNeuronConfig::new(0.0, 0.0, 1.0, 1.0);
+    let create_nn = |sizes: &[usize]| NNBuilder::<LeakyIntegrateFire, _>::fully_connected(sizes, &config, &mut Pcg64Mcg::seed_from_u64(0), rand::distributions::Uniform::new(0.0, 1.0))
+        .unwrap()
+        .build()
+        .unwrap();
+    let groups = |nn: &NN<LeakyIntegrateFire>, min_neurons_for_threading| {
+        let config = SolveConfig { max_threads: 8.try_into().unwrap(), min_neurons_for_threading };
+        let num_neurons = nn.layers.iter().map(|layer| layer.neurons.len()).sum();
+
+        group_layers(&nn.layers, config.max_groups(num_neurons).unwrap(), min_neurons_for_threading)
+    };
+
+    // A tiny layer doesn't get a thread of its own, even if there are as many groups as layers
+    let nn = create_nn(&[3, 1000]);
+    assert_eq!(groups(&nn, 0), vec![0..1, 1..2]);
+    assert_eq!(groups(&nn, 100), vec![0..2]);
+
+    // Small layers join their smaller neighbour, until every group is big enough
+    let nn = create_nn(&[3, 400, 2, 50, 400, 5]);
+    assert_eq!(groups(&nn, 0), vec![0..1, 1..2, 2..3, 3..4, 4..5, 5..6]);
+    assert_eq!(groups(&nn, 100), vec![0..2, 2..6]);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_min_neurons_for_threading_sync() {
+    let (nn, spikes) = create_random_lif_nn(
+        543513,
+        50.try_into().unwrap(),
+        10.try_into().unwrap()..20.try_into().unwrap(),
+        75
+    );
+    let expected = nn.solve_on_current_thread(spikes.clone());
+
+    for min_neurons_for_threading in [1, 40, 300, 100_000] {
+        let config = SolveConfig { max_threads: 64.try_into().unwrap(), min_neurons_for_threading };
+        assert_eq!(nn.solve_with_config(spikes.clone(), config), expected);
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_min_neurons_for_threading_async() {
+    let (nn, spikes) = create_random_lif_nn(
+        543513,
+        50.try_into().unwrap(),
+        10.try_into().unwrap()..20.try_into().unwrap(),
+        75
+    );
+    let expected = nn.solve_on_current_thread(spikes.clone());
+
+    for min_neurons_for_threading in [1, 40, 300, 100_000] {
+        let config = SolveConfig { max_threads: 64.try_into().unwrap(), min_neurons_for_threading };
+        assert_eq!(nn.solve_with_config(spikes.clone(), config).await, expected);
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_solve_with_config_async() {
+    let (nn, spikes) = create_random_lif_nn(
+        543513,
+        50.try_into().unwrap(),
+        10.try_into().unwrap()..20.try_into().unwrap(),
+        75
+    );
+
+    let mut solver = Solver::new(spikes.clone(), nn.clone());
+    let expected = solver.solve();
+
+    for max_threads in [1, 3, 50, 64] {
+        let config = SolveConfig { max_threads: max_threads.try_into().unwrap(), ..Default::default() };
+        assert_eq!(nn.solve_with_config(spikes.clone(), config).await, expected);
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_solvers_agree_excitatory_intra_sync() {
+    for seed in 0..10 {
+        let (mut nn, spikes) = create_random_lif_nn(
+            seed,
+            5.try_into().unwrap(),
+            2.try_into().unwrap()..10.try_into().unwrap(),
+            50
+        );
+
+        // Excitatory intra-layer synapses from every neuron to the following ones make neurons fire each other in cascade
+        for layer in nn.layers.iter_mut() {
+            layer.intra_weights.indexed_iter_mut()
+                .filter(|((from, to), _)| from < to)
+                .for_each(|(_, w)| *w = -*w * 2.0);
+        }
+
+        let mut solver = Solver::new(spikes.clone(), nn.clone());
+        let expected = solver.solve();
+
+        assert_eq!(
+            nn.solve_sequential(spikes.clone()),
+            Spike::create_terminal_vec(expected.iter().cloned().enumerate().map(|(i, ts)| Spike::spike_vec_for(i, ts)).collect())
+        );
+        assert_eq!(nn.solve(spikes), expected);
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_solvers_agree_excitatory_intra_async() {
+    for seed in 0..10 {
+        let (mut nn, spikes) = create_random_lif_nn(
+            seed,
+            5.try_into().unwrap(),
+            2.try_into().unwrap()..10.try_into().unwrap(),
+            50
+        );
+
+        // Excitatory intra-layer synapses from every neuron to the following ones make neurons fire each other in cascade
+        for layer in nn.layers.iter_mut() {
+            layer.intra_weights.indexed_iter_mut()
+                .filter(|((from, to), _)| from < to)
+                .for_each(|(_, w)| *w = -*w * 2.0);
+        }
+
+        let mut solver = Solver::new(spikes.clone(), nn.clone());
+        let expected = solver.solve();
+
+        assert_eq!(
+            nn.solve_sequential(spikes.clone()),
+            Spike::create_terminal_vec(expected.iter().cloned().enumerate().map(|(i, ts)| Spike::spike_vec_for(i, ts)).collect())
+        );
+        assert_eq!(nn.solve(spikes).await, expected);
+    }
+}
+
+/// Number of output spikes that appear in only one of the two outputs
+fn count_output_differences(a: &[Vec<u128>], b: &[Vec<u128>]) -> usize {
+    a.iter().zip(b).map(|(a, b)| {
+        a.iter().filter(|ts| !b.contains(ts)).count() + b.iter().filter(|ts| !a.contains(ts)).count()
+    }).sum()
+}
+
+#[test]
+fn test_quantize_weights_grid() {
+    let (mut nn, _) = create_random_lif_nn(
+        498247,
+        15.try_into().unwrap(),
+        4.try_into().unwrap()..12.try_into().unwrap(),
+        25
+    );
+    let original = nn.clone();
+    let (min, max) = (-1.0, 2.5);
+    let step = (max - min) / 15.0;
+
+    let error = nn.quantize_weights(4, (min, max));
+    assert!(error > 0.0 && error <= step / 2.0);
+
+    for (layer, original_layer) in nn.layers.iter().zip(&original.layers) {
+        for (w, original_w) in layer.input_weights.iter().chain(&layer.intra_weights)
+            .zip(original_layer.input_weights.iter().chain(&original_layer.intra_weights))
+        {
+            if *original_w == 0.0 {
+                assert_eq!(*w, 0.0);
+            } else {
+                let level = (w - min) / step;
+                assert!((level - level.round()).abs() < 1e-9);
+                assert!((0.0..=15.0).contains(&level.round()));
+                assert!((w - original_w).abs() <= step / 2.0 + 1e-9);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_quantize_weights_precision_sync() {
+    let (nn, spikes) = create_random_lif_nn(
+        498247,
+        15.try_into().unwrap(),
+        4.try_into().unwrap()..12.try_into().unwrap(),
+        25
+    );
+    let expected = nn.solve(spikes.clone());
+
+    let mut nn_8 = nn.clone();
+    let mut nn_2 = nn;
+    assert!(nn_8.quantize_weights(8, (-1.0, 2.5)) < nn_2.quantize_weights(2, (-1.0, 2.5)));
+
+    assert!(
+        count_output_differences(&expected, &nn_8.solve(spikes.clone())) <
+        count_output_differences(&expected, &nn_2.solve(spikes))
+    );
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_quantize_weights_precision_async() {
+    let (nn, spikes) = create_random_lif_nn(
+        498247,
+        15.try_into().unwrap(),
+        4.try_into().unwrap()..12.try_into().unwrap(),
+        25
+    );
+    let expected = nn.solve(spikes.clone()).await;
+
+    let mut nn_8 = nn.clone();
+    let mut nn_2 = nn;
+    assert!(nn_8.quantize_weights(8, (-1.0, 2.5)) < nn_2.quantize_weights(2, (-1.0, 2.5)));
+
+    assert!(
+        count_output_differences(&expected, &nn_8.solve(spikes.clone()).await) <
+        count_output_differences(&expected, &nn_2.solve(spikes).await)
+    );
+}
+
+#[test]
+fn test_quantize_symmetric_grid() {
+    let (mut nn, _) = create_random_lif_nn(
+        77215,
+        10.try_into().unwrap(),
+        4.try_into().unwrap()..12.try_into().unwrap(),
+        25
+    );
+    add_random_skips(&mut nn, 77215, -1.0..2.5);
+    let original = nn.clone();
+
+    let scales = nn.quantize(8);
+    assert_eq!(scales.len(), nn.layers.len());
+
+    for ((layer, original_layer), scales) in nn.layers.iter().zip(&original.layers).zip(scales) {
+        assert_eq!(scales.skips.len(), layer.skips.len());
+
+        for (weights, original_weights, scale) in [
+            (&layer.input_weights, &original_layer.input_weights, scales.input),
+            (&layer.intra_weights, &original_layer.intra_weights, scales.intra)
+        ].into_iter().chain(
+            layer.skips.iter().zip(&original_layer.skips).zip(&scales.skips)
+                .map(|(((_, weights), (_, original_weights)), &scale)| (weights, original_weights, scale))
+        ) {
+            let max = original_weights.iter().fold(0.0, |max: f64, w| max.max(w.abs()));
+            assert!((scale - max / 127.0).abs() < 1e-12);
+
+            for (w, original_w) in weights.iter().zip(original_weights) {
+                let level = w / scale;
+                assert!((level - level.round()).abs() < 1e-9);
+                assert!(level.round().abs() <= 127.0);
+                assert!((w - original_w).abs() <= scale / 2.0 + 1e-9);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_quantize_degrades_gracefully() {
+    let (nn, spikes) = create_random_lif_nn(
+        77215,
+        10.try_into().unwrap(),
+        4.try_into().unwrap()..12.try_into().unwrap(),
+        25
+    );
+    let expected = nn.solve_on_current_thread(spikes.clone());
+    let num_spikes = expected.iter().map(Vec::len).sum::<usize>();
+
+    let mut nn_8 = nn.clone();
+    let mut nn_2 = nn;
+    nn_8.quantize(8);
+    nn_2.quantize(2);
+
+    let differences_8 = count_output_differences(&expected, &nn_8.solve_on_current_thread(spikes.clone()));
+    let differences_2 = count_output_differences(&expected, &nn_2.solve_on_current_thread(spikes));
+
+    assert!(num_spikes > 0);
+    assert!(differences_8 < differences_2);
+    assert!(differences_8 * 10 <= num_spikes);
+}
+
+/// Random weights matrix, and a spike vector with `active` neurons firing
+fn random_weights_and_spike(seed: u64, size: (usize, usize), active: usize) -> (Array2<f64>, Array2<f64>) {
+    let mut rng = Pcg64Mcg::seed_from_u64(seed);
+    let weights = Array2::from_shape_fn(size, |_| rng.gen_range(-1.0..2.5));
+    let mut spike = Array2::zeros((1, size.0));
+
+    for i in rand::seq::index::sample(&mut rng, size.0, active) {
+        spike[(0, i)] = 1.0;
+    }
+
+    (weights, spike)
+}
+
+#[test]
+fn test_clamp_and_normalize_weights() {
+    let (mut nn, _) = create_random_lif_nn(
+        98123,
+        4.try_into().unwrap(),
+        2.try_into().unwrap()..20.try_into().unwrap(),
+        0
+    );
+    add_random_skips(&mut nn, 98123, -1.0..2.5);
+    let original = nn.clone();
+
+    // Weights are in -1.0..2.5: the null ones are left alone, the others are clamped
+    nn.clamp_weights(-0.5, 1.0);
+    for (layer, original) in nn.layers.iter().zip(&original.layers) {
+        let weights = layer.input_weights.iter().chain(&layer.intra_weights).chain(layer.skips.iter().flat_map(|(_, w)| w));
+        let original_weights = original.input_weights.iter().chain(&original.intra_weights).chain(original.skips.iter().flat_map(|(_, w)| w));
+
+        for (w, original) in weights.zip(original_weights) {
+            assert_eq!(*w, if *original == 0.0 { 0.0 } else { original.clamp(-0.5, 1.0) });
+        }
+    }
+
+    let mut nn = original.clone();
+    nn.normalize_incoming(3.0);
+    assert_eq!(nn.layers[0].input_weights, original.layers[0].input_weights);
+    for (layer, original) in nn.layers.iter().zip(&original.layers).skip(1) {
+        assert_eq!(layer.intra_weights, original.intra_weights);
+
+        for neuron_id in 0..layer.neurons.len() {
+            let incoming = |layer: &crate::Layer<LeakyIntegrateFire>| std::iter::once(&layer.input_weights)
+                .chain(layer.skips.iter().map(|(_, w)| w))
+                .flat_map(|w| w.column(neuron_id).to_vec())
+                .collect::<Vec<_>>();
+            let (incoming, original) = (incoming(layer), incoming(original));
+            let ratio = original.iter().map(|w| w.abs()).sum::<f64>() / 3.0;
+
+            assert!((incoming.iter().map(|w| w.abs()).sum::<f64>() - 3.0).abs() < 1e-9);
+            assert!(incoming.iter().zip(&original).all(|(w, original)| (w * ratio - original).abs() < 1e-9));
+        }
+    }
+}
+
+#[test]
+fn test_weigh_matches_dot() {
+    for active in [0, 1, 5, 20, 200, 2000] {
+        let (weights, spike) = random_weights_and_spike(6843218, (2000, 300), active);
+        let expected = spike.dot(&weights);
+        let res = weigh(&spike, &weights);
+
+        assert!(res.is_standard_layout());
+        assert_eq!(res.dim(), expected.dim());
+        assert!(res.iter().zip(&expected).all(|(a, b)| (a - b).abs() < 1e-9));
+    }
+}
+
+#[test]
+fn test_synapse_stats() {
+    let (nn, _) = create_random_lif_nn(
+        2394871,
+        6.try_into().unwrap(),
+        2.try_into().unwrap()..40.try_into().unwrap(),
+        0
+    );
+    let sizes = nn.layers.iter().map(|l| l.neurons.len()).collect::<Vec<_>>();
+
+    // Entry diagonal, full input matrices of the other layers and full intra-layer matrices
+    let total = sizes[0] + sizes.windows(2).map(|w| w[0] * w[1]).sum::<usize>() + sizes.iter().map(|n| n * n).sum::<usize>();
+    // Only the diagonals of the intra-layer matrices are null
+    let nonzero = total - sizes.iter().sum::<usize>();
+
+    let stats = nn.synapse_stats(0.0);
+    assert_eq!((stats.total, stats.nonzero), (total, nonzero));
+    assert!((stats.sparsity - (total - nonzero) as f64 / total as f64).abs() < 1e-12);
+
+    // No weight is greater than 2.5 in absolute value
+    assert_eq!(nn.synapse_stats(2.5).nonzero, 0);
+    assert_eq!(nn.synapse_stats(2.5).sparsity, 1.0);
+}
+
+#[test]
+fn test_fuzz_solve_resumable() {
+    for seed in 0..20 {
+        let (mut nn, spikes) = create_random_lif_nn(
+            seed,
+            4.try_into().unwrap(),
+            1.try_into().unwrap()..10.try_into().unwrap(),
+            40
+        );
+        if seed % 2 == 0 {
+            add_random_delays(&mut nn, seed, 10);
+        }
+        if seed % 4 == 0 {
+            add_random_skips(&mut nn, seed, 0.5..2.5);
+        }
+        let expected = nn.solve_sequential(spikes.clone());
+
+        // Split the input stream at random timestamps, never splitting the spikes with the same timestamp
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+        let mut chunks = vec![vec![]];
+        for spike in spikes {
+            let last = chunks.last().unwrap();
+            if last.last().is_some_and(|s: &Spike| s.ts != spike.ts) && rng.gen_bool(0.3) {
+                chunks.push(vec![]);
+            }
+            chunks.last_mut().unwrap().push(spike);
+        }
+
+        let mut output = vec![];
+        let mut state = None;
+        for chunk in chunks.into_iter().chain([vec![]]) {
+            let (chunk_output, new_state) = nn.solve_resumable(chunk, state);
+            output.extend(chunk_output);
+            state = Some(new_state);
+        }
+
+        assert!(!state.unwrap().has_pending_inputs());
+        assert_eq!(Spike::create_terminal_vec(vec![output]), expected);
+    }
+}
+
+#[test]
+fn test_validate() {
+    use crate::nn::{ValidationIssue::*, Synapses};
+
+    for seed in 0..10 {
+        let (mut nn, _) = create_random_lif_nn(
+            seed,
+            4.try_into().unwrap(),
+            1.try_into().unwrap()..10.try_into().unwrap(),
+            0
+        );
+        add_random_delays(&mut nn, seed, 10);
+        add_random_skips(&mut nn, seed, 0.5..2.5);
+
+        assert_eq!(nn.validate(), Ok(()));
+    }
+
+    let (mut nn, _) = create_random_lif_nn(
+        7,
+        3.try_into().unwrap(),
+        3.try_into().unwrap()..4.try_into().unwrap(),
+        0
+    );
+    nn.layers[0].input_weights[(1, 2)] = 1.0;
+    nn.layers[1].input_weights = Array2::zeros((2, 3));
+    nn.layers[1].input_delays = Some(Array2::zeros((3, 3)));
+    nn.layers[1].intra_weights[(1, 1)] = 0.5;
+    nn.layers[2].intra_weights[(0, 2)] = f64::INFINITY;
+    nn.layers[2].skips.push((3, Array2::zeros((3, 3))));
+    nn.layers[2].skips.push((2, Array2::zeros((3, 1))));
+    nn.heads = vec![1, 1];
+
+    assert_eq!(nn.validate(), Err(vec![
+        OffDiagonalInput { from: 1, to: 2 },
+        WeightsShape { layer: 1, synapses: Synapses::Input, expected: (3, 3), found: (2, 3) },
+        SelfSynapse { layer: 1, neuron: 1 },
+        DelaysShape { layer: 1, synapses: Synapses::Input, expected: (2, 3), found: (3, 3) },
+        InvalidSkip { layer: 2, back: 3 },
+        NonFiniteWeight { layer: 2, synapses: Synapses::Intra, from: 0, to: 2 },
+        WeightsShape { layer: 2, synapses: Synapses::Skip(2), expected: (3, 3), found: (3, 1) },
+        HeadsMismatch { heads: vec![1, 1], found: 3 }
+    ]));
+
+    nn.layers.clear();
+    assert_eq!(nn.validate(), Err(vec![NoLayers]));
+}
+
 #[cfg(feature = "bench")]
 mod benches {
     extern crate test;
     use test::{Bencher, black_box};
     #[cfg(feature = "async")]
     use tokio::runtime::Builder;
+    #[cfg(not(feature = "async"))]
+    use super::super::SolveConfig;
 
-    use super::{create_random_lif_nn, super::solver_v1::Solver};
+    use super::{create_random_lif_nn, random_weights_and_spike, super::{solver_v1::Solver, layer::weigh}};
 
     #[bench]
     fn bench_tiny_single(b: &mut Bencher) {
@@ -358,6 +1160,21 @@ mod benches {
         b.iter(|| black_box(nn.solve(spikes.clone())));
     }
 
+    /// One thread for every layer, like the solver used to do
+    #[cfg(not(feature = "async"))]
+    #[bench]
+    fn bench_big_multi_thread_per_layer(b: &mut Bencher) {
+        let (nn, spikes) = create_random_lif_nn(
+            136415635468,
+            200.try_into().unwrap(),
+            20.try_into().unwrap()..35.try_into().unwrap(),
+            350
+        );
+        let config = SolveConfig { max_threads: nn.layers.len().try_into().unwrap(), ..Default::default() };
+
+        b.iter(|| black_box(nn.solve_with_config(spikes.clone(), config)));
+    }
+
     #[cfg(feature = "async")]
     #[bench]
     fn bench_big_async(b: &mut Bencher) {
@@ -422,4 +1239,32 @@ mod benches {
 
         b.iter(|| runtime.block_on(black_box(nn.solve(spikes.clone()))));
     }
+
+    #[bench]
+    fn bench_wide_layer_sparse_dot(b: &mut Bencher) {
+        let (weights, spike) = random_weights_and_spike(6843218, (2000, 2000), 5);
+
+        b.iter(|| black_box(spike.dot(&weights)));
+    }
+
+    #[bench]
+    fn bench_wide_layer_sparse_weigh(b: &mut Bencher) {
+        let (weights, spike) = random_weights_and_spike(6843218, (2000, 2000), 5);
+
+        b.iter(|| black_box(weigh(&spike, &weights)));
+    }
+
+    #[bench]
+    fn bench_wide_layer_dense_dot(b: &mut Bencher) {
+        let (weights, spike) = random_weights_and_spike(6843218, (2000, 2000), 1000);
+
+        b.iter(|| black_box(spike.dot(&weights)));
+    }
+
+    #[bench]
+    fn bench_wide_layer_dense_weigh(b: &mut Bencher) {
+        let (weights, spike) = random_weights_and_spike(6843218, (2000, 2000), 1000);
+
+        b.iter(|| black_box(weigh(&spike, &weights)));
+    }
 }
@@ -5,268 +5,436 @@ use tokio::sync::mpsc::{Receiver, Sender};
 #[cfg(not(feature = "async"))]
 use std::sync::mpsc::{Receiver, Sender};
 
+use std::{ops::Range, collections::BTreeMap};
 use ndarray::Array2;
 
-use crate::{nn::layer::Layer, Model};
+use crate::{nn::{layer::{Layer, weigh}, sequential::Pipeline, model::fired, Float}, Model};
+
+/// State of a solve of a layer: the [SolverVars](Model::SolverVars) of its neurons and the inputs still traveling through delayed synapses,
+/// keyed by arrival time
+pub(crate) type LayerState<M> = (Vec<<M as Model>::SolverVars>, BTreeMap<u128, Array2<f64>>);
 
 /// Linked with a [NN](crate::NN)'s [Layer], this "solves" that layer.
-/// 
-/// Spikes are received through an mpsc channel as [Array2]s of the previous layer's neurons' outputs.
-/// After applying said input to every neuron in this layer, an output array is constructed and passed to the next layer
-/// via a [Sender], and the same spike is then reapplied to the same neurons via the intra-weights.
-/// 
+///
+/// Spikes are fed one at a time as [Array2]s of the previous layer's neurons' outputs.
+/// After applying said input to every neuron in this layer, an output array is constructed and handed to the caller,
+/// and the same spike is then reapplied to the same neurons via the intra-weights.
+/// The neurons are thus updated simultaneously: none of them sees the intra-layer feedback of the others before the whole layer
+/// has been updated with the same input, whatever the order of the updates.
+///
+/// If the input (or intra-layer) synapses of the layer have delays, the weighted inputs are instead queued until their arrival time,
+/// and only applied once no earlier input can be received anymore.
+///
 /// This struct's lifetime is that of the [NN](crate::NN) it references the [Layer] from.
-pub(crate) struct LayerManager<'a, M: Model> {
+pub(crate) struct LayerManager<'a, M: Model, F: Float> {
     /// Reference to the [NN](crate::NN)'s [Layer] this manager is for
-    layer: &'a Layer<M>,
-    /// [Vec] of the [SolverVars](Model::SolverVars) for every neuron in this layer.
+    layer: &'a Layer<M, F>,
+    /// Weighted inputs still traveling through delayed synapses, keyed by arrival time
+    pending: BTreeMap<u128, Array2<f64>>,
+    /// Whether only the neurons receiving a nonzero input are updated, see [new_event_driven](LayerManager::new_event_driven)
+    event_driven: bool,
+    /// [Vec] of the [SolverVars](Model::SolverVars) for every neuron in this layer (only for the neurons that don't fit in a simd vector, with the `simd` feature).
     /// [SolverVars](Model::SolverVars) contain the mutable portion of the neuron, which must be dynamic during the solve process.
     vars: Vec<M::SolverVars>,
-    /// Mpsc [Receiver] linked to the previous layer's sender
-    receiver: Receiver<(u128, Array2<f64>)>,
-    /// Mpsc [Sender] linked to the next layer's receiver
-    sender: Sender<(u128, Array2<f64>)>,
+    /// Simd aggregates of the neurons of this layer, four at a time
+    #[cfg(feature = "simd")]
+    neurons_x4: Vec<M::Neuronx4>,
+    /// Simd aggregates of the [SolverVars](Model::SolverVars) of this layer, four at a time
+    #[cfg(feature = "simd")]
+    vars_x4: Vec<M::SolverVarsx4>
 }
 
-impl<'a, M: Model> LayerManager<'a, M> where for<'b> &'b M::Neuron: Into<M::SolverVars> {
+impl<'a, M: Model, F: Float> LayerManager<'a, M, F> where for<'b> &'b M::Neuron: Into<M::SolverVars> {
     /// Build a new instance of [LayerManager] for the provided [Layer].
-    /// 
-    /// `receiver` must be linked to the previous layer's manager, and `sender` to the next layer's receiver.
-    pub fn new(
-        layer: &'a Layer<M>,
-        receiver: Receiver<(u128, Array2<f64>)>,
-        sender: Sender<(u128, Array2<f64>)>, 
-    ) -> Self
-    {
+    #[cfg(not(feature = "simd"))]
+    pub fn new(layer: &'a Layer<M, F>) -> Self {
         let vars = layer.neurons.iter().map(|neuron| neuron.into()).collect();
-    
+
+        Self {
+            layer,
+            pending: BTreeMap::new(),
+            event_driven: false,
+            vars
+        }
+    }
+
+    /// Build a new instance of [LayerManager] for the provided [Layer].
+    #[cfg(feature = "simd")]
+    pub fn new(layer: &'a Layer<M, F>) -> Self {
+        let mut vars = layer.neurons.iter().map(|neuron| neuron.into()).collect::<Vec<_>>();
+
+        let neurons_x4 = layer.neurons.chunks_exact(4).map(|chunk| M::neuron_x4_from_neurons(chunk)).collect::<Vec<_>>();
+        let vars_x4 = vars.chunks_exact(4).map(|chunk| M::vars_x4_from_vars(chunk)).collect();
+
+        // Only the remainder is kept in scalar form
+        vars.drain(..4*neurons_x4.len());
+
         Self {
             layer,
+            pending: BTreeMap::new(),
+            event_driven: false,
             vars,
-            receiver,
-            sender,
+            neurons_x4,
+            vars_x4
         }
     }
 
-    /// Consume `self` and solve the layer.
-    /// 
-    /// This only returns after the previous layer's manager has completed its `run` and
-    /// dropped its `sender`.
-    #[cfg(all(not(feature = "async"), not(feature = "simd")))]
-    pub fn run(mut self) {
-        for (ts, spike) in self.receiver {
-            let mut weighted_inputs = spike.dot(&self.layer.input_weights);
-
-            loop {
-                let mut spiked = false;
-                
-                let output = Array2::from_shape_fn((1, self.layer.neurons.len()), |(_, neuron_id)| {
-                    let o = M::handle_spike(
-                        &self.layer.neurons[neuron_id],
-                        &mut self.vars[neuron_id],
-                        weighted_inputs[(0, neuron_id)],
-                        ts
-                    );
-                    spiked |= o > 0.5;
-                    o
-                });
-
-                if spiked {
-                    weighted_inputs = output.dot(&self.layer.intra_weights);
-                    self.sender.send((ts, output)).unwrap();
-                } else {
-                    break;
+    /// Build a new instance of [LayerManager] for the provided [Layer], handling every neuron individually
+    /// so that all of them can be observed by [feed](LayerManager::feed).
+    #[cfg(not(feature = "simd"))]
+    pub fn new_scalar(layer: &'a Layer<M, F>) -> Self {
+        Self::new(layer)
+    }
+
+    /// Build a new instance of [LayerManager] for the provided [Layer], handling every neuron individually
+    /// so that all of them can be observed by [feed](LayerManager::feed).
+    #[cfg(feature = "simd")]
+    pub fn new_scalar(layer: &'a Layer<M, F>) -> Self {
+        Self {
+            layer,
+            pending: BTreeMap::new(),
+            event_driven: false,
+            vars: layer.neurons.iter().map(|neuron| neuron.into()).collect(),
+            neurons_x4: vec![],
+            vars_x4: vec![]
+        }
+    }
+
+    /// Build a new instance of [LayerManager] for the provided [Layer], which only updates the neurons receiving a nonzero input.
+    ///
+    /// Every neuron is handled individually like with [new_scalar](LayerManager::new_scalar), but those without input are skipped altogether
+    /// (and not observed): their output is zero, and their [SolverVars](Model::SolverVars) are left untouched until their next input.
+    /// This is only equivalent to updating every neuron if the model ignores zero inputs, like every built-in model does.
+    pub fn new_event_driven(layer: &'a Layer<M, F>) -> Self {
+        Self {
+            event_driven: true,
+            ..Self::new_scalar(layer)
+        }
+    }
+
+    /// Build a new instance of [LayerManager] for the provided [Layer] like [new_scalar](LayerManager::new_scalar),
+    /// resuming the solve from the state returned by [into_state](LayerManager::into_state).
+    pub fn resume(layer: &'a Layer<M, F>, (vars, pending): LayerState<M>) -> Self {
+        Self {
+            vars,
+            pending,
+            ..Self::new_scalar(layer)
+        }
+    }
+
+    /// Take the [SolverVars](Model::SolverVars) of the neurons and the queued inputs out of this manager, to resume the solve later.
+    ///
+    /// Only the managers built by [new_scalar](LayerManager::new_scalar) have the [SolverVars](Model::SolverVars) of all of their neurons.
+    pub fn into_state(self) -> LayerState<M> {
+        (self.vars, self.pending)
+    }
+
+    /// Apply the spike generated by the previous layer (or the network's inputs) at time `ts`.
+    ///
+    /// Every output generated by this layer, including those caused by the intra-layer weights,
+    /// is appended to `out` in order along with its timestamp.
+    /// With delayed synapses, this also applies every queued input arriving until `ts`, so the outputs can be earlier than `ts`.
+    ///
+    /// `observer` is called with the timestamp, the id of the neuron, its weighted input and its [SolverVars](Model::SolverVars)
+    /// right before every update of a neuron handled individually (that is, every neuron unless the `simd` feature is enabled and
+    /// this manager wasn't built by [new_scalar](LayerManager::new_scalar)).
+    pub fn feed(
+        &mut self,
+        ts: u128,
+        spike: &Array2<f64>,
+        out: &mut Vec<(u128, Array2<f64>)>,
+        observer: &mut impl FnMut(u128, usize, f64, &M::SolverVars)
+    ) {
+        let layer = self.layer;
+
+        match &layer.input_delays {
+            None if self.pending.is_empty() => self.integrate(ts, layer.weigh_inputs(spike), out, observer),
+            None => {
+                // Merge with the queued inputs arriving at the same time, and apply the earlier ones first
+                *self.pending.entry(ts).or_insert_with(|| Array2::zeros((1, layer.neurons.len()))) += &layer.weigh_inputs(spike);
+                self.flush(ts, out, observer);
+            },
+            Some(delays) => {
+                layer.schedule_inputs(ts, spike, delays, &mut self.pending);
+                self.flush(ts, out, observer);
+            }
+        }
+    }
+
+    /// Queue `current`, injected straight into the neurons of this layer at time `ts` (bypassing the input synapses),
+    /// so that it's added to the inputs they receive at that time.
+    ///
+    /// The current is only applied by the next [feed](LayerManager::feed) or [flush](LayerManager::flush) reaching `ts`.
+    pub fn inject(&mut self, ts: u128, current: &Array2<f64>) {
+        *self.pending.entry(ts).or_insert_with(|| Array2::zeros((1, self.layer.neurons.len()))) += current;
+    }
+
+    /// Queue the inputs of the skip connections of this layer caused by `outputs`, the outputs of the layers preceding it in order
+    /// (the last one being the previous layer), so that they're applied along with the other inputs like [inject](LayerManager::inject)ed currents.
+    ///
+    /// Skip connections from layers before the first of `outputs` are ignored.
+    pub fn inject_skips(&mut self, outputs: &[Vec<(u128, Array2<f64>)>]) {
+        let layer = self.layer;
+
+        for (back, weights) in &layer.skips {
+            if let Some(source) = outputs.len().checked_sub(*back).map(|i| &outputs[i]) {
+                for (ts, output) in source {
+                    self.inject(*ts, &weigh(output, weights));
                 }
             }
         }
     }
 
-    /// Consume `self` and solve the layer.
-    /// 
-    /// This only returns after the previous layer's manager has completed its `run` and
-    /// dropped its `sender`.
-    #[cfg(all(not(feature = "async"), feature = "simd"))]
-    pub fn run(mut self) {
+    /// Apply every queued input arriving until `until` (included), appending the generated outputs to `out` like [feed](LayerManager::feed).
+    ///
+    /// This must be called with [u128::MAX] once the previous layer has generated all of its spikes.
+    pub fn flush(&mut self, until: u128, out: &mut Vec<(u128, Array2<f64>)>, observer: &mut impl FnMut(u128, usize, f64, &M::SolverVars)) {
+        while let Some(entry) = self.pending.first_entry() {
+            if *entry.key() > until {
+                break;
+            }
+
+            let (ts, weighted_inputs) = entry.remove_entry();
+            self.integrate(ts, weighted_inputs, out, observer);
+        }
+    }
+
+    /// Apply the already weighted inputs to the neurons at time `ts`, and then reapply every output via the intra-layer weights
+    /// (queueing the inputs of the delayed intra-layer synapses).
+    #[cfg(not(feature = "simd"))]
+    fn integrate(
+        &mut self,
+        ts: u128,
+        mut weighted_inputs: Array2<f64>,
+        out: &mut Vec<(u128, Array2<f64>)>,
+        observer: &mut impl FnMut(u128, usize, f64, &M::SolverVars)
+    ) {
+        loop {
+            let mut spiked = false;
+
+            let output = Array2::from_shape_fn((1, self.layer.neurons.len()), |(_, neuron_id)| {
+                if self.event_driven && weighted_inputs[(0, neuron_id)] == 0.0 {
+                    return 0.0;
+                }
+
+                observer(ts, neuron_id, weighted_inputs[(0, neuron_id)], &self.vars[neuron_id]);
+
+                let o = M::handle_spike(
+                    &self.layer.neurons[neuron_id],
+                    &mut self.vars[neuron_id],
+                    weighted_inputs[(0, neuron_id)],
+                    ts
+                );
+                spiked |= fired::<M>(o);
+                o
+            });
+
+            if spiked {
+                weighted_inputs = self.layer.schedule_intra(ts, &output, &mut self.pending);
+                out.push((ts, output));
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Apply the already weighted inputs to the neurons at time `ts`, and then reapply every output via the intra-layer weights
+    /// (queueing the inputs of the delayed intra-layer synapses).
+    #[cfg(feature = "simd")]
+    fn integrate(
+        &mut self,
+        ts: u128,
+        mut weighted_inputs: Array2<f64>,
+        out: &mut Vec<(u128, Array2<f64>)>,
+        observer: &mut impl FnMut(u128, usize, f64, &M::SolverVars)
+    ) {
         use packed_simd::f64x4;
 
-        let (neurons, neuron_remainder) = {
-            let chunks = self.layer.neurons.chunks_exact(4);
-            let remainder = chunks.remainder();
-            
-            (
-                chunks.into_iter().map(|chunk| M::neuron_x4_from_neurons(chunk)).collect::<Vec<_>>(),
-                remainder
-            )
-        };
+        let num_vec = self.neurons_x4.len();
+        let neuron_remainder = &self.layer.neurons[4*num_vec..];
 
-        let num_vec = neurons.len();
+        loop {
+            let mut spiked = false;
+            let mut output = Array2::zeros((1, self.layer.neurons.len()));
+            let weighted_inputs_slice = weighted_inputs.as_slice().unwrap();
+            let output_slice = output.as_slice_mut().unwrap();
 
-        let (mut vars, vars_remainder) = {
-            let chunks = self.vars.chunks_exact_mut(4);
-            
-            (
-                chunks.into_iter().map(|chunk| M::vars_x4_from_vars(chunk)).collect::<Vec<_>>(),
-                &mut self.vars[4*num_vec..]
-            )
-        };
+            for (i, (neurons, vars)) in self.neurons_x4.iter().zip(self.vars_x4.iter_mut()).enumerate() {
+                let o = M::handle_spike_x4(
+                    neurons,
+                    vars,
+                    unsafe { f64x4::from_slice_unaligned_unchecked(&weighted_inputs_slice[4*i..(4*i + 4)]) },
+                    ts
+                );
 
-        let mut weighted_inputs;
+                spiked |= o.gt(f64x4::splat(M::FIRING_THRESHOLD)).any();
 
-        for (ts, spike) in self.receiver {
-            weighted_inputs = spike.dot(&self.layer.input_weights);
-            let mut weighted_inputs_slice = weighted_inputs.as_slice().unwrap();
-
-            loop {
-                let mut spiked = false;
-                let mut output = Array2::zeros((1, self.layer.neurons.len()));
-                let output_slice = output.as_slice_mut().unwrap();
-
-                for (i, (neurons, vars)) in neurons.iter().zip(vars.iter_mut()).enumerate() {
-                    let o = M::handle_spike_x4(
-                        neurons,
-                        vars,
-                        unsafe { f64x4::from_slice_unaligned_unchecked(&weighted_inputs_slice[4*i..(4*i + 4)]) },
-                        ts
-                    );
-
-                    spiked |= o.gt(f64x4::splat(0.5)).any();
-
-                    unsafe {
-                        o.write_to_slice_unaligned_unchecked(&mut output_slice[4*i..(4*i + 4)]);
-                    };
-                }
+                unsafe {
+                    o.write_to_slice_unaligned_unchecked(&mut output_slice[4*i..(4*i + 4)]);
+                };
+            }
 
-                for (i, (neuron, vars)) in neuron_remainder.iter().zip(vars_remainder.iter_mut()).enumerate() {
-                    let o = M::handle_spike(
-                        neuron,
-                        vars,
-                        weighted_inputs[(0, num_vec*4 + i)],
-                        ts
-                    );
-                    spiked |= o > 0.5;
-                    output[(0, num_vec*4 + i)] = o;
+            for (i, (neuron, vars)) in neuron_remainder.iter().zip(self.vars.iter_mut()).enumerate() {
+                if self.event_driven && weighted_inputs[(0, num_vec*4 + i)] == 0.0 {
+                    continue;
                 }
 
-                if spiked {
-                    weighted_inputs = output.dot(&self.layer.intra_weights);
-                    weighted_inputs_slice = weighted_inputs.as_slice().unwrap();
-                    self.sender.send((ts, output)).unwrap();
-                } else {
-                    break;
-                }
+                observer(ts, num_vec*4 + i, weighted_inputs[(0, num_vec*4 + i)], vars);
+
+                let o = M::handle_spike(
+                    neuron,
+                    vars,
+                    weighted_inputs[(0, num_vec*4 + i)],
+                    ts
+                );
+                spiked |= fired::<M>(o);
+                output[(0, num_vec*4 + i)] = o;
+            }
+
+            if spiked {
+                weighted_inputs = self.layer.schedule_intra(ts, &output, &mut self.pending);
+                out.push((ts, output));
+            } else {
+                break;
             }
         }
     }
+}
+
+/// Linked with a contiguous group of a [NN](crate::NN)'s [Layer]s, this "solves" them on a single thread (or task).
+///
+/// Spikes are received through an mpsc channel as [Array2]s of the outputs of the neurons of the layer preceding the group,
+/// and pushed through every layer of the group. The outputs of the last layer of the group are then passed to the
+/// next group via a [Sender].
+pub(crate) struct GroupManager<'a, M: Model, F: Float> {
+    /// Chain of the [LayerManager]s of this group
+    pipeline: Pipeline<'a, M, F>,
+    /// Mpsc [Receiver] linked to the previous group's sender
+    receiver: Receiver<(u128, Array2<f64>)>,
+    /// Mpsc [Sender] linked to the next group's receiver
+    sender: Sender<(u128, Array2<f64>)>
+}
+
+impl<'a, M: Model, F: Float> GroupManager<'a, M, F> where for<'b> &'b M::Neuron: Into<M::SolverVars> {
+    /// Build a new instance of [GroupManager] for the provided [Layer]s.
+    ///
+    /// `receiver` must be linked to the previous group's manager, and `sender` to the next group's receiver.
+    pub fn new(
+        layers: &'a [Layer<M, F>],
+        receiver: Receiver<(u128, Array2<f64>)>,
+        sender: Sender<(u128, Array2<f64>)>
+    ) -> Self {
+        Self {
+            pipeline: Pipeline::new(layers),
+            receiver,
+            sender
+        }
+    }
 
-    /// Consume `self` and solve the layer.
-    /// 
-    /// This `Future` only resolves after the previous layer's manager has completed its `run` and
+    /// Consume `self` and solve the group.
+    ///
+    /// This only returns after the previous group's manager has completed its `run` and
     /// dropped its `sender`.
-    #[cfg(all(feature = "async", not(feature = "simd")))]
-    pub async fn run(mut self) {
-        let mut weighted_inputs;
-        
-        while let Some((ts, spike)) = self.receiver.recv().await {
-            weighted_inputs = spike.dot(&self.layer.input_weights);
-
-            loop {
-                let mut spiked = false;
-
-                let output = Array2::from_shape_fn((1, self.layer.neurons.len()), |(_, neuron_id)| {
-                    let o = M::handle_spike(
-                        &self.layer.neurons[neuron_id],
-                        &mut self.vars[neuron_id],
-                        weighted_inputs[(0, neuron_id)],
-                        ts
-                    );
-                    spiked |= o > 0.5;
-                    o
-                });
-                
-                if spiked {
-                    weighted_inputs = output.dot(&self.layer.intra_weights);
-                    self.sender.send((ts, output)).await.unwrap();
-                } else {
-                    break;
-                }
+    /// Every input still traveling through delayed synapses is then applied, so that no trailing spike is lost
+    /// when this group's own `sender` is dropped.
+    ///
+    /// If the next group's manager is gone, because its thread panicked, this returns early, leaving that panic to be
+    /// reported by whoever joins its thread.
+    #[cfg(not(feature = "async"))]
+    pub fn run(mut self) {
+        for (ts, spike) in self.receiver {
+            for output in self.pipeline.feed(ts, spike, |_, _, _| ()) {
+                if self.sender.send(output).is_err() { return }
             }
         }
+
+        for output in self.pipeline.finish(|_, _, _| ()) {
+            if self.sender.send(output).is_err() { return }
+        }
     }
 
-    /// Consume `self` and solve the layer.
-    /// 
-    /// This `Future` only resolves after the previous layer's manager has completed its `run` and
+    /// Consume `self` and solve the group.
+    ///
+    /// This `Future` only resolves after the previous group's manager has completed its `run` and
     /// dropped its `sender`.
-    #[cfg(all(feature = "async", feature = "simd"))]
+    /// Every input still traveling through delayed synapses is then applied, so that no trailing spike is lost
+    /// when this group's own `sender` is dropped.
+    ///
+    /// If the next group's manager is gone, because its task panicked, this resolves early, leaving that panic to be
+    /// reported by whoever awaits its task.
+    #[cfg(feature = "async")]
     pub async fn run(mut self) {
-        use packed_simd::f64x4;
+        while let Some((ts, spike)) = self.receiver.recv().await {
+            for output in self.pipeline.feed(ts, spike, |_, _, _| ()) {
+                if self.sender.send(output).await.is_err() { return }
+            }
+        }
 
-        let (neurons, neuron_remainder) = {
-            let chunks = self.layer.neurons.chunks_exact(4);
-            let remainder = chunks.remainder();
-            
-            (
-                chunks.into_iter().map(|chunk| M::neuron_x4_from_neurons(chunk)).collect::<Vec<_>>(),
-                remainder
-            )
-        };
+        for output in self.pipeline.finish(|_, _, _| ()) {
+            if self.sender.send(output).await.is_err() { return }
+        }
+    }
+}
 
-        let num_vec = neurons.len();
+/// Split `layers` in at most `max_groups` contiguous groups, each with roughly the same number of neurons.
+///
+/// No group is ever empty, and the two ends of a skip connection always belong to the same group, merging groups if needed.
+/// Groups with fewer than `min_neurons` neurons are merged into a neighbour too, unless they're left alone.
+pub(crate) fn group_layers<M: Model, F: Float>(layers: &[Layer<M, F>], max_groups: usize, min_neurons: usize) -> Vec<Range<usize>> {
+    let num_groups = max_groups.clamp(1, layers.len().max(1));
+    let total = layers.iter().map(|layer| layer.neurons.len()).sum::<usize>();
 
-        let (mut vars, vars_remainder) = {
-            let chunks = self.vars.chunks_exact_mut(4);
-            
-            (
-                chunks.into_iter().map(|chunk| M::vars_x4_from_vars(chunk)).collect::<Vec<_>>(),
-                &mut self.vars[4*num_vec..]
-            )
-        };
+    let mut groups = Vec::with_capacity(num_groups);
+    let mut start = 0;
+    let mut acc = 0;
 
-        let mut weighted_inputs;
+    for (i, layer) in layers.iter().enumerate() {
+        acc += layer.neurons.len();
 
-        while let Some((ts, spike)) = self.receiver.recv().await {
-            weighted_inputs = spike.dot(&self.layer.input_weights);
-            let mut weighted_inputs_slice = weighted_inputs.as_slice().unwrap();
-
-            loop {
-                let mut spiked = false;
-                let mut output = Array2::zeros((1, self.layer.neurons.len()));
-                let output_slice = output.as_slice_mut().unwrap();
-
-                for (i, (neurons, vars)) in neurons.iter().zip(vars.iter_mut()).enumerate() {
-                    let o = M::handle_spike_x4(
-                        neurons,
-                        vars,
-                        unsafe { f64x4::from_slice_unaligned_unchecked(&weighted_inputs_slice[4*i..(4*i + 4)]) },
-                        ts
-                    );
-
-                    spiked |= o.gt(f64x4::splat(0.5)).any();
-
-                    unsafe {
-                        o.write_to_slice_unaligned_unchecked(&mut output_slice[4*i..(4*i + 4)]);
-                    };
-                }
+        let remaining_layers = layers.len() - i - 1;
+        let remaining_groups = num_groups - groups.len() - 1;
 
-                for (i, (neuron, vars)) in neuron_remainder.iter().zip(vars_remainder.iter_mut()).enumerate() {
-                    let o = M::handle_spike(
-                        neuron,
-                        vars,
-                        weighted_inputs[(0, num_vec*4 + i)],
-                        ts
-                    );
-                    spiked |= o > 0.5;
-                    output[(0, num_vec*4 + i)] = o;
-                }
+        // Close the group once it reached its share of neurons, as long as there are enough layers left for the others
+        if remaining_groups > 0 && (acc * num_groups >= total * (groups.len() + 1) || remaining_layers == remaining_groups) {
+            groups.push(start..i+1);
+            start = i + 1;
+        }
+    }
 
-                if spiked {
-                    weighted_inputs = output.dot(&self.layer.intra_weights);
-                    weighted_inputs_slice = weighted_inputs.as_slice().unwrap();
-                    self.sender.send((ts, output)).await.unwrap();
-                } else {
-                    break;
-                }
+    if start < layers.len() {
+        groups.push(start..layers.len());
+    }
+
+    // The layers between the two ends of a skip connection must not be split
+    for (to, layer) in layers.iter().enumerate() {
+        for (back, _) in &layer.skips {
+            let from = to - back;
+            let first = groups.iter().position(|group| group.contains(&from)).unwrap();
+            let last = groups.iter().position(|group| group.contains(&to)).unwrap();
+
+            if first != last {
+                let merged = groups[first].start..groups[last].end;
+                groups.splice(first..=last, [merged]);
             }
         }
     }
+
+    // A small layer may still end up alone in its group (e.g. when there are as many groups as layers),
+    // so the groups not worth a thread of their own are merged into their smaller neighbour
+    let size = |group: &Range<usize>| layers[group.clone()].iter().map(|layer| layer.neurons.len()).sum::<usize>();
+    while groups.len() > 1 {
+        let Some(i) = groups.iter().position(|group| size(group) < min_neurons) else { break };
+        let neighbour = match i {
+            0 => 1,
+            i if i == groups.len() - 1 => i - 1,
+            i if size(&groups[i - 1]) <= size(&groups[i + 1]) => i - 1,
+            i => i + 1
+        };
+
+        let (first, last) = (i.min(neighbour), i.max(neighbour));
+        let merged = groups[first].start..groups[last].end;
+        groups.splice(first..=last, [merged]);
+    }
+
+    groups
 }
@@ -0,0 +1,126 @@
+//! Helpers to check the consistency of the solvers, e.g. when implementing a custom [Model].
+
+use std::any::Any;
+use crate::{Model, Spike, NN, Float};
+
+/// Assert that the different solvers of this crate produce the same output for `nn` stimulated by `spikes`.
+/// 
+/// The parallel [solve](NN::solve) is checked against the same solve performed on the calling thread and, when the network
+/// has `f64` weights and neither delayed synapses nor skip connections, against the [reference solver](NN::solve_reference) too.
+/// 
+/// # Panics
+/// 
+/// Panics if the solvers disagree, listing every output neuron whose spikes differ.
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use pds_spiking_nn::{NNBuilder, Spike, lif::*, testing::assert_solvers_agree};
+/// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+///     .layer(
+///         [
+///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+///         ],
+///         [1.5, 1.8],
+///         [[0.0, -0.3], [-0.2, 0.0]]
+///     )
+///     .build();
+/// 
+/// let spikes = Spike::create_terminal_vec(vec![
+///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+///     Spike::spike_vec_for(1, vec![2, 3, 6])
+/// ]);
+/// 
+/// assert_solvers_agree(&nn, &spikes);
+/// ```
+#[cfg(not(feature = "async"))]
+pub fn assert_solvers_agree<M: Model, F: Float>(nn: &NN<M, F>, spikes: &[Spike]) where for<'a> &'a M::Neuron: Into<M::SolverVars> {
+    let parallel = nn.solve(spikes.to_vec());
+    check_agreement(&parallel, "parallel", &nn.solve_on_current_thread(spikes.to_vec()), "sequential");
+    check_reference(nn, spikes, &parallel);
+}
+
+/// Assert that the different solvers of this crate produce the same output for `nn` stimulated by `spikes`.
+/// 
+/// The parallel [solve](NN::solve) is checked against the same solve performed on the calling thread and, when the network
+/// has `f64` weights and neither delayed synapses nor skip connections, against the [reference solver](NN::solve_reference) too.
+/// 
+/// # Panics
+/// 
+/// Panics if the solvers disagree, listing every output neuron whose spikes differ.
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use pds_spiking_nn::{NNBuilder, Spike, lif::*, testing::assert_solvers_agree};
+/// # use tokio::runtime::Runtime;
+/// # let runtime = Runtime::new().unwrap();
+/// let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+///     .layer(
+///         [
+///             LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+///             From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+///         ],
+///         [1.5, 1.8],
+///         [[0.0, -0.3], [-0.2, 0.0]]
+///     )
+///     .build();
+/// 
+/// let spikes = Spike::create_terminal_vec(vec![
+///     Spike::spike_vec_for(0, vec![1, 3, 4]),
+///     Spike::spike_vec_for(1, vec![2, 3, 6])
+/// ]);
+/// 
+/// # runtime.block_on(async {
+/// assert_solvers_agree(&nn, &spikes).await;
+/// # });
+/// ```
+#[cfg(feature = "async")]
+pub async fn assert_solvers_agree<M: Model, F: Float>(nn: &NN<M, F>, spikes: &[Spike]) where for<'a> &'a M::Neuron: Into<M::SolverVars> {
+    let parallel = nn.solve(spikes.to_vec()).await;
+    check_agreement(&parallel, "parallel", &nn.solve_on_current_thread(spikes.to_vec()), "sequential");
+    check_reference(nn, spikes, &parallel);
+}
+
+/// Compare `output` with the one of the reference solver, if it supports `nn`
+fn check_reference<M: Model, F: Float>(nn: &NN<M, F>, spikes: &[Spike], output: &[Vec<u128>]) where for<'a> &'a M::Neuron: Into<M::SolverVars> {
+    let Some(nn) = (nn as &dyn Any).downcast_ref::<NN<M>>() else { return };
+
+    if nn.iter().any(|layer| layer.input_delays.is_some() || layer.intra_delays.is_some() || !layer.skips.is_empty()) {
+        return;
+    }
+
+    let mut spikes = spikes.to_vec();
+    spikes.sort_by_key(|spike| (spike.ts, spike.neuron_id));
+
+    let mut reference = vec![vec![]; output.len()];
+    for spike in nn.solve_reference(spikes) {
+        reference[spike.neuron_id].push(spike.ts);
+    }
+
+    check_agreement(output, "parallel", &reference, "reference");
+}
+
+/// Compare the (sorted) outputs of two solvers, panicking with a diff if they differ
+fn check_agreement(first: &[Vec<u128>], first_name: &str, second: &[Vec<u128>], second_name: &str) {
+    let sorted = |output: &[Vec<u128>]| output.iter()
+        .map(|spikes| {
+            let mut spikes = spikes.clone();
+            spikes.sort_unstable();
+            spikes
+        })
+        .collect::<Vec<_>>();
+    let (first, second) = (sorted(first), sorted(second));
+
+    let diff = first.iter()
+        .zip(&second)
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(neuron_id, (a, b))| format!("  neuron {}: {} {:?}, {} {:?}", neuron_id, first_name, a, second_name, b))
+        .collect::<Vec<_>>();
+
+    if !diff.is_empty() {
+        panic!("Solvers disagree on {} output neurons:\n{}", diff.len(), diff.join("\n"));
+    }
+}
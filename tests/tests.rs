@@ -1,4 +1,8 @@
-use pds_spiking_nn::{NNBuilder, Spike, lif::*};
+use ndarray::{Array2, array};
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use std::num::NonZeroUsize;
+use pds_spiking_nn::{Model, NNBuilder, Spike, SolveConfig, SolveError, encoding, nn::{SpikeCsvError, builder::{DynamicBuilderError, NeuronKind, IntraWeightsError, intra_weights_from}}, lif::*, adex::*, coba::*, stochastic::*, hybrid::*, plasticity::ExponentialStdp, testing::assert_solvers_agree};
 
 #[test]
 fn test_build_empty_nn() {
@@ -6,6 +10,254 @@ fn test_build_empty_nn() {
     assert!(nn.is_err());
 }
 
+#[test]
+fn test_dynamic_builder_errors() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic();
+
+    let builder = match builder.head([LifNeuron::new(&config)], [1.0], [0.0]) {
+        Err(DynamicBuilderError::MissingBackbone(builder)) => builder,
+        _ => panic!("expected MissingBackbone")
+    };
+
+    let builder = match builder.layer([], [], []) {
+        Err(DynamicBuilderError::EmptyLayer(builder)) => builder,
+        _ => panic!("expected EmptyLayer")
+    };
+
+    // Intra weights not square
+    let builder = match builder.layer(LifNeuron::new_vec(vec![config.clone()], 2), [1.0, 1.0], [0.0, -0.1, -0.1]) {
+        Err(DynamicBuilderError::InvalidIntraWeights { builder, expected: 4, found: 3 }) => builder,
+        _ => panic!("expected InvalidIntraWeights")
+    };
+
+    // Entry layer must have one input weight per neuron
+    let builder = match builder.layer(LifNeuron::new_vec(vec![config.clone()], 2), [1.0, 1.0, 1.0, 1.0], [0.0; 4]) {
+        Err(DynamicBuilderError::InvalidInputWeights { builder, expected: 2, found: 4 }) => builder,
+        _ => panic!("expected InvalidInputWeights")
+    };
+    let builder = match builder.layer(LifNeuron::new_vec(vec![config.clone()], 3), [1.0, 1.0], [0.0; 9]) {
+        Err(DynamicBuilderError::InvalidInputWeights { builder, expected: 3, found: 2 }) => builder,
+        _ => panic!("expected InvalidInputWeights")
+    };
+
+    let builder = builder.layer(LifNeuron::new_vec(vec![config.clone()], 2), [1.0, 1.0], [0.0; 4]).unwrap();
+
+    // 2x3 synapses are required from the previous layer
+    let builder = match builder.layer(LifNeuron::new_vec(vec![config.clone()], 3), [1.0; 3], [0.0; 9]) {
+        Err(DynamicBuilderError::InvalidInputWeights { builder, expected: 6, found: 3 }) => builder,
+        _ => panic!("expected InvalidInputWeights")
+    };
+
+    // Delays must match the input weights, and the failed layer is not added
+    let builder = match builder.layer_with_delays(LifNeuron::new_vec(vec![config.clone()], 3), [1.0; 6], [0.0; 9], [1; 3]) {
+        Err(DynamicBuilderError::InvalidInputDelays { builder, expected: 6, found: 3 }) => builder,
+        _ => panic!("expected InvalidInputDelays")
+    };
+
+    // Transposed input weights have the right length, but not the right shape
+    let builder = match builder.layer_with_delay_matrices(
+        LifNeuron::new_vec(vec![config.clone()], 3),
+        Array2::ones((3, 2)),
+        Array2::zeros((3, 3)),
+        Array2::ones((3, 2)),
+        Array2::zeros((3, 3))
+    ) {
+        Err(DynamicBuilderError::InvalidInputWeights { builder, expected: 6, found: 6 }) => builder,
+        _ => panic!("expected InvalidInputWeights")
+    };
+
+    let builder = match builder.layer_with_delay_matrices(
+        LifNeuron::new_vec(vec![config.clone()], 3),
+        Array2::ones((2, 3)),
+        Array2::zeros((3, 3)),
+        Array2::ones((2, 3)),
+        Array2::zeros((2, 2))
+    ) {
+        Err(DynamicBuilderError::InvalidIntraDelays { builder, expected: 9, found: 4 }) => builder,
+        _ => panic!("expected InvalidIntraDelays")
+    };
+
+    // Every existing synapse must be delayed
+    let builder = match builder.layer_with_delay_matrices(
+        LifNeuron::new_vec(vec![config.clone()], 3),
+        Array2::ones((2, 3)),
+        Array2::zeros((3, 3)),
+        array![[1, 1, 1], [1, 0, 1]],
+        Array2::zeros((3, 3))
+    ) {
+        Err(DynamicBuilderError::ZeroDelay { builder, from: 1, to: 1, intra: false }) => builder,
+        _ => panic!("expected ZeroDelay")
+    };
+
+    let builder = builder.layer_with_delays(LifNeuron::new_vec(vec![config.clone()], 3), [1.0; 6], [0.0; 9], [1; 6]).unwrap();
+
+    // Heads are fed by the last layer, of 3 neurons
+    let builder = match builder.head(LifNeuron::new_vec(vec![config.clone()], 2), [1.0; 4], [0.0; 4]) {
+        Err(DynamicBuilderError::InvalidInputWeights { builder, expected: 6, found: 4 }) => builder,
+        _ => panic!("expected InvalidInputWeights")
+    };
+    let builder = builder.head(LifNeuron::new_vec(vec![config.clone()], 2), [1.0; 6], [0.0; 4]).unwrap();
+
+    // And so are the following ones
+    let builder = match builder.head(LifNeuron::new_vec(vec![config.clone()], 1), [1.0; 2], [0.0]) {
+        Err(DynamicBuilderError::InvalidInputWeights { builder, expected: 3, found: 2 }) => builder,
+        _ => panic!("expected InvalidInputWeights")
+    };
+
+    let nn = builder.head(LifNeuron::new_vec(vec![config], 1), [1.0; 3], [0.0]).unwrap().build().unwrap();
+    assert_eq!(nn.num_layers(), 3);
+    assert_eq!(nn.num_heads(), 2);
+    assert_eq!(nn.layer_size(2), Some(3));
+}
+
+#[test]
+fn test_intra_weights_from() {
+    assert_eq!(intra_weights_from(Array2::<f64>::zeros((2, 3)), false), Err(IntraWeightsError::NotSquare { rows: 2, cols: 3 }));
+    assert_eq!(intra_weights_from(Array2::<f64>::zeros((2, 3)), true), Err(IntraWeightsError::NotSquare { rows: 2, cols: 3 }));
+
+    let weights = array![
+        [0.0, -0.3, 0.1],
+        [-0.2, 0.0, 0.4],
+        [0.5, 0.6, 1.2]
+    ];
+    assert_eq!(intra_weights_from(weights.clone(), false), Err(IntraWeightsError::SelfSynapse(2)));
+
+    let mut expected = weights.clone();
+    expected[(2, 2)] = 0.0;
+    assert_eq!(intra_weights_from(weights, true), Ok(expected.clone()));
+    assert_eq!(intra_weights_from(expected.clone(), false), Ok(expected.clone()));
+
+    // Non-standard layouts are accepted too, and returned in standard layout
+    let transposed = intra_weights_from(expected.clone().reversed_axes(), false).unwrap();
+    assert_eq!(transposed, expected.t());
+    assert!(transposed.as_slice().is_some());
+}
+
+#[test]
+fn test_skip_connections() {
+    let passthrough = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    // Only fires when both of its inputs arrive together
+    let coincidence = LifNeuronConfig::new(0.0, 0.0, 1.5, 1.0);
+
+    let builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_uniform(2, &passthrough, [1.0, 1.0], [0.0; 4]).unwrap()
+        .layer_uniform(1, &passthrough, [1.0, 0.0], [0.0]).unwrap()
+        .layer_uniform(1, &coincidence, [1.0], [0.0]).unwrap();
+
+    let builder = match builder.skip_connection(2, 2, array![[1.0]]) {
+        Err(DynamicBuilderError::InvalidSkipConnection { builder, from: 2, to: 2 }) => builder,
+        _ => panic!("a layer can't skip to itself")
+    };
+    let builder = match builder.skip_connection(0, 3, array![[1.0], [1.0]]) {
+        Err(DynamicBuilderError::InvalidSkipConnection { builder, from: 0, to: 3 }) => builder,
+        _ => panic!("layer 3 doesn't exist")
+    };
+    let builder = match builder.skip_connection(0, 2, array![[0.0, 1.0]]) {
+        Err(DynamicBuilderError::InvalidSkipWeights { builder, expected: (2, 1), found: (1, 2) }) => builder,
+        _ => panic!("the skip weights are transposed")
+    };
+
+    let nn = builder.skip_connection(0, 2, array![[0.0], [1.0]]).unwrap().build().unwrap();
+
+    let spikes = Spike::from_times(&[vec![1, 5, 9], vec![5, 12]]);
+    assert_eq!(nn.solve_sequential(spikes.clone()), vec![Spike::new(5, 0)]);
+    assert_eq!(nn.solve_event_driven(spikes.clone()), vec![Spike::new(5, 0)]);
+    // 5 input spikes, 3 + 2 spikes of the entry layer through one synapse each (the second neuron through the skip connection),
+    // and 3 spikes of the hidden layer
+    assert_eq!(nn.solve_counting_ops(spikes), (vec![Spike::new(5, 0)], 13));
+
+    // Input 1 only reaches the output layer through the skip connection
+    assert_eq!(nn.reachability(), vec![vec![0, 1]]);
+    assert!(nn.to_dot().contains("    l0n1 -> l2n0 [label=\"1\", style=dotted];\n"));
+    assert_eq!(nn.synapse_stats(0.0).total, 2 + 4 + 2 + 1 + 1 + 1 + 2);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_skip_connections_threads_sync() {
+    let passthrough = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let coincidence = LifNeuronConfig::new(0.0, 0.0, 1.5, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_uniform(2, &passthrough, [1.0, 1.0], [0.0; 4]).unwrap()
+        .layer_uniform(1, &passthrough, [1.0, 0.0], [0.0]).unwrap()
+        .layer_uniform(1, &passthrough, [1.0], [0.0]).unwrap()
+        .layer_uniform(1, &coincidence, [1.0], [0.0]).unwrap()
+        .skip_connection(0, 3, array![[0.0], [1.0]]).unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::from_times(&[vec![1, 5, 9], vec![5, 12]]);
+    let config = SolveConfig { max_threads: NonZeroUsize::new(4).unwrap(), ..Default::default() };
+    assert_eq!(nn.solve_with_config(spikes.clone(), config), vec![vec![5]]);
+    assert_eq!(nn.solve(spikes), vec![vec![5]]);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_skip_connections_threads_async() {
+    let passthrough = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let coincidence = LifNeuronConfig::new(0.0, 0.0, 1.5, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_uniform(2, &passthrough, [1.0, 1.0], [0.0; 4]).unwrap()
+        .layer_uniform(1, &passthrough, [1.0, 0.0], [0.0]).unwrap()
+        .layer_uniform(1, &passthrough, [1.0], [0.0]).unwrap()
+        .layer_uniform(1, &coincidence, [1.0], [0.0]).unwrap()
+        .skip_connection(0, 3, array![[0.0], [1.0]]).unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::from_times(&[vec![1, 5, 9], vec![5, 12]]);
+    let config = SolveConfig { max_threads: NonZeroUsize::new(4).unwrap(), ..Default::default() };
+    assert_eq!(nn.solve_with_config(spikes.clone(), config).await, vec![vec![5]]);
+    assert_eq!(nn.solve(spikes).await, vec![vec![5]]);
+}
+
+#[test]
+fn test_dale_law() {
+    use NeuronKind::*;
+
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic();
+
+    // Kinds need a layer to tag, and one for every one of its neurons
+    let builder = match builder.neuron_kinds([Excitatory]) {
+        Err(DynamicBuilderError::InvalidNeuronKinds { builder, expected: 0, found: 1 }) => builder,
+        _ => panic!("expected InvalidNeuronKinds")
+    };
+    let builder = builder.layer_uniform(3, &config, [1.0; 3], [
+        0.0, 0.5, 0.0,
+        -0.5, 0.0, -0.5,
+        0.0, 0.0, 0.0
+    ]).unwrap();
+    let builder = match builder.neuron_kinds([Excitatory, Inhibitory]) {
+        Err(DynamicBuilderError::InvalidNeuronKinds { builder, expected: 3, found: 2 }) => builder,
+        _ => panic!("expected InvalidNeuronKinds")
+    };
+
+    // Zero weights are allowed for both kinds, so the last neuron can be tagged either way
+    let builder = builder.neuron_kinds([Excitatory, Inhibitory, Inhibitory]).unwrap();
+    let valid = builder.clone().layer_uniform(2, &config, [1.0, 0.0, -1.0, -2.0, 0.0, 0.0], [0.0; 4]).unwrap();
+    assert!(valid.build().is_ok());
+
+    // The outgoing synapses to the next layer are checked too
+    let invalid = builder.clone().layer_uniform(2, &config, [1.0, 0.0, -1.0, -2.0, 0.0, 0.1], [0.0; 4]).unwrap();
+    assert!(matches!(invalid.build(), Err(DynamicBuilderError::DaleViolation { layer: 0, neuron: 2, kind: Inhibitory, .. })));
+
+    // Tagging the same layer again replaces its kinds
+    let builder = builder.neuron_kinds([Inhibitory, Inhibitory, Inhibitory]).unwrap();
+    let builder = match builder.build() {
+        Err(DynamicBuilderError::DaleViolation { builder, layer: 0, neuron: 0, kind: Inhibitory }) => builder,
+        _ => panic!("expected DaleViolation")
+    };
+
+    // The builder can be fixed after the error
+    let nn = builder.neuron_kinds([Excitatory, Inhibitory, Excitatory]).unwrap().build().unwrap();
+    assert_eq!(nn.num_layers(), 1);
+}
+
 #[cfg(not(feature = "async"))]
 #[test]
 fn test_passthrough_nn() {
@@ -88,6 +340,62 @@ async fn test_passthrough_nn() {
     );
 }
 
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_solve_with_stats() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config), From::from(&config), From::from(&config)],
+            [1.0, 1.0, 1.0],
+            [
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 2, 3, 5]),
+        Spike::spike_vec_for(2, vec![2, 6])
+    ]);
+
+    let result = nn.solve_with_stats(spikes);
+    assert_eq!(result.output, vec![vec![1, 2, 3, 5], vec![], vec![2, 6]]);
+    assert_eq!(result.spikes_per_neuron, vec![4, 0, 2]);
+    assert_eq!(result.total_spikes, 6);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_solve_with_stats() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config), From::from(&config), From::from(&config)],
+            [1.0, 1.0, 1.0],
+            [
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 2, 3, 5]),
+        Spike::spike_vec_for(2, vec![2, 6])
+    ]);
+
+    let result = nn.solve_with_stats(spikes).await;
+    assert_eq!(result.output, vec![vec![1, 2, 3, 5], vec![], vec![2, 6]]);
+    assert_eq!(result.spikes_per_neuron, vec![4, 0, 2]);
+    assert_eq!(result.total_spikes, 6);
+}
+
 #[cfg(not(feature = "async"))]
 #[test]
 fn test_hand_solved() {
@@ -190,171 +498,137 @@ async fn test_hand_solved() {
     );
 }
 
+#[cfg(not(feature = "async"))]
 #[test]
-fn test_spike_vec_for() {
-    assert_eq!(
-        Spike::spike_vec_for(4, vec![4, 7, 3, 10, 11, 2]),
-        {
-            let mut v = vec![4, 7, 3, 10, 11, 2]
-                .into_iter()
-                .map(|ts| Spike {neuron_id: 4, ts})
-                .collect::<Vec<_>>();
-            
-            v.sort();
-            v
-        }
-    );
-}
+fn test_simultaneous_intra_layer_updates() {
+    let config = LifNeuronConfig::new(0.0, 0.0, 0.5, 1.0);
 
-#[test]
-fn test_spike_vec_for_empty() {
-    assert_eq!(
-        Spike::spike_vec_for(1, vec![]),
-        vec![]
-    );
-}
+    // Only one of the two neurons inhibits the other one
+    let cases = [
+        ([[0.0, -2.0], [0.0, 0.0]], vec![vec![1, 2], vec![1]]),
+        ([[0.0, 0.0], [-2.0, 0.0]], vec![vec![1], vec![1, 2]])
+    ];
 
-#[test]
-fn test_spike_vec_for_repeating() {
-    assert_eq!(
-        Spike::spike_vec_for(7, vec![1, 1, 1, 5, 1]),
-        vec![1, 1, 1, 1, 5].into_iter().map(|ts| Spike {neuron_id: 7, ts}).collect::<Vec<_>>()
-    );
+    for (intra_weights, expected) in cases {
+        let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+            .layer([From::from(&config), From::from(&config)], [1.0, 1.0], intra_weights)
+            .build();
+
+        // Whatever its index, the inhibited neuron fires together with the other one before being inhibited
+        let spikes = Spike::create_terminal_vec(vec![
+            Spike::spike_vec_for(0, vec![1, 2]),
+            Spike::spike_vec_for(1, vec![1, 2])
+        ]);
+
+        assert_eq!(nn.solve(spikes), expected);
+    }
 }
 
-#[test]
-fn test_create_terminal_vec(){
-    let spikes_neuron_1 = [11, 9, 23, 43, 42].to_vec();
-    let spike_vec_for_neuron_1 = Spike::spike_vec_for(1, spikes_neuron_1 );
-    
-    let spikes_neuron_2 = [1, 29, 3, 11, 22].to_vec();
-    let spike_vec_for_neuron_2 = Spike::spike_vec_for(2, spikes_neuron_2 );
-    
-    let spikes: Vec<Vec<Spike>> = [spike_vec_for_neuron_1, spike_vec_for_neuron_2].to_vec();
-    
-    let sorted_spike_array_for_nn: Vec<Spike> = Spike::create_terminal_vec(spikes);
-    
-    assert_eq!(
-        sorted_spike_array_for_nn,
-        {
-            let mut v = [11, 9, 23, 43, 42].into_iter()
-                .map(|ts| Spike {neuron_id: 1, ts})
-                .chain([1, 29, 3, 11, 22].into_iter().map(|ts| Spike {neuron_id: 2, ts}))
-                .collect::<Vec<_>>();
-            
-            v.sort();
-            v
-        }
-    );
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_simultaneous_intra_layer_updates() {
+    let config = LifNeuronConfig::new(0.0, 0.0, 0.5, 1.0);
+
+    // Only one of the two neurons inhibits the other one
+    let cases = [
+        ([[0.0, -2.0], [0.0, 0.0]], vec![vec![1, 2], vec![1]]),
+        ([[0.0, 0.0], [-2.0, 0.0]], vec![vec![1], vec![1, 2]])
+    ];
+
+    for (intra_weights, expected) in cases {
+        let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+            .layer([From::from(&config), From::from(&config)], [1.0, 1.0], intra_weights)
+            .build();
+
+        // Whatever its index, the inhibited neuron fires together with the other one before being inhibited
+        let spikes = Spike::create_terminal_vec(vec![
+            Spike::spike_vec_for(0, vec![1, 2]),
+            Spike::spike_vec_for(1, vec![1, 2])
+        ]);
+
+        assert_eq!(nn.solve(spikes).await, expected);
+    }
 }
 
+#[cfg(not(feature = "async"))]
 #[test]
-fn test_nn_get_params() {
+fn test_simultaneous_input_spikes() {
+    let config = LifNeuronConfig::new(0.0, 0.0, 0.5, 1.0);
+
     let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
         .layer(
             [
-                LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 2.8, 0.9)),
-                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.6, 2.9, 1.2)),
-            ],
-            [
-                1.2, 1.1
-            ],
-            [
-                [0.0, -0.3],
-                [-0.2, 0.0]
-            ]
-        )
-        .layer(
-            [
-                LifNeuron::new(&LifNeuronConfig::new(0.8, 0.3, 2.5, 1.2)),
-                LifNeuron::new(&LifNeuronConfig::new(1.1, 0.4, 2.6, 1.2)),
-                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.4, 3.0, 1.0))
+                From::from(&config),
+                From::from(&config)
             ],
             [
-                [1.2, 1.3, 1.2],
-                [1.4, 1.3, 1.5]
+                1.0, 1.0
             ],
             [
-                [0.0, -0.2, -0.3],
-                [-0.3, 0.0, -0.3],
-                [-0.2, -0.1, 0.0]
+                [0.0, -2.0],
+                [-2.0, 0.0]
             ]
         )
         .build();
-    
-    assert_eq!(nn.get_input_weight(0), Some(1.2));
-    assert_eq!(nn.get_input_weight(1), Some(1.1));
-    assert_eq!(nn.get_input_weight(2), None);
 
-    assert_eq!(nn[0][(0, 0)], 0.0);
-    assert_eq!(nn[0][(0, 1)], -0.3);
-    assert_eq!(nn[0][(1, 0)], -0.2);
-    assert_eq!(nn[0][(1, 1)], 0.0);
+    // Both neurons must fire before inhibiting each other
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![1]),
+            Spike::spike_vec_for(1, vec![1])
+        ]
+    );
 
-    assert_eq!(nn[((0, 0), (1, 0))], 1.2);
-    assert_eq!(nn.get_weight((0, 1), (1, 1)), Some(1.3));
-    assert_eq!(nn.get_weight((1, 0), (0, 0)), None);
+    assert_eq!(
+        nn.solve(spikes),
+        vec![
+            vec![1],
+            vec![1]
+        ]
+    );
 }
 
-#[test]
-fn test_nn_update_params() {
-    let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
-        .layer(
-            [
-                LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 2.8, 0.9)),
-                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.6, 2.9, 1.2)),
-            ],
-            [
-                1.2, 1.1
-            ],
-            [
-                [0.0, -0.3],
-                [-0.2, 0.0]
-            ]
-        )
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_simultaneous_input_spikes() {
+    let config = LifNeuronConfig::new(0.0, 0.0, 0.5, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
         .layer(
             [
-                LifNeuron::new(&LifNeuronConfig::new(0.8, 0.3, 2.5, 1.2)),
-                LifNeuron::new(&LifNeuronConfig::new(1.1, 0.4, 2.6, 1.2)),
-                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.4, 3.0, 1.0))
+                From::from(&config),
+                From::from(&config)
             ],
             [
-                [1.2, 1.3, 1.2],
-                [1.4, 1.3, 1.5]
+                1.0, 1.0
             ],
             [
-                [0.0, -0.2, -0.3],
-                [-0.3, 0.0, -0.3],
-                [-0.2, -0.1, 0.0]
+                [0.0, -2.0],
+                [-2.0, 0.0]
             ]
         )
         .build();
-    
-    nn[0][0].v_rest = 2.0;
-    assert_eq!(nn.get_neuron(0, 0).unwrap().v_rest, 2.0);
-
-    *nn.get_input_weight_mut(1).unwrap() = 1.5;
-    assert_eq!(nn.get_input_weight(1), Some(1.5));
-
-    nn[0][(0, 0)] = 1.0;
-    assert_eq!(nn.get_layer(0).unwrap().get_intra_weight(0, 0), Some(1.0));
-
-    nn[((0, 1), (1, 1))] = 3.0;
-    assert_eq!(nn.get_weight((0, 1), (1, 1)), Some(3.0));
 
-    for neuron in nn.iter_mut().flat_map(|l| l.iter_mut_neurons()) {
-        neuron.v_reset += 1.0;
-    }
+    // Both neurons must fire before inhibiting each other
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![1]),
+            Spike::spike_vec_for(1, vec![1])
+        ]
+    );
 
-    assert_eq!(nn[0][1].v_reset, 1.6);
-    assert_eq!(nn[1][2].v_reset, 1.4);
+    assert_eq!(
+        nn.solve(spikes).await,
+        vec![
+            vec![1],
+            vec![1]
+        ]
+    );
 }
 
-#[cfg(feature = "expose-test-solver")]
+#[cfg(not(feature = "async"))]
 #[test]
-fn test_solver_v1() {
-    use pds_spiking_nn::test_solver;
-    
+fn test_latency_distribution() {
     let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
         .layer(
             [
@@ -386,9 +660,2824 @@ fn test_solver_v1() {
         )
         .build();
 
-    let spikes = Spike::create_terminal_vec(
-        vec![
-            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+    let dataset = vec![
+        Spike::create_terminal_vec(
+            vec![
+                Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+                Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+                Spike::spike_vec_for(2, vec![4, 9, 12])
+            ]
+        ),
+        Spike::spike_vec_for(1, vec![1, 2, 3, 4, 5]),
+        Spike::spike_vec_for(2, vec![4]),
+        vec![]
+    ];
+
+    let latencies = nn.latency_distribution(&dataset);
+    assert_eq!(latencies[0], Some(6));
+
+    for (spikes, latency) in dataset.into_iter().zip(latencies) {
+        assert_eq!(latency, nn.first_output_spike(spikes.clone()).map(|s| s.ts));
+        assert_eq!(latency, nn.solve(spikes).into_iter().flatten().min());
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_latency_distribution() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let dataset = vec![
+        Spike::create_terminal_vec(
+            vec![
+                Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+                Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+                Spike::spike_vec_for(2, vec![4, 9, 12])
+            ]
+        ),
+        Spike::spike_vec_for(1, vec![1, 2, 3, 4, 5]),
+        Spike::spike_vec_for(2, vec![4]),
+        vec![]
+    ];
+
+    let latencies = nn.latency_distribution(&dataset);
+    assert_eq!(latencies[0], Some(6));
+
+    for (spikes, latency) in dataset.into_iter().zip(latencies) {
+        assert_eq!(latency, nn.first_output_spike(spikes.clone()).map(|s| s.ts));
+        assert_eq!(latency, nn.solve(spikes).await.into_iter().flatten().min());
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_independent_solves() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+            Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+            Spike::spike_vec_for(2, vec![4, 9, 12])
+        ]
+    );
+
+    let first = nn.solve(spikes.clone());
+    
+    // A different trial in between must not leak any state into the next one
+    nn.solve(Spike::spike_vec_for(1, vec![1, 2, 3, 4, 5]));
+    
+    assert_eq!(nn.solve(spikes), first);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_independent_solves() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+            Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+            Spike::spike_vec_for(2, vec![4, 9, 12])
+        ]
+    );
+
+    let first = nn.solve(spikes.clone()).await;
+    
+    // A different trial in between must not leak any state into the next one
+    nn.solve(Spike::spike_vec_for(1, vec![1, 2, 3, 4, 5])).await;
+    
+    assert_eq!(nn.solve(spikes).await, first);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_trace_file() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+            Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+            Spike::spike_vec_for(2, vec![4, 9, 12])
+        ]
+    );
+
+    let mut trace = vec![];
+    nn.solve_to_trace_file(spikes.clone(), &mut trace).unwrap();
+    let trace = String::from_utf8(trace).unwrap();
+    let mut lines = trace.lines();
+    assert_eq!(lines.next(), Some("ts,layer,neuron"));
+
+    let mut last_ts = 0;
+    let mut output = vec![vec![]; 2];
+    for line in lines {
+        let record = line.split(',').collect::<Vec<_>>();
+        let ts = record[0].parse::<u128>().unwrap();
+        assert!(ts >= last_ts);
+        last_ts = ts;
+
+        if record[1] == "1" {
+            output[record[2].parse::<usize>().unwrap()].push(ts);
+        }
+    }
+
+    assert_eq!(output, nn.solve(spikes));
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_trace_file() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+            Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+            Spike::spike_vec_for(2, vec![4, 9, 12])
+        ]
+    );
+
+    let mut trace = vec![];
+    nn.solve_to_trace_file(spikes.clone(), &mut trace).unwrap();
+    let trace = String::from_utf8(trace).unwrap();
+    let mut lines = trace.lines();
+    assert_eq!(lines.next(), Some("ts,layer,neuron"));
+
+    let mut last_ts = 0;
+    let mut output = vec![vec![]; 2];
+    for line in lines {
+        let record = line.split(',').collect::<Vec<_>>();
+        let ts = record[0].parse::<u128>().unwrap();
+        assert!(ts >= last_ts);
+        last_ts = ts;
+
+        if record[1] == "1" {
+            output[record[2].parse::<usize>().unwrap()].push(ts);
+        }
+    }
+
+    assert_eq!(output, nn.solve(spikes).await);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_solvers_agree() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+            Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+            Spike::spike_vec_for(2, vec![4, 9, 12])
+        ]
+    );
+
+    assert_solvers_agree(&nn, &spikes);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_solvers_agree() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+            Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+            Spike::spike_vec_for(2, vec![4, 9, 12])
+        ]
+    );
+
+    assert_solvers_agree(&nn, &spikes).await;
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_delay_matrices() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_with_delay_matrices(
+            vec![LifNeuron::new(&config); 2],
+            array![[1.0, 1.0]],
+            // The first neuron excites the second one, through a delayed synapse
+            array![
+                [0.0, 3.0],
+                [0.0, 0.0]
+            ],
+            array![[1, 3]],
+            array![
+                [0, 5],
+                [0, 0]
+            ]
+        )
+        .unwrap()
+        .layer_with_delay_matrices(
+            vec![LifNeuron::new(&config); 2],
+            array![
+                [1.0, 0.0],
+                [0.0, 1.0]
+            ],
+            array![
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ],
+            array![
+                [2, 0],
+                [0, 7]
+            ],
+            array![
+                [0, 0],
+                [0, 0]
+            ]
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![10]),
+        Spike::spike_vec_for(1, vec![20])
+    ]);
+
+    // 10 + 1 + 2 for the first output, 10 + 1 + 5 + 7 and 20 + 3 + 7 for the second one
+    assert_eq!(nn.solve(spikes.clone()), vec![vec![13], vec![23, 30]]);
+    assert_solvers_agree(&nn, &spikes);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_delay_matrices() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_with_delay_matrices(
+            vec![LifNeuron::new(&config); 2],
+            array![[1.0, 1.0]],
+            // The first neuron excites the second one, through a delayed synapse
+            array![
+                [0.0, 3.0],
+                [0.0, 0.0]
+            ],
+            array![[1, 3]],
+            array![
+                [0, 5],
+                [0, 0]
+            ]
+        )
+        .unwrap()
+        .layer_with_delay_matrices(
+            vec![LifNeuron::new(&config); 2],
+            array![
+                [1.0, 0.0],
+                [0.0, 1.0]
+            ],
+            array![
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ],
+            array![
+                [2, 0],
+                [0, 7]
+            ],
+            array![
+                [0, 0],
+                [0, 0]
+            ]
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![10]),
+        Spike::spike_vec_for(1, vec![20])
+    ]);
+
+    // 10 + 1 + 2 for the first output, 10 + 1 + 5 + 7 and 20 + 3 + 7 for the second one
+    assert_eq!(nn.solve(spikes.clone()).await, vec![vec![13], vec![23, 30]]);
+    assert_solvers_agree(&nn, &spikes).await;
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_f32_weights() {
+    let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    let mut rng = Pcg64Mcg::seed_from_u64(1234);
+    let sizes = [8, 16, 16, 4];
+
+    let mut nn_f64 = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic();
+    let mut nn_f32 = NNBuilder::<LeakyIntegrateFire, _, f32>::new_dynamic();
+    let mut len_last_layer = None;
+
+    for n in sizes {
+        let input_weights = (0..len_last_layer.unwrap_or(1) * n).map(|_| rng.gen_range(0.5..1.5)).collect::<Vec<f64>>();
+        let intra_weights = (0..n*n).map(|i| if i / n == i % n { 0.0 } else { rng.gen_range(-0.2..0.0) }).collect::<Vec<f64>>();
+
+        nn_f64 = nn_f64.layer(vec![LifNeuron::new(&config); n], &input_weights[..], &intra_weights[..]).unwrap();
+        nn_f32 = nn_f32.layer(
+            vec![LifNeuron::new(&config); n],
+            input_weights.iter().map(|w| *w as f32).collect::<Vec<_>>(),
+            intra_weights.iter().map(|w| *w as f32).collect::<Vec<_>>()
+        ).unwrap();
+        len_last_layer = Some(n);
+    }
+
+    let (nn_f64, nn_f32) = (nn_f64.build().unwrap(), nn_f32.build().unwrap());
+    let spikes = Spike::create_terminal_vec((0..8).map(|i| Spike::poisson_train(i, 300.0, 200, i as u64)).collect());
+
+    let output_f64 = nn_f64.solve(spikes.clone());
+    let output_f32 = nn_f32.solve(spikes.clone());
+
+    // The weights are only rounded to the closest f32, which can at most shift a handful of spikes
+    assert!(output_f64.iter().map(Vec::len).sum::<usize>() > 0);
+    for (ts_f64, ts_f32) in output_f64.iter().zip(&output_f32) {
+        assert!(ts_f64.len().abs_diff(ts_f32.len()) <= 1);
+    }
+    assert_solvers_agree(&nn_f32, &spikes);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_f32_weights() {
+    let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    let mut rng = Pcg64Mcg::seed_from_u64(1234);
+    let sizes = [8, 16, 16, 4];
+
+    let mut nn_f64 = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic();
+    let mut nn_f32 = NNBuilder::<LeakyIntegrateFire, _, f32>::new_dynamic();
+    let mut len_last_layer = None;
+
+    for n in sizes {
+        let input_weights = (0..len_last_layer.unwrap_or(1) * n).map(|_| rng.gen_range(0.5..1.5)).collect::<Vec<f64>>();
+        let intra_weights = (0..n*n).map(|i| if i / n == i % n { 0.0 } else { rng.gen_range(-0.2..0.0) }).collect::<Vec<f64>>();
+
+        nn_f64 = nn_f64.layer(vec![LifNeuron::new(&config); n], &input_weights[..], &intra_weights[..]).unwrap();
+        nn_f32 = nn_f32.layer(
+            vec![LifNeuron::new(&config); n],
+            input_weights.iter().map(|w| *w as f32).collect::<Vec<_>>(),
+            intra_weights.iter().map(|w| *w as f32).collect::<Vec<_>>()
+        ).unwrap();
+        len_last_layer = Some(n);
+    }
+
+    let (nn_f64, nn_f32) = (nn_f64.build().unwrap(), nn_f32.build().unwrap());
+    let spikes = Spike::create_terminal_vec((0..8).map(|i| Spike::poisson_train(i, 300.0, 200, i as u64)).collect());
+
+    let output_f64 = nn_f64.solve(spikes.clone()).await;
+    let output_f32 = nn_f32.solve(spikes.clone()).await;
+
+    // The weights are only rounded to the closest f32, which can at most shift a handful of spikes
+    assert!(output_f64.iter().map(Vec::len).sum::<usize>() > 0);
+    for (ts_f64, ts_f32) in output_f64.iter().zip(&output_f32) {
+        assert!(ts_f64.len().abs_diff(ts_f32.len()) <= 1);
+    }
+    assert_solvers_agree(&nn_f32, &spikes).await;
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_input_delays() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config), From::from(&config)],
+            [1.0, 1.0],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        // Only the synapse between the first neurons is delayed
+        .layer_with_delays(
+            [From::from(&config), From::from(&config)],
+            [
+                [1.0, 0.0],
+                [0.0, 1.0]
+            ],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ],
+            [
+                [5, 0],
+                [0, 0]
+            ]
+        )
+        .layer(
+            [From::from(&config), From::from(&config)],
+            [
+                [1.0, 0.0],
+                [0.0, 1.0]
+            ],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 4]),
+        Spike::spike_vec_for(1, vec![2, 3, 10])
+    ]);
+
+    assert_eq!(nn.solve(spikes.clone()), vec![vec![6, 9], vec![2, 3, 10]]);
+    assert_solvers_agree(&nn, &spikes);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_input_delays() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config), From::from(&config)],
+            [1.0, 1.0],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        // Only the synapse between the first neurons is delayed
+        .layer_with_delays(
+            [From::from(&config), From::from(&config)],
+            [
+                [1.0, 0.0],
+                [0.0, 1.0]
+            ],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ],
+            [
+                [5, 0],
+                [0, 0]
+            ]
+        )
+        .layer(
+            [From::from(&config), From::from(&config)],
+            [
+                [1.0, 0.0],
+                [0.0, 1.0]
+            ],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 4]),
+        Spike::spike_vec_for(1, vec![2, 3, 10])
+    ]);
+
+    assert_eq!(nn.solve(spikes.clone()).await, vec![vec![6, 9], vec![2, 3, 10]]);
+    assert_solvers_agree(&nn, &spikes).await;
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_zero_delay_cycle() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0)),
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))
+            ],
+            [1.0, 1.0],
+            [
+                [0.0, 2.0],
+                [2.0, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0)),
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))
+            ],
+            [
+                [1.0, 0.0],
+                [0.0, 1.0]
+            ],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1]),
+        Spike::spike_vec_for(1, vec![1])
+    ]);
+
+    assert_eq!(
+        nn.try_solve(spikes),
+        Err(SolveError::ZeroDelayCycle { layer: 0, neurons: vec![0, 1] })
+    );
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_zero_delay_cycle() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0)),
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))
+            ],
+            [1.0, 1.0],
+            [
+                [0.0, 2.0],
+                [2.0, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0)),
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))
+            ],
+            [
+                [1.0, 0.0],
+                [0.0, 1.0]
+            ],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1]),
+        Spike::spike_vec_for(1, vec![1])
+    ]);
+
+    assert_eq!(
+        nn.try_solve(spikes).await,
+        Err(SolveError::ZeroDelayCycle { layer: 0, neurons: vec![0, 1] })
+    );
+}
+
+#[test]
+fn test_zero_delay_cycle_detection() {
+    let build = |intra_weights| NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            std::array::from_fn::<_, 4, _>(|_| LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))),
+            [1.0; 4],
+            intra_weights
+        )
+        .build();
+
+    // Excitatory chain with an inhibitory feedback: no cycle
+    assert_eq!(build([
+        [0.0, 0.5, 0.0, 0.0],
+        [0.0, 0.0, 0.5, 0.0],
+        [0.0, 0.0, 0.0, 0.5],
+        [-0.5, -0.5, 0.0, 0.0]
+    ]).check_zero_delay_cycles(), Ok(()));
+
+    // 1 -> 2 -> 3 -> 1
+    assert_eq!(build([
+        [0.0, 0.5, 0.0, 0.0],
+        [0.0, 0.0, 0.5, 0.0],
+        [0.0, 0.0, 0.0, 0.5],
+        [-0.5, 0.5, 0.0, 0.0]
+    ]).check_zero_delay_cycles(), Err(SolveError::ZeroDelayCycle { layer: 0, neurons: vec![1, 2, 3] }));
+
+    // Self-excitation
+    assert_eq!(build([
+        [0.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 0.1, 0.0],
+        [0.0, 0.0, 0.0, 0.0]
+    ]).check_zero_delay_cycles(), Err(SolveError::ZeroDelayCycle { layer: 0, neurons: vec![2] }));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_refractory_period() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0).with_refractory_period(3);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config), From::from(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))],
+            [2.0, 2.0],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .build();
+
+    // The second spike of each pair is too close to the first one for the refractory neuron
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 2, 4, 6]),
+        Spike::spike_vec_for(1, vec![1, 2, 4, 6])
+    ]);
+
+    assert_eq!(nn.solve(spikes), vec![vec![1, 4], vec![1, 2, 4, 6]]);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_refractory_period() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0).with_refractory_period(3);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config), From::from(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))],
+            [2.0, 2.0],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .build();
+
+    // The second spike of each pair is too close to the first one for the refractory neuron
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 2, 4, 6]),
+        Spike::spike_vec_for(1, vec![1, 2, 4, 6])
+    ]);
+
+    assert_eq!(nn.solve(spikes).await, vec![vec![1, 4], vec![1, 2, 4, 6]]);
+}
+
+#[test]
+fn test_lif_threshold_adaptation() {
+    let config = LifNeuronConfig::new(0.0, 0.0, 1.0, 5.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config.clone().with_threshold_adaptation(0.8, 15.0)), From::from(&config)],
+            [0.6, 0.6],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .build();
+
+    // A regular high-rate input, paused long enough for the threshold to relax back
+    let input = (1..=40).chain(200..=240).collect::<Vec<_>>();
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, input.clone()),
+        Spike::spike_vec_for(1, input)
+    ]);
+    let output = nn.solve_sequential(spikes.clone());
+    assert_eq!(output, nn.solve_reference(spikes));
+
+    let times = |neuron_id, range: std::ops::Range<u128>| output.iter()
+        .filter(|s| s.neuron_id == neuron_id && range.contains(&s.ts))
+        .map(|s| s.ts)
+        .collect::<Vec<_>>();
+    let isis = |times: Vec<u128>| times.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
+
+    // The neuron without adaptation fires at a constant rate
+    assert!(isis(times(1, 0..100)).iter().all(|isi| *isi == 2));
+
+    // The adapting one fires the first time just like it, and then it slows down
+    for (start, range) in [(1, 0..100), (200, 200..300)] {
+        let times = times(0, range);
+        let isis = isis(times.clone());
+
+        assert_eq!(times[0], start + 1);
+        assert!(isis[0] > 2);
+        assert!(isis.windows(2).all(|w| w[0] <= w[1]));
+        assert!(isis.last() > isis.first());
+    }
+}
+
+#[test]
+fn test_adex_adaptation() {
+    let nn = NNBuilder::<AdaptiveExponential, _>::new()
+        .layer([From::from(&AdExNeuronConfig::default())], [1.0], [[0.0]])
+        .build();
+
+    // A constant drive makes the neuron fire at a decreasing rate
+    let output = nn.solve_sequential(Spike::spike_vec_for(0, (1..500).collect()));
+    let intervals = output.windows(2).map(|pair| pair[1].ts - pair[0].ts).collect::<Vec<_>>();
+
+    assert!(intervals.len() > 2);
+    assert!(intervals.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(intervals.first() < intervals.last());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_adex_solvers_agree() {
+    let config = AdExNeuronConfig::default();
+    let nn = NNBuilder::<AdaptiveExponential, _>::new()
+        .layer(
+            [From::from(&config), From::from(&config)],
+            [1.2, 1.5],
+            [[0.0, -0.5], [-0.5, 0.0]]
+        )
+        .layer(
+            [From::from(&AdExNeuronConfig { b: 0.5, ..config.clone() })],
+            [[20.0], [20.0]],
+            [[0.0]]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, (1..300).collect()),
+        Spike::spike_vec_for(1, (1..300).step_by(2).collect())
+    ]);
+
+    assert_solvers_agree(&nn, &spikes);
+    assert!(!nn.solve_sequential(spikes).is_empty());
+}
+
+#[test]
+fn test_coba_reversal_potential() {
+    // The excitatory reversal potential is below the threshold, so no input can make the neuron fire
+    let config = CobaNeuronConfig { e_exc: -55.0, ..Default::default() };
+    let nn = NNBuilder::<ConductanceBased, _>::new()
+        .layer([From::from(&config)], [1000.0], [[0.0]])
+        .build();
+
+    assert!(nn.solve_sequential(Spike::spike_vec_for(0, (1..500).collect())).is_empty());
+
+    // Which is not the case with a reversal potential above the threshold
+    let nn = NNBuilder::<ConductanceBased, _>::new()
+        .layer([From::from(&CobaNeuronConfig::default())], [1000.0], [[0.0]])
+        .build();
+
+    assert!(!nn.solve_sequential(Spike::spike_vec_for(0, (1..500).collect())).is_empty());
+}
+
+#[test]
+fn test_coba_shunting_inhibition() {
+    // The inhibitory reversal potential is the resting one, so inhibition alone doesn't move the membrane
+    let neuron = CobaNeuron::new(&CobaNeuronConfig { e_inh: -65.0, ..Default::default() });
+    let excite = |vars: &mut CobaSolverVars| (11..=13)
+        .map(|ts| ConductanceBased::handle_spike(&neuron, vars, 3.0, ts))
+        .collect::<Vec<_>>();
+
+    let mut vars = From::from(&neuron);
+    assert_eq!(excite(&mut vars), vec![0.0, 0.0, 1.0]);
+
+    let mut vars = From::from(&neuron);
+    assert_eq!(ConductanceBased::handle_spike(&neuron, &mut vars, -50.0, 10), 0.0);
+    assert_eq!(vars.get_vars().0, -65.0);
+
+    // But it shunts the excitatory inputs, preventing the neuron from firing
+    assert_eq!(excite(&mut vars), vec![0.0, 0.0, 0.0]);
+    assert!(vars.get_vars().0 > -65.0);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_coba_solvers_agree() {
+    let config = CobaNeuronConfig::default();
+    let nn = NNBuilder::<ConductanceBased, _>::new()
+        .layer(
+            [From::from(&config), From::from(&config)],
+            [0.3, 0.4],
+            [[0.0, -0.5], [-0.5, 0.0]]
+        )
+        .layer(
+            [From::from(&CobaNeuronConfig { tau_syn: 2.0, ..config.clone() })],
+            [[2.0], [2.0]],
+            [[0.0]]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, (1..300).collect()),
+        Spike::spike_vec_for(1, (1..300).step_by(2).collect())
+    ]);
+
+    assert_solvers_agree(&nn, &spikes);
+    assert!(!nn.solve_sequential(spikes).is_empty());
+}
+
+#[test]
+fn test_hybrid_layers() {
+    type LifAdEx = Hybrid<LeakyIntegrateFire, AdaptiveExponential>;
+
+    let lif_config = LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2);
+    let adex_config = AdExNeuronConfig::default();
+    let nn = NNBuilder::<LifAdEx, _>::new()
+        .layer_uniform::<2>(&HybridNeuronConfig::First(lif_config.clone()), [1.5, 1.8], [[0.0, -0.3], [-0.2, 0.0]])
+        .layer_uniform::<2>(&HybridNeuronConfig::Second(adex_config.clone()), [[20.0, 0.0], [0.0, 20.0]], [[0.0, 0.0], [0.0, 0.0]])
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, (1..100).collect()),
+        Spike::spike_vec_for(1, (1..100).step_by(2).collect())
+    ]);
+
+    // The hybrid network behaves like the lif network feeding the adex one
+    let lif = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer_uniform::<2>(&lif_config, [1.5, 1.8], [[0.0, -0.3], [-0.2, 0.0]])
+        .build();
+    let adex = NNBuilder::<AdaptiveExponential, _>::new()
+        .layer_uniform::<2>(&adex_config, [20.0, 20.0], [[0.0, 0.0], [0.0, 0.0]])
+        .build();
+
+    let expected = adex.solve_sequential(lif.solve_sequential(spikes.clone()));
+    assert!(!expected.is_empty());
+    assert_eq!(nn.solve_sequential(spikes), expected);
+}
+
+#[test]
+fn test_solve_batch() {
+    let mut rng = Pcg64Mcg::seed_from_u64(3);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+                From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+            ],
+            [1.5, 1.8],
+            [[0.0, -0.3], [-0.2, 0.0]]
+        )
+        .layer(
+            [LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))],
+            [[1.0], [1.0]],
+            [[0.0]]
+        )
+        .build();
+
+    let batches = (0..100)
+        .map(|i| Spike::create_terminal_vec(vec![
+            Spike::spike_vec_for(0, (1..=rng.gen_range(1..200)).filter(|_| rng.gen_bool(0.5)).collect()),
+            Spike::regular_train(1, i % 7 + 1, 20, 1)
+        ]))
+        .collect::<Vec<_>>();
+
+    let expected = batches.iter().map(|spikes| nn.solve_sequential(spikes.clone())).collect::<Vec<_>>();
+
+    assert!(expected.iter().any(|output| !output.is_empty()));
+    assert_eq!(nn.solve_batch(batches.clone()), expected);
+    assert_eq!(nn.solve_batch(batches), expected);
+    assert!(nn.solve_batch(vec![]).is_empty());
+}
+
+#[test]
+fn test_solve_with_dropout() {
+    let mut rng = Pcg64Mcg::seed_from_u64(5);
+    let config = LifNeuronConfig::new(0.0, 0.0, 1.5, 5.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer(vec![From::from(&config); 10], (0..10).map(|_| rng.gen_range(1.0..2.0)).collect::<Vec<_>>(), vec![0.0; 100])
+        .unwrap()
+        .layer(vec![From::from(&config); 10], (0..100).map(|_| rng.gen_range(0.0..0.5)).collect::<Vec<_>>(), vec![0.0; 100])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::create_terminal_vec(
+        (0..10).map(|neuron_id| Spike::poisson_train(neuron_id, 300.0, 200, neuron_id as u64)).collect()
+    );
+    let output = nn.solve_sequential(spikes.clone());
+
+    assert!(!output.is_empty());
+    assert_eq!(nn.solve_with_dropout(spikes.clone(), 0.0, 1), output);
+    assert!(nn.solve_with_dropout(spikes.clone(), 1.0, 1).is_empty());
+
+    let dropped = nn.solve_with_dropout(spikes.clone(), 0.5, 1);
+    assert_eq!(nn.solve_with_dropout(spikes.clone(), 0.5, 1), dropped);
+    assert!(dropped.len() < output.len());
+    assert!((2..10).any(|seed| nn.solve_with_dropout(spikes.clone(), 0.5, seed) != dropped));
+}
+
+#[test]
+fn test_classify_with_margin() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config), From::from(&config), From::from(&config)], [1.0, 1.0, 1.0], [[0.0; 3]; 3])
+        .build();
+
+    // A clear winner
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(1, (1..=10).collect()),
+        Spike::spike_vec_for(2, vec![1])
+    ]);
+    let (winner, margin) = nn.classify_with_margin(spikes, 3);
+    assert_eq!(winner, 1);
+    assert!((margin - 0.9).abs() < 1e-9);
+
+    // A near-tie
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, (1..=9).collect()),
+        Spike::spike_vec_for(2, (1..=10).collect())
+    ]);
+    let (winner, margin) = nn.classify_with_margin(spikes.clone(), 3);
+    assert_eq!(winner, 2);
+    assert!((margin - 0.1).abs() < 1e-9);
+
+    // The last neuron is not a class
+    assert_eq!(nn.classify_with_margin(spikes, 2), (0, 1.0));
+
+    // Ties and silence
+    assert_eq!(nn.classify_with_margin(Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(1, vec![1, 2]),
+        Spike::spike_vec_for(2, vec![1, 2])
+    ]), 3), (1, 0.0));
+    assert_eq!(nn.classify_with_margin(vec![], 3), (0, 0.0));
+}
+
+#[test]
+fn test_reachability() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer(vec![From::from(&config); 4], vec![1.0, 1.0, 1.0, 1.0], vec![
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.5, 0.0,
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0
+        ])
+        .unwrap()
+        .layer(vec![From::from(&config); 3], vec![
+            1.0, 0.0, 0.0,
+            0.0, 0.0, 0.0,
+            0.0, 2.0, 0.0,
+            0.0, 0.0, 0.0
+        ], vec![
+            0.0, -1.0, 0.0,
+            0.0, 0.0, 0.0,
+            0.0, 3.0, 0.0
+        ])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // Input 1 reaches the output layer only through the intra-layer synapse to neuron 2 of the first layer, and input 3 doesn't reach it at all
+    assert_eq!(nn.reachability(), vec![vec![0], vec![0, 1, 2], vec![]]);
+}
+
+#[test]
+fn test_to_dot() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config), From::from(&config)], [1.0, 0.0], [[0.0, 0.5], [0.0, 0.0]])
+        .layer([From::from(&config)], [[0.0], [2.0]], [[0.0]])
+        .build();
+
+    assert_eq!(nn.to_dot(), "\
+digraph nn {
+    rankdir=LR;
+    subgraph cluster_input {
+        label=\"input\";
+        in0;
+        in1;
+    }
+    subgraph cluster_0 {
+        label=\"layer 0\";
+        l0n0;
+        l0n1;
+    }
+    subgraph cluster_1 {
+        label=\"layer 1\";
+        l1n0;
+    }
+    in0 -> l0n0 [label=\"1\"];
+    l0n0 -> l0n1 [label=\"0.5\", style=dashed];
+    l0n1 -> l1n0 [label=\"2\"];
+}
+");
+}
+
+#[test]
+fn test_solve_event_driven() {
+    let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.5);
+    let sparse = |rows: usize, cols: usize, seed: usize| (0..rows*cols)
+        .map(|i| match (i * 7 + seed) % 11 {
+            0 => 1.4,
+            1 => -0.8,
+            _ => 0.0
+        })
+        .collect::<Vec<_>>();
+    // Only inhibitory intra-layer synapses, so that no neurons can excite each other forever
+    let inhibitory = |n: usize, seed: usize| sparse(n, n, seed).into_iter().map(|w: f64| -w.abs()).collect::<Vec<_>>();
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer(vec![From::from(&config); 20], vec![1.2; 20], inhibitory(20, 3))
+        .unwrap()
+        .layer(vec![From::from(&config); 50], sparse(20, 50, 5), inhibitory(50, 9))
+        .unwrap()
+        .layer(vec![From::from(&config); 10], sparse(50, 10, 2), vec![0.0; 100])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::create_terminal_vec((0..20).map(|neuron_id| Spike::poisson_train(neuron_id, 200.0, 500, neuron_id as u64)).collect());
+    let output = nn.solve_event_driven(spikes.clone());
+
+    assert!(!output.is_empty());
+    assert_eq!(output, nn.solve_sequential(spikes));
+}
+
+#[test]
+fn test_solve_wta() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config), From::from(&config), From::from(&config), From::from(&config)], [1.0; 4], [[0.0; 4]; 4])
+        .build();
+    let spikes = Spike::from_times(&[vec![5, 8], vec![3, 9], vec![3, 4], vec![1, 7]]);
+
+    assert_eq!(nn.solve_wta(spikes.clone(), 0), vec![]);
+    assert_eq!(nn.solve_wta(spikes.clone(), 1), vec![Spike::new(1, 3), Spike::new(7, 3)]);
+
+    // Neurons 1 and 2 fire first at the same time, so the lowest id wins
+    assert_eq!(nn.solve_wta(spikes.clone(), 2), vec![Spike::new(1, 3), Spike::new(3, 1), Spike::new(7, 3), Spike::new(9, 1)]);
+    assert_eq!(nn.solve_wta(spikes.clone(), 3).len(), 6);
+    assert_eq!(nn.solve_wta(spikes.clone(), 10), nn.solve_sequential(spikes));
+}
+
+#[test]
+fn test_solve_counting_ops() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config), From::from(&config), From::from(&config)], [1.0, 1.0, 0.0], [[0.0, -0.1, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]])
+        .layer([From::from(&config), From::from(&config)], [[1.0, 0.5], [0.0, 0.0], [1.0, 1.0]], [[0.0, 0.0], [0.0, 0.0]])
+        .build();
+
+    // Input 2 has a null input weight, and neuron 1 has no outgoing synapses
+    let spikes = Spike::from_times(&[vec![1], vec![3], vec![5]]);
+    let (output, ops) = nn.solve_counting_ops(spikes.clone());
+
+    assert_eq!(output, nn.solve_sequential(spikes));
+    assert_eq!(output, vec![Spike::new(1, 0), Spike::new(1, 1)]);
+    assert_eq!(ops, 2 + 3);
+
+    assert_eq!(nn.solve_counting_ops(vec![]), (vec![], 0));
+}
+
+#[test]
+fn test_nn_display_debug() {
+    let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config), From::from(&config)], [1.5, 0.01], [[0.0, -0.3], [-0.2, 0.0]])
+        .head([From::from(&config)], [[1.2], [0.4]], [[0.0]])
+        .head([From::from(&config), From::from(&config)], [[0.4, 1.2], [1.2, 0.4]], [[0.0, 0.0], [0.0, 0.0]])
+        .build();
+
+    assert_eq!(nn.to_string(), "NN: 2 layers [2, 3] neurons (output split in 2 heads [1, 2]), -0.3..1.5 weight range");
+
+    let debug = format!("{:?}", nn);
+    assert!(debug.starts_with("NN { layers: [Layer { neurons: [LifNeuron { v_rest: 1.0"));
+    assert!(debug.contains("intra_weights: [[0.0, -0.3],\n [-0.2, 0.0]]"));
+    assert!(debug.ends_with("heads: [1, 2] }"));
+
+    let silent = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config)], [0.0], [[0.0]])
+        .build();
+    assert_eq!(silent.to_string(), "NN: 1 layers [1] neurons, no synapses");
+}
+
+#[test]
+fn test_solve_until() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_with_delay_matrices(
+            vec![LifNeuron::new(&config); 3],
+            array![[1.0, 0.0, 0.0]],
+            array![[0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, 0.0]],
+            array![[1, 1, 1]],
+            array![[0, 10, 0], [0, 0, 20], [0, 0, 0]]
+        )
+        .unwrap()
+        .layer_uniform(3, &config, [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], [0.0; 9])
+        .unwrap()
+        .build()
+        .unwrap();
+    let spikes = Spike::spike_vec_for(0, vec![1, 2, 40]);
+    let full = nn.solve_sequential(spikes.clone());
+
+    // Spikes exactly at the cutoff are included, but not the delayed ones still on their way
+    assert_eq!(nn.solve_until(spikes.clone(), 12), vec![Spike::new(2, 0), Spike::new(3, 0), Spike::new(12, 1)]);
+    assert_eq!(nn.solve_until(spikes.clone(), 0), vec![]);
+
+    for t_max in [1, 2, 13, 31, 32, 33, 40, 41, 100] {
+        let prefix = full.iter().copied().filter(|spike| spike.ts <= t_max).collect::<Vec<_>>();
+        assert_eq!(nn.solve_until(spikes.clone(), t_max), prefix);
+    }
+}
+
+#[test]
+fn test_solve_resumable() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_with_delay_matrices(
+            vec![LifNeuron::new(&config); 3],
+            array![[1.0, 0.0, 0.0]],
+            array![[0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, 0.0]],
+            array![[1, 1, 1]],
+            array![[0, 10, 0], [0, 0, 20], [0, 0, 0]]
+        )
+        .unwrap()
+        .layer_uniform(3, &config, [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], [0.0; 9])
+        .unwrap()
+        .build()
+        .unwrap();
+    let full = nn.solve_sequential(Spike::spike_vec_for(0, vec![1, 2, 40]));
+    let between = |from, to| full.iter().copied().filter(|spike| from < spike.ts && spike.ts <= to).collect::<Vec<_>>();
+
+    // Every call stops at its last input spike, leaving the delayed spikes on their way
+    let (output, state) = nn.solve_resumable(Spike::spike_vec_for(0, vec![1, 2]), None);
+    assert_eq!(output, between(0, 2));
+    assert_eq!(state.ts(), Some(2));
+    assert!(state.has_pending_inputs());
+
+    let (output, state) = nn.solve_resumable(Spike::spike_vec_for(0, vec![40]), Some(state));
+    assert_eq!(output, between(2, 40));
+    assert_eq!(state.ts(), Some(40));
+
+    // Until the input stream is over
+    let (output, state) = nn.solve_resumable(vec![], Some(state));
+    assert_eq!(output, between(40, u128::MAX));
+    assert!(!state.has_pending_inputs());
+    assert!(state.ts() >= full.last().map(|spike| spike.ts));
+}
+
+#[test]
+#[should_panic(expected = "not later than")]
+fn test_solve_resumable_earlier_spikes() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config)], [1.0], [[0.0]])
+        .build();
+
+    let (_, state) = nn.solve_resumable(Spike::spike_vec_for(0, vec![1, 5]), None);
+    nn.solve_resumable(Spike::spike_vec_for(0, vec![5]), Some(state));
+}
+
+#[test]
+fn test_solve_reference() {
+    let mut rng = Pcg64Mcg::seed_from_u64(5);
+    let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.5);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_uniform(8, &config, [1.2; 8], (0..64).map(|_| -rng.gen_range(0.0..0.5)).collect::<Vec<_>>())
+        .unwrap()
+        .layer_uniform(6, &config, (0..48).map(|_| rng.gen_range(-0.5..1.5)).collect::<Vec<_>>(), (0..36).map(|_| -rng.gen_range(0.0..0.5)).collect::<Vec<_>>())
+        .unwrap()
+        .head(vec![From::from(&config); 2], (0..12).map(|_| rng.gen_range(0.0..1.5)).collect::<Vec<_>>(), [0.0, -0.4, -0.4, 0.0])
+        .unwrap()
+        .head(vec![From::from(&config); 3], (0..18).map(|_| rng.gen_range(0.0..1.5)).collect::<Vec<_>>(), [0.0; 9])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::create_terminal_vec((0..8).map(|neuron_id| Spike::poisson_train(neuron_id, 150.0, 300, neuron_id as u64)).collect());
+    let reference = nn.solve_reference(spikes.clone());
+
+    assert!(!reference.is_empty());
+    assert_eq!(reference, nn.solve_sequential(spikes));
+}
+
+#[test]
+#[should_panic(expected = "delayed synapses")]
+fn test_solve_reference_delays() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer_with_delays([From::from(&config)], [1.0], [[0.0]], [2])
+        .build();
+
+    nn.solve_reference(Spike::spike_vec_for(0, vec![1]));
+}
+
+#[test]
+fn test_solve_with_current() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    // f-I curve: the firing rate grows with the current injected at every timestamp
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config)], [1.0], [[0.0]])
+        .build();
+    let counts = [0.05, 0.5, 2.0].map(|current| nn.solve_with_current(vec![], (1..=10).map(|ts| (0, ts, current)).collect()).len());
+    assert_eq!(counts[0], 0);
+    assert!(counts[0] < counts[1] && counts[1] < counts[2]);
+    assert_eq!(counts[2], 10);
+
+    // Currents skip the delay of the input synapses, and sum to the delayed spikes reaching the neuron at the same time
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer_with_delays([From::from(&config), From::from(&config)], [0.06, 0.06], [[0.0; 2]; 2], [4, 4])
+        .build();
+    let spikes = vec![Spike::new(1, 0), Spike::new(1, 1)];
+    assert_eq!(nn.solve_with_current(spikes.clone(), vec![(0, 5, 0.03), (0, 5, 0.03), (1, 1, 0.06)]), vec![Spike::new(5, 0)]);
+
+    // Without any current, this is the same as solving sequentially
+    let spikes = Spike::poisson_train(0, 300.0, 100, 7);
+    assert_eq!(nn.solve_with_current(spikes.clone(), vec![]), nn.solve_sequential(spikes));
+}
+
+#[test]
+fn test_solve_with_context() {
+    let passthrough = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let integrator = LifNeuronConfig::new(0.0, 0.0, 2.2, 50.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&passthrough), From::from(&passthrough)], [1.0, 1.0], [[0.0, 0.0], [0.0, 0.0]])
+        .layer([From::from(&integrator), From::from(&integrator)], [[1.0, 1.0], [0.0, 1.0]], [[0.0, 0.0], [0.0, 0.0]])
+        .layer([From::from(&passthrough), From::from(&passthrough)], [[1.0, 0.0], [0.0, 1.0]], [[0.0, 0.0], [0.0, 0.0]])
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![10, 20]),
+        Spike::spike_vec_for(1, vec![30])
+    ]);
+    let baseline = nn.solve_sequential(spikes.clone());
+    assert_eq!(baseline, vec![Spike::new(30, 1)]);
+
+    // Without context, the behaviour is the same as a normal solve
+    assert_eq!(nn.solve_with_context(&[], 1, &spikes), baseline);
+
+    // Priming the hidden layer makes it fire earlier
+    let context = Spike::spike_vec_for(0, vec![1, 2]);
+    assert_eq!(nn.solve_with_context(&context, 1, &spikes), vec![Spike::new(10, 0), Spike::new(10, 1)]);
+
+    // Priming the first layer is the same as prepending the context to the input
+    let mut primed = context.clone();
+    primed.extend(spikes.iter().copied());
+    assert_eq!(nn.solve_with_context(&context, 0, &spikes), nn.solve_sequential(primed));
+}
+
+#[test]
+fn test_critical_path() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer([From::from(&config), From::from(&config)], [1.0, 1.0], [0.0, 0.0, 0.0, 0.0])
+        .unwrap()
+        .layer_with_delays([From::from(&config)], [1.0, 1.0], [0.0], [2, 6])
+        .unwrap()
+        .layer_with_delays([From::from(&config)], [1.0], [0.0], [3])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // The second input is earlier, but the first one reaches the hidden layer first
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![2]),
+        Spike::spike_vec_for(1, vec![1])
+    ]);
+
+    let path = nn.critical_path(spikes.clone());
+    assert_eq!(path, vec![Spike::new(2, 0), Spike::new(2, 0), Spike::new(4, 0), Spike::new(7, 0)]);
+    assert_eq!(path.len(), nn.num_layers() + 1);
+    assert!(path.windows(2).all(|pair| pair[0].ts <= pair[1].ts));
+    assert_eq!(path.last().copied(), nn.first_output_spike(spikes));
+
+    assert!(nn.critical_path(vec![]).is_empty());
+}
+
+#[test]
+fn test_lif_physical_units() {
+    let physical = (-65.0, -50.0, -70.0, 20.0);
+    let config = LifNeuronConfig::from_physical(physical.0, physical.1, physical.2, physical.3, 0.1);
+    let neuron = LifNeuron::new(&config);
+
+    assert_eq!((neuron.v_rest, neuron.v_threshold, neuron.v_reset), (-65.0, -50.0, -70.0));
+    assert!((neuron.tau - 200.0).abs() < 1e-9);
+
+    let (v_rest, v_thresh, v_reset, tau) = config.to_physical(0.1);
+    assert_eq!((v_rest, v_thresh, v_reset), (physical.0, physical.1, physical.2));
+    assert!((tau - physical.3).abs() < 1e-9);
+}
+
+#[test]
+fn test_weight_sweep() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config), From::from(&config)], [1.0, 0.05], [[0.0; 2]; 2])
+        .layer([From::from(&config)], [[0.0], [0.0]], [[0.0]])
+        .build();
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 2]),
+        Spike::spike_vec_for(1, vec![3])
+    ]);
+
+    // Tweak the weights in place between solves, without rebuilding the network
+    let mut outputs = vec![];
+    for weight in [0.0, 0.05, 1.0] {
+        *nn.get_weight_mut((0, 0), (1, 0)).unwrap() = weight;
+        outputs.push(nn.solve_sequential(spikes.clone()));
+    }
+    assert_eq!(outputs, vec![vec![], vec![], vec![Spike::new(1, 0), Spike::new(2, 0)]]);
+
+    *nn.get_input_weight_mut(1).unwrap() = 1.0;
+    *nn.get_weight_mut((0, 1), (1, 0)).unwrap() = 1.0;
+    assert_eq!(nn.solve_sequential(spikes).len(), 3);
+
+    assert!(nn.get_weight_mut((0, 2), (1, 0)).is_none());
+    assert!(nn.get_input_weight_mut(2).is_none());
+}
+
+#[test]
+fn test_lif_long_intervals() {
+    // A tiny tau makes the membrane decay completely between any two inputs
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1e-3))], [1.0], [[0.0]])
+        .build();
+
+    assert_eq!(
+        nn.solve_sequential(Spike::spike_vec_for(0, vec![1, 1_000_000_000, 1_000_000_001])),
+        Spike::spike_vec_for(0, vec![1, 1_000_000_000, 1_000_000_001])
+    );
+
+    // With a null tau, the inputs of the same timestamp must still add up
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                From::from(&LifNeuronConfig::new(0.0, 0.0, 1.0, 0.0)),
+                From::from(&LifNeuronConfig::new(0.0, 0.0, 1.0, 0.0))
+            ],
+            [1.5, 0.6],
+            [[0.0, 0.6], [0.0, 0.0]]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![5, 9]),
+        Spike::spike_vec_for(1, vec![5, 7, 9])
+    ]);
+    assert_eq!(nn.solve_sequential(spikes), vec![Spike::new(5, 0), Spike::new(5, 1), Spike::new(9, 0), Spike::new(9, 1)]);
+}
+
+/// Feed `inputs` to a fresh `neuron` and read its membrane potential through the [Model] trait alone
+fn potential_after<M: Model>(neuron: &M::Neuron, inputs: &[(u128, f64)]) -> f64 where for<'a> &'a M::Neuron: Into<M::SolverVars> {
+    let mut vars = neuron.into();
+    for (ts, input) in inputs {
+        M::handle_spike(neuron, &mut vars, *input, *ts);
+    }
+
+    M::membrane_potential(&vars)
+}
+
+#[test]
+fn test_membrane_potential() {
+    let lif = LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0));
+    assert_eq!(potential_after::<LeakyIntegrateFire>(&lif, &[]), 2.0);
+    assert_eq!(potential_after::<LeakyIntegrateFire>(&lif, &[(0, 0.05)]), 2.05);
+    // Reset after firing
+    assert_eq!(potential_after::<LeakyIntegrateFire>(&lif, &[(1, 1.0)]), 0.5);
+
+    let adex = AdExNeuron::new(&AdExNeuronConfig::default());
+    let mut vars = AdExSolverVars::from(&adex);
+    AdaptiveExponential::handle_spike(&adex, &mut vars, 3.0, 4);
+    assert_eq!(potential_after::<AdaptiveExponential>(&adex, &[(4, 3.0)]), vars.get_vars().0);
+
+    let coba = CobaNeuron::new(&CobaNeuronConfig::default());
+    let mut vars = CobaSolverVars::from(&coba);
+    ConductanceBased::handle_spike(&coba, &mut vars, 3.0, 4);
+    assert_eq!(potential_after::<ConductanceBased>(&coba, &[(4, 3.0)]), vars.get_vars().0);
+
+    // Hybrid neurons report the potential of their own model
+    type LifAdEx = Hybrid<LeakyIntegrateFire, AdaptiveExponential>;
+    assert_eq!(potential_after::<LifAdEx>(&HybridNeuron::First(lif), &[(0, 0.05)]), 2.05);
+    assert_eq!(potential_after::<LifAdEx>(&HybridNeuron::Second(adex.clone()), &[(4, 3.0)]), potential_after::<AdaptiveExponential>(&adex, &[(4, 3.0)]));
+}
+
+/// Feed `inputs` to a fresh `neuron` and export its state through the [Model] trait alone
+fn state_after<M: Model>(neuron: &M::Neuron, inputs: &[(u128, f64)]) -> Vec<(&'static str, f64)> where for<'a> &'a M::Neuron: Into<M::SolverVars> {
+    let mut vars = neuron.into();
+    for (ts, input) in inputs {
+        M::handle_spike(neuron, &mut vars, *input, *ts);
+    }
+
+    M::state(&vars)
+}
+
+#[test]
+fn test_model_state() {
+    let lif = LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0).with_threshold_adaptation(0.25, 10.0));
+    assert_eq!(state_after::<LeakyIntegrateFire>(&lif, &[(0, 0.05)]), vec![("v_mem", 2.05), ("threshold_offset", 0.0)]);
+    // Firing resets the membrane and raises the threshold
+    assert_eq!(state_after::<LeakyIntegrateFire>(&lif, &[(1, 1.0)]), vec![("v_mem", 0.5), ("threshold_offset", 0.25)]);
+
+    let adex = AdExNeuron::new(&AdExNeuronConfig::default());
+    let mut vars = AdExSolverVars::from(&adex);
+    AdaptiveExponential::handle_spike(&adex, &mut vars, 3.0, 4);
+    let (v_mem, w, _) = vars.get_vars();
+    assert_eq!(state_after::<AdaptiveExponential>(&adex, &[(4, 3.0)]), vec![("v_mem", v_mem), ("w", w)]);
+
+    let coba = CobaNeuron::new(&CobaNeuronConfig::default());
+    let mut vars = CobaSolverVars::from(&coba);
+    ConductanceBased::handle_spike(&coba, &mut vars, 3.0, 4);
+    let (v_mem, g_exc, g_inh, _) = vars.get_vars();
+    assert_eq!(state_after::<ConductanceBased>(&coba, &[(4, 3.0)]), vec![("v_mem", v_mem), ("g_exc", g_exc), ("g_inh", g_inh)]);
+
+    // Hybrid neurons report the state of their own model
+    type LifAdEx = Hybrid<LeakyIntegrateFire, AdaptiveExponential>;
+    assert_eq!(state_after::<LifAdEx>(&HybridNeuron::First(lif.clone()), &[(1, 1.0)]), state_after::<LeakyIntegrateFire>(&lif, &[(1, 1.0)]));
+    assert_eq!(state_after::<LifAdEx>(&HybridNeuron::Second(adex.clone()), &[(4, 3.0)]), state_after::<AdaptiveExponential>(&adex, &[(4, 3.0)]));
+}
+
+#[test]
+fn test_lif_neuron_config() {
+    let configs = vec![
+        LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2),
+        LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1).with_refractory_period(4)
+    ];
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer(LifNeuron::new_vec(configs.clone(), 2), [1.5, 1.8], [0.0, -0.3, -0.2, 0.0])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // The parameters can be read back from the neurons of a built (and cloned) network
+    let nn = nn.clone();
+    let read_back = (0..2).map(|neuron| nn.get_neuron(0, neuron).unwrap().config()).collect::<Vec<_>>();
+    assert_eq!(read_back, configs);
+}
+
+#[test]
+fn test_lif_config_validation() {
+    assert!(LifNeuronConfig::try_new(-65.0, -70.0, -50.0, 20.0).is_ok());
+
+    assert_eq!(LifNeuronConfig::try_new(1.0, 0.5, 2.0, 0.0).unwrap_err(), LifConfigError::NonPositiveTau(0.0));
+    assert_eq!(LifNeuronConfig::try_new(1.0, 0.5, 2.0, -3.0).unwrap_err(), LifConfigError::NonPositiveTau(-3.0));
+    assert_eq!(LifNeuronConfig::try_new(1.0, 0.5, 2.0, f64::NAN).unwrap_err(), LifConfigError::NonFiniteParameter("tau"));
+    assert_eq!(LifNeuronConfig::try_new(1.0, f64::NEG_INFINITY, 2.0, 1.0).unwrap_err(), LifConfigError::NonFiniteParameter("v_reset"));
+    assert_eq!(
+        LifNeuronConfig::try_new(1.0, 2.0, 2.0, 1.0).unwrap_err(),
+        LifConfigError::ThresholdNotAboveReset { v_threshold: 2.0, v_reset: 2.0 }
+    );
+}
+
+#[test]
+fn test_lif_config_builder() {
+    // Parameters can be set in any order
+    let config = LifNeuronConfig::builder()
+        .tau(1.2)
+        .v_threshold(3.0)
+        .v_reset(0.5)
+        .v_rest(1.0)
+        .refractory_period(2)
+        .threshold_adaptation(0.3, 10.0)
+        .build();
+    assert_eq!(config, LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2).with_refractory_period(2).with_threshold_adaptation(0.3, 10.0));
+
+    assert_eq!(LifNeuronConfig::builder().build(), LifNeuronConfig::new(0.0, 0.0, 1.0, 1.0));
+
+    assert_eq!(LifNeuronConfig::builder().v_rest(-65.0).v_reset(-70.0).v_threshold(-50.0).tau(20.0).try_build(), LifNeuronConfig::try_new(-65.0, -70.0, -50.0, 20.0));
+    assert_eq!(LifNeuronConfig::builder().tau(0.0).try_build(), Err(LifConfigError::NonPositiveTau(0.0)));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_spike_polarity() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 2.5, 100.0))],
+            [1.0],
+            [[0.0]]
+        )
+        .build();
+
+    // Two excitatory spikes are enough to fire
+    let excitatory = Spike::spike_vec_for(0, vec![1, 2]);
+
+    // The inhibitory spike lowers the membrane, so a third excitatory spike is needed
+    let mut mixed = vec![Spike::with_polarity(1, 0, -1)];
+    mixed.extend(Spike::spike_vec_for(0, vec![2, 3, 4]));
+
+    assert_eq!(nn.solve(excitatory), vec![vec![2]]);
+    assert_eq!(nn.solve(mixed), vec![vec![4]]);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_spike_polarity() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 2.5, 100.0))],
+            [1.0],
+            [[0.0]]
+        )
+        .build();
+
+    // Two excitatory spikes are enough to fire
+    let excitatory = Spike::spike_vec_for(0, vec![1, 2]);
+
+    // The inhibitory spike lowers the membrane, so a third excitatory spike is needed
+    let mut mixed = vec![Spike::with_polarity(1, 0, -1)];
+    mixed.extend(Spike::spike_vec_for(0, vec![2, 3, 4]));
+
+    assert_eq!(nn.solve(excitatory).await, vec![vec![2]]);
+    assert_eq!(nn.solve(mixed).await, vec![vec![4]]);
+}
+
+#[test]
+fn test_overshoot_report() {
+    let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config), From::from(&config)],
+            [5.0, 5.0],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .layer(
+            [From::from(&config)],
+            [
+                [3.0],
+                [3.0]
+            ],
+            [
+                [0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1]),
+        Spike::spike_vec_for(1, vec![5])
+    ]);
+
+    let report = nn.overshoot_report(spikes);
+    let expected_last = 1.0 + (0.5 - 1.0) * (-4f64).exp() + 3.0;
+
+    assert_eq!(report[..3], [(0, 0, 6.0), (1, 0, 4.0), (0, 1, 6.0)]);
+    assert_eq!((report[3].0, report[3].1), (1, 0));
+    assert!((report[3].2 - expected_last).abs() < 1e-9);
+    assert_eq!(report.len(), 4);
+}
+
+#[test]
+fn test_solve_with_probe() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&LifNeuronConfig::new(0.0, 0.0, 1.0, 5.0)), From::from(&LifNeuronConfig::new(0.0, 0.0, 1.0, 5.0))],
+            [0.3, 0.2],
+            [[0.0, 0.0], [0.0, 0.0]]
+        )
+        .build();
+
+    let spikes = Spike::spike_vec_for(0, (1..=40).collect());
+    let (output, traces) = nn.solve_with_probe(spikes.clone(), &[(0, 0), (0, 1)]);
+
+    assert_eq!(output, nn.solve_sequential(spikes));
+    assert_eq!(traces[0].len(), 40);
+    // The second neuron never receives any input
+    assert!(traces[1].is_empty());
+
+    // Sawtooth: the membrane charges up between spikes, and drops to the reset potential when the neuron fires
+    let fired = output.iter().map(|spike| spike.ts).collect::<Vec<_>>();
+    assert!(fired.len() > 3);
+    for pair in traces[0].windows(2) {
+        let ((_, before), (ts, after)) = (pair[0], pair[1]);
+        if fired.contains(&ts) {
+            assert_eq!(after, 0.0);
+        } else {
+            assert!(after > before);
+        }
+    }
+}
+
+#[test]
+fn test_stdp_learning() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config)], [1.0], [[0.0]])
+        .layer([From::from(&config)], [[1.0]], [[0.0]])
+        .build();
+
+    // Every presynaptic spike is immediately followed by a postsynaptic one
+    let spikes = Spike::spike_vec_for(0, (0..10).map(|i| 1 + 10*i).collect());
+    let output = nn.solve_with_learning(spikes.clone(), &ExponentialStdp::new(0.1, 0.12, 20.0, 20.0));
+
+    assert_eq!(output, vec![spikes.iter().map(|s| s.ts).collect::<Vec<_>>()]);
+    assert!(nn[((0, 0), (1, 0))] > 1.2);
+}
+
+#[test]
+fn test_stdp_learning_with_skips() {
+    let passthrough = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let coincidence = LifNeuronConfig::new(0.0, 0.0, 1.5, 1.0);
+    let frozen = ExponentialStdp::new(0.0, 0.0, 20.0, 20.0);
+
+    // The output layer is only reached through the skip connection
+    let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_uniform(1, &passthrough, [1.0], [0.0]).unwrap()
+        .layer_uniform(1, &passthrough, [0.0], [0.0]).unwrap()
+        .layer_uniform(1, &passthrough, [0.0], [0.0]).unwrap()
+        .skip_connection(0, 2, array![[3.0]]).unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::spike_vec_for(0, vec![1, 4]);
+    assert_eq!(nn.solve_sequential(spikes.clone()), vec![Spike::new(1, 0), Spike::new(4, 0)]);
+    assert_eq!(nn.solve_with_learning(spikes, &frozen), vec![vec![1, 4]]);
+
+    // Same network as in `test_skip_connections`, where the skip input must coincide with the one of the hidden layer
+    let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_uniform(2, &passthrough, [1.0, 1.0], [0.0; 4]).unwrap()
+        .layer_uniform(1, &passthrough, [1.0, 0.0], [0.0]).unwrap()
+        .layer_uniform(1, &coincidence, [1.0], [0.0]).unwrap()
+        .skip_connection(0, 2, array![[0.0], [1.0]]).unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::from_times(&[vec![1, 5, 9], vec![5, 12]]);
+    assert_eq!(nn.solve_sequential(spikes.clone()), vec![Spike::new(5, 0)]);
+    assert_eq!(nn.solve_with_learning(spikes, &frozen), vec![vec![5]]);
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn test_solve_traces_to_parquet() {
+    use std::fs::File;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                From::from(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+                From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+            ],
+            [1.5, 1.8],
+            [
+                [0.0, -0.3],
+                [-0.2, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 3, 4]),
+        Spike::spike_vec_for(1, vec![2, 3, 6])
+    ]);
+
+    let path = std::env::temp_dir().join(format!("pds_spiking_nn_trace_{}.parquet", std::process::id()));
+    nn.solve_traces_to_parquet(spikes, File::create(&path).unwrap()).unwrap();
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+    let columns = reader.schema().fields().iter().map(|field| field.name().clone()).collect::<Vec<_>>();
+    let num_rows = reader.build().unwrap().map(|batch| batch.unwrap().num_rows()).sum::<usize>();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(columns, vec!["layer", "neuron", "ts", "v_mem"]);
+    // One row for each input received by a neuron, including the intra-layer ones caused by the two output spikes
+    assert_eq!(num_rows, 8);
+}
+
+#[test]
+fn test_spike_csv() {
+    let mut spikes = Spike::create_terminal_vec(vec![
+        Spike::poisson_train(0, 50.0, 1000, 7),
+        Spike::poisson_train(1, 80.0, 1000, 8),
+        vec![Spike::with_polarity(500, 2, -1)]
+    ]);
+
+    let mut csv = vec![];
+    Spike::to_csv(&spikes, &mut csv).unwrap();
+    assert_eq!(Spike::from_csv(csv.as_slice()).unwrap(), spikes);
+
+    // The export doesn't depend on the order of the spikes
+    spikes.reverse();
+    let mut reversed_csv = vec![];
+    Spike::to_csv(&spikes, &mut reversed_csv).unwrap();
+    assert_eq!(reversed_csv, csv);
+
+    for (malformed, reason) in [
+        ("0,1\n\n0,x\n", "invalid timestamp"),
+        ("0,1\n\n-1,2\n", "invalid neuron id"),
+        ("0,1\n\n0,2,+\n", "invalid polarity"),
+        ("0,1\n\n0,2,1,1\n", "expected 2 or 3 comma-separated fields")
+    ] {
+        match Spike::from_csv(malformed.as_bytes()) {
+            Err(SpikeCsvError::Malformed { line, reason: r, .. }) => {
+                assert_eq!(line, 3);
+                assert_eq!(r, reason);
+            },
+            res => panic!("Unexpected result: {:?}", res)
+        }
+    }
+}
+
+#[test]
+fn test_layer_uniform() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let uniform = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer_uniform(&config, [1.0, 1.0], [[0.0, -0.3], [-0.3, 0.0]])
+        .layer_uniform(&config, [[1.0, 0.5, 0.2], [0.2, 0.5, 1.0]], [[0.0; 3]; 3])
+        .build();
+    let explicit = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config), From::from(&config)], [1.0, 1.0], [[0.0, -0.3], [-0.3, 0.0]])
+        .layer([From::from(&config), From::from(&config), From::from(&config)], [[1.0, 0.5, 0.2], [0.2, 0.5, 1.0]], [[0.0; 3]; 3])
+        .build();
+    let dynamic = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_uniform(2, &config, [1.0, 1.0], [0.0, -0.3, -0.3, 0.0])
+        .unwrap()
+        .layer_uniform(3, &config, [1.0, 0.5, 0.2, 0.2, 0.5, 1.0], [0.0; 9])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 2, 5, 8]),
+        Spike::spike_vec_for(1, vec![2, 3, 8])
+    ]);
+    let expected = explicit.solve_sequential(spikes.clone());
+
+    assert_eq!(uniform.solve_sequential(spikes.clone()), expected);
+    assert_eq!(dynamic.solve_sequential(spikes), expected);
+    assert!(matches!(
+        NNBuilder::<LeakyIntegrateFire, _>::new_dynamic().layer_uniform(0, &config, [], []),
+        Err(DynamicBuilderError::EmptyLayer(_))
+    ));
+}
+
+#[test]
+fn test_layer_random() {
+    use rand::distributions::Uniform;
+
+    let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    let dist = Uniform::new(-1.0, 1.0);
+
+    let mut rng = Pcg64Mcg::seed_from_u64(7);
+    let static_nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer_random([From::from(&config), From::from(&config), From::from(&config)], &mut rng, dist)
+        .layer_random([From::from(&config), From::from(&config)], &mut rng, dist)
+        .build();
+
+    let mut rng = Pcg64Mcg::seed_from_u64(7);
+    let dynamic_nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_random(vec![LifNeuron::new(&config); 3], &mut rng, dist).unwrap()
+        .layer_random(vec![LifNeuron::new(&config); 2], &mut rng, dist).unwrap()
+        .build()
+        .unwrap();
+
+    // The same seed always produces the same weights, regardless of the builder
+    assert_eq!(static_nn.input_weights(), dynamic_nn.input_weights());
+    assert_eq!(static_nn.synapses_between(0), dynamic_nn.synapses_between(0));
+
+    for (static_layer, dynamic_layer) in static_nn.iter().zip(dynamic_nn.iter()) {
+        let n = static_layer.num_neurons();
+
+        for from in 0..n {
+            for to in 0..n {
+                let weight = static_layer.get_intra_weight(from, to).unwrap();
+
+                assert_eq!(Some(weight), dynamic_layer.get_intra_weight(from, to));
+                if from == to {
+                    assert_eq!(weight, 0.0);
+                } else {
+                    assert!((-1.0..1.0).contains(&weight) && weight != 0.0);
+                }
+            }
+        }
+    }
+
+    // Input weights are only sampled on the diagonal for the entry layer
+    assert!(static_nn.input_weights().iter().all(|w| *w != 0.0));
+
+    let builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic().layer_random([], &mut rng, dist);
+    assert!(matches!(builder, Err(DynamicBuilderError::EmptyLayer(_))));
+}
+
+#[test]
+fn test_fully_connected() {
+    use rand::distributions::Uniform;
+
+    let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 1.0);
+    let dist = Uniform::new(0.5, 1.0);
+
+    let mut rng = Pcg64Mcg::seed_from_u64(11);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::fully_connected(&[4, 3, 2], &config, &mut rng, dist).unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!((0..3).map(|layer| nn.layer_size(layer).unwrap()).collect::<Vec<_>>(), vec![4, 3, 2]);
+    assert!(nn.input_weights().iter().all(|w| (0.5..1.0).contains(w)));
+    for layer in 0..2 {
+        assert!(nn.synapses_between(layer).unwrap().iter().all(|w| (0.5..1.0).contains(w)));
+    }
+    assert!(nn.iter().all(|layer| (0..layer.num_neurons()).all(|from| (0..layer.num_neurons()).all(|to| layer.get_intra_weight(from, to) == Some(0.0)))));
+
+    // The same seed always produces the same network
+    let mut rng = Pcg64Mcg::seed_from_u64(11);
+    let same_nn = NNBuilder::<LeakyIntegrateFire, _>::fully_connected(&[4, 3, 2], &config, &mut rng, dist).unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(nn.input_weights(), same_nn.input_weights());
+    assert_eq!(nn.synapses_between(0), same_nn.synapses_between(0));
+    assert_eq!(nn.synapses_between(1), same_nn.synapses_between(1));
+
+    let builder = NNBuilder::<LeakyIntegrateFire, _>::fully_connected(&[4, 0, 2], &config, &mut rng, dist);
+    assert!(matches!(builder, Err(DynamicBuilderError::EmptyLayer(_))));
+}
+
+#[test]
+fn test_perturbed() {
+    use rand::distributions::Uniform;
+
+    let config = LifNeuronConfig::new(1.0, 0.5, 2.0, 0.01);
+    let mut rng = Pcg64Mcg::seed_from_u64(17);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::fully_connected(&[20, 50, 10], &config, &mut rng, Uniform::new(0.5, 1.0)).unwrap()
+        .build()
+        .unwrap();
+
+    // No noise, no changes
+    let same = nn.perturbed(&mut rng, 0.0, 0.0);
+    assert_eq!(format!("{:?}", same), format!("{:?}", nn));
+
+    let mut rng = Pcg64Mcg::seed_from_u64(3);
+    let mutant = nn.perturbed(&mut rng, 0.2, 0.5);
+    let mut rng = Pcg64Mcg::seed_from_u64(3);
+    assert_eq!(format!("{:?}", nn.perturbed(&mut rng, 0.2, 0.5)), format!("{:?}", mutant));
+
+    // The noise on the weights has the requested standard deviation, and the missing synapses stay missing
+    let diffs = (0..2)
+        .flat_map(|layer| (mutant.synapses_between(layer).unwrap() - nn.synapses_between(layer).unwrap()).into_iter())
+        .collect::<Vec<_>>();
+    let mean = diffs.iter().sum::<f64>() / diffs.len() as f64;
+    let std = (diffs.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / diffs.len() as f64).sqrt();
+    assert!(mean.abs() < 0.02 && (std - 0.2).abs() < 0.02, "mean {mean}, std {std}");
+    assert!(mutant.iter().all(|layer| (0..layer.num_neurons()).all(|n| layer.get_intra_weight(n, n) == Some(0.0))));
+
+    // Every neuron is perturbed, keeping its time constant positive
+    let neurons = mutant.iter().flat_map(|layer| (0..layer.num_neurons()).map(move |n| layer.get_neuron(n).unwrap())).collect::<Vec<_>>();
+    assert!(neurons.iter().all(|neuron| neuron.v_threshold != 2.0 && neuron.tau > 0.0));
+
+    // Hybrid networks perturb every neuron with its own model
+    type LifAdEx = Hybrid<LeakyIntegrateFire, AdaptiveExponential>;
+    let adex = AdExNeuronConfig::default();
+    let nn = NNBuilder::<LifAdEx, _>::new()
+        .layer([From::from(&HybridNeuronConfig::First(config)), From::from(&HybridNeuronConfig::Second(adex.clone()))], [1.0, 1.0], [[0.0, 0.0], [0.0, 0.0]])
+        .build();
+    let mutant = nn.perturbed(&mut rng, 0.0, 0.01);
+    assert!(matches!(mutant.get_neuron(0, 0), Some(HybridNeuron::First(lif)) if lif.v_rest != 1.0));
+    assert!(matches!(mutant.get_neuron(0, 1), Some(HybridNeuron::Second(neuron)) if neuron.c_m != adex.c_m && neuron.c_m > 0.0));
+}
+
+#[test]
+fn test_saturation_threshold() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)), From::from(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2))],
+            [1.0, 1.0],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .layer(
+            [From::from(&LifNeuronConfig::new(0.8, 0.3, 2.2, 1.3))],
+            [
+                [1.4],
+                [0.0]
+            ],
+            [[0.0]]
+        )
+        .build();
+
+    let (max_input, step) = (6.0, 0.25);
+    let level = nn.saturation_threshold(0, max_input, step);
+
+    // Number of output spikes with a constant input of the given level
+    let output_spikes = |level| {
+        let mut nn = nn.clone();
+        *nn.get_input_weight_mut(0).unwrap() = level;
+        nn.solve_sequential(Spike::spike_vec_for(0, (0..1000).collect())).len()
+    };
+
+    assert!(level > 0.0 && level < max_input);
+    assert!(output_spikes(level - step) < output_spikes(level));
+    for i in 1..=((max_input - level) / step) as usize {
+        assert_eq!(output_spikes(level + i as f64 * step), output_spikes(level));
+    }
+}
+
+#[test]
+fn test_effective_connectivity() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config), From::from(&config), From::from(&config)],
+            [1.0, 1.0, 1.0],
+            [
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0]
+            ]
+        )
+        .layer(
+            [From::from(&config), From::from(&config)],
+            [
+                [3.0, 0.0],
+                [0.0, 0.0],
+                [0.0, 3.0]
+            ],
+            [
+                [0.0, 0.0],
+                [0.0, 0.0]
+            ]
+        )
+        .build();
+
+    // The third neuron of the first layer is never stimulated, so its strong synapse is never used
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 5, 9]),
+        Spike::spike_vec_for(1, vec![3, 7])
+    ]);
+
+    let connectivity = nn.effective_connectivity(spikes);
+
+    assert_eq!(connectivity.dim(), (5, 5));
+    assert_eq!(connectivity[(0, 3)], 1.0);
+    assert_eq!(connectivity[(1, 3)], 0.0);
+    assert_eq!(connectivity[(2, 3)], 0.0);
+    assert!(connectivity.column(4).iter().all(|c| *c == 0.0));
+}
+
+#[test]
+fn test_solve_multihead() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&config), From::from(&config), From::from(&config)],
+            [1.0, 1.0, 1.0],
+            [
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0]
+            ]
+        )
+        // The first head fires when any of the backbone neurons does
+        .head(
+            [From::from(&config)],
+            [[1.0], [1.0], [1.0]],
+            [[0.0]]
+        )
+        // The second head needs two backbone neurons firing together, and has lateral inhibition
+        .head(
+            [From::from(&LifNeuronConfig::new(0.0, 0.0, 1.5, 1.0)), From::from(&LifNeuronConfig::new(0.0, 0.0, 1.5, 1.0))],
+            [[1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+            [[0.0, -5.0], [0.0, 0.0]]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 4, 10]),
+        Spike::spike_vec_for(1, vec![4, 7, 10]),
+        Spike::spike_vec_for(2, vec![7, 12])
+    ]);
+
+    let heads = nn.solve_multihead(spikes.clone());
+    assert_eq!(heads.len(), 2);
+    assert_eq!(heads[0].iter().map(|spike| spike.ts).collect::<Vec<_>>(), vec![1, 4, 7, 10, 12]);
+    assert!(heads[0].iter().all(|spike| spike.neuron_id == 0));
+    assert_eq!(heads[1], vec![Spike::new(4, 0), Spike::new(7, 1), Spike::new(10, 0)]);
+
+    // The heads are solved together by the other solvers too
+    assert_eq!(nn.solve_sequential(spikes).len(), heads[0].len() + heads[1].len());
+}
+
+#[test]
+fn test_heads_kept_after_invalid_delays() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let builder = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_uniform(2, &config, [1.0, 1.0], [0.0; 4]).unwrap()
+        .head(vec![LifNeuron::new(&config)], [1.0, 1.0], [0.0]).unwrap()
+        .head(vec![LifNeuron::new(&config); 2], [1.0, 0.0, 0.0, 1.0], [0.0; 4]).unwrap();
+
+    // The heads would feed the new layer, which has the wrong number of delays
+    let builder = match builder.layer_with_delays(vec![LifNeuron::new(&config)], [1.0; 3], [0.0], [1; 2]) {
+        Err(DynamicBuilderError::InvalidInputDelays { builder, expected: 3, found: 2 }) => builder,
+        _ => panic!("expected InvalidInputDelays")
+    };
+
+    let nn = builder.build().unwrap();
+    assert_eq!(nn.num_heads(), 2);
+    assert_eq!(nn.layer_size(1), Some(3));
+}
+
+#[test]
+fn test_solve_streaming() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_with_delay_matrices(
+            [From::from(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0)), From::from(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))],
+            array![[1.0, 1.0]],
+            array![[0.0, -0.5], [-0.5, 0.0]],
+            array![[1, 4]],
+            array![[0, 2], [3, 0]]
+        )
+        .unwrap()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0))
+            ],
+            [0.9, 0.85, 1.0, 0.8, 0.9, 0.0],
+            [0.0, -0.2, 0.0, -0.15, 0.0, 0.0, 0.0, 0.0, 0.0]
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, (0..200).filter(|ts| ts % 3 != 0).collect()),
+        Spike::spike_vec_for(1, (0..200).filter(|ts| ts % 4 == 1).collect())
+    ]);
+
+    let expected = nn.solve_sequential(spikes.clone());
+    assert!(!expected.is_empty());
+    assert_eq!(nn.solve_streaming(spikes.clone()).collect::<Vec<_>>(), expected);
+
+    // The output can also be consumed partially
+    assert_eq!(nn.solve_streaming(spikes).take(3).collect::<Vec<_>>(), expected[..3]);
+}
+
+#[test]
+fn test_critical_gain() {
+    let build = |gain: f64| NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&LifNeuronConfig::new(0.0, 0.0, 1.0, 2.0)), From::from(&LifNeuronConfig::new(0.0, 0.0, 1.2, 1.0))],
+            [0.4 * gain, 0.5 * gain],
+            [[0.0, -0.1 * gain], [-0.1 * gain, 0.0]]
+        )
+        .layer(
+            [From::from(&LifNeuronConfig::new(0.0, 0.0, 1.0, 1.0))],
+            [[0.6 * gain], [0.6 * gain]],
+            [[0.0]]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, (0..200).collect()),
+        Spike::spike_vec_for(1, (0..200).collect())
+    ]);
+    let output_rate = |gain: f64| build(gain).solve_sequential(spikes.clone()).len() as f64 / 200.0;
+
+    let gain = build(1.0).critical_gain(spikes.clone(), 1e-3);
+    assert!(gain.is_finite() && gain > 0.0);
+
+    assert!(output_rate(gain * 0.5) < 1.0);
+    assert!(output_rate(gain * 0.95) < 1.0);
+    assert!(output_rate(gain * 1.05) >= 1.0);
+    assert!(output_rate(gain * 2.0) >= 1.0);
+}
+
+#[test]
+fn test_critical_gain_excitatory_cycle() {
+    let config = LifNeuronConfig::new(0.0, 0.0, 1.0, 1.0);
+
+    // The inhibitory output layer can't saturate, while the weak mutual excitation of the hidden neurons would cascade forever
+    // once scaled up enough
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([From::from(&config), From::from(&config)], [2.0, 2.0], [[0.0, 0.01], [0.01, 0.0]])
+        .layer([From::from(&config)], [[-1.0], [-1.0]], [[0.0]])
+        .build();
+
+    let spikes = Spike::spike_vec_for(0, (0..10).collect());
+    assert_eq!(nn.critical_gain(spikes, 1e-3), 0.0);
+}
+
+#[test]
+fn test_necessary_inputs() {
+    let passthrough = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [From::from(&passthrough), From::from(&passthrough), From::from(&passthrough)],
+            [1.0, 1.0, 1.0],
+            [
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0]
+            ]
+        )
+        .layer(
+            [From::from(&LifNeuronConfig::new(0.0, 0.0, 1.5, 1.0))],
+            [[1.0], [1.0], [0.0]],
+            [[0.0]]
+        )
+        .build();
+
+    // The output neuron only fires when the first two inputs coincide, the third one never reaches it
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 5]),
+        Spike::spike_vec_for(1, vec![5]),
+        Spike::spike_vec_for(2, vec![1, 3, 5])
+    ]);
+
+    assert_eq!(nn.solve_sequential(spikes.clone()), vec![Spike::new(5, 0)]);
+    assert_eq!(nn.necessary_inputs(spikes.clone(), Spike::new(5, 0)), vec![0, 1]);
+    assert!(nn.necessary_inputs(spikes, Spike::new(3, 0)).is_empty());
+}
+
+#[test]
+fn test_spike_vec_for() {
+    assert_eq!(
+        Spike::spike_vec_for(4, vec![4, 7, 3, 10, 11, 2]),
+        {
+            let mut v = vec![4, 7, 3, 10, 11, 2]
+                .into_iter()
+                .map(|ts| Spike::new(ts, 4))
+                .collect::<Vec<_>>();
+            
+            v.sort();
+            v
+        }
+    );
+}
+
+#[test]
+fn test_spike_vec_for_empty() {
+    assert_eq!(
+        Spike::spike_vec_for(1, vec![]),
+        vec![]
+    );
+}
+
+#[test]
+fn test_spike_vec_for_repeating() {
+    assert_eq!(
+        Spike::spike_vec_for(7, vec![1, 1, 1, 5, 1]),
+        vec![1, 1, 1, 1, 5].into_iter().map(|ts| Spike::new(ts, 7)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_spike_rle() {
+    // Sparse, regular train with a single irregular spike in the middle
+    let regular = (1..=1000).map(|i| 5000 * i).chain([5_002_500]).chain((1001..=2000).map(|i| 5000 * i)).collect::<Vec<_>>();
+    let irregular = vec![0, 0, 3, 7, 7, 8, 20, 21];
+
+    let encoded = Spike::rle_encode(&regular);
+    assert_eq!(Spike::rle_decode(&encoded), regular);
+    assert!(encoded.len() < 5);
+    assert!(std::mem::size_of_val(encoded.as_slice()) < std::mem::size_of_val(regular.as_slice()));
+
+    assert_eq!(Spike::rle_decode(&Spike::rle_encode(&irregular)), irregular);
+    assert!(Spike::rle_encode(&[]).is_empty());
+}
+
+#[test]
+fn test_create_terminal_vec(){
+    let spikes_neuron_1 = [11, 9, 23, 43, 42].to_vec();
+    let spike_vec_for_neuron_1 = Spike::spike_vec_for(1, spikes_neuron_1 );
+    
+    let spikes_neuron_2 = [1, 29, 3, 11, 22].to_vec();
+    let spike_vec_for_neuron_2 = Spike::spike_vec_for(2, spikes_neuron_2 );
+    
+    let spikes: Vec<Vec<Spike>> = [spike_vec_for_neuron_1, spike_vec_for_neuron_2].to_vec();
+    
+    let sorted_spike_array_for_nn: Vec<Spike> = Spike::create_terminal_vec(spikes);
+    
+    assert_eq!(
+        sorted_spike_array_for_nn,
+        {
+            let mut v = [11, 9, 23, 43, 42].into_iter()
+                .map(|ts| Spike::new(ts, 1))
+                .chain([1, 29, 3, 11, 22].into_iter().map(|ts| Spike::new(ts, 2)))
+                .collect::<Vec<_>>();
+            
+            v.sort();
+            v
+        }
+    );
+}
+
+#[test]
+fn test_poisson_train() {
+    let spikes = Spike::poisson_train(3, 50.0, 100_000, 11);
+
+    assert!(spikes.windows(2).all(|w| w[0] < w[1]));
+    assert!(spikes.iter().all(|s| s.neuron_id == 3 && s.ts < 100_000));
+    assert_eq!(spikes, Spike::poisson_train(3, 50.0, 100_000, 11));
+    assert_ne!(spikes, Spike::poisson_train(3, 50.0, 100_000, 12));
+
+    // 50 Hz over 100 s, within four standard deviations
+    let count = spikes.len() as f64;
+    assert!((count - 5000.0).abs() <= 4.0 * 5000f64.sqrt() + 5000.0 * 0.05, "expected about 5000 spikes, found {count}");
+}
+
+#[test]
+fn test_poisson_train_zero_rate() {
+    assert!(Spike::poisson_train(0, 0.0, 1_000_000, 42).is_empty());
+}
+
+#[test]
+fn test_create_terminal_vec_is_stable() {
+    let spikes = Spike::create_terminal_vec(vec![
+        vec![Spike::with_polarity(5, 1, -1), Spike::new(2, 1), Spike::new(5, 1)],
+        vec![Spike::new(5, 0), Spike::with_polarity(5, 1, 1), Spike::with_polarity(5, 0, -1)]
+    ]);
+
+    assert_eq!(spikes, vec![
+        Spike::new(2, 1),
+        Spike::new(5, 0),
+        Spike::with_polarity(5, 0, -1),
+        Spike::with_polarity(5, 1, -1),
+        Spike::new(5, 1),
+        Spike::new(5, 1)
+    ]);
+
+    // The polarity never takes part in the ordering
+    let spikes = Spike::create_terminal_vec(vec![vec![Spike::new(5, 1), Spike::with_polarity(5, 1, -1)]]);
+    assert_eq!(spikes, vec![Spike::new(5, 1), Spike::with_polarity(5, 1, -1)]);
+}
+
+#[test]
+fn test_merge_sorted() {
+    let mut rng = Pcg64Mcg::seed_from_u64(6513);
+    let mut random_spikes = |n: usize| Spike::create_terminal_vec(vec![
+        (0..n).map(|_| Spike::with_polarity(rng.gen_range(0..50), rng.gen_range(0..4), if rng.gen() { 1 } else { -1 })).collect()
+    ]);
+
+    for (n, m) in [(0, 0), (0, 10), (10, 0), (100, 100), (500, 30)] {
+        let (a, b) = (random_spikes(n), random_spikes(m));
+        assert_eq!(Spike::merge_sorted(&a, &b), Spike::create_terminal_vec(vec![a, b]));
+    }
+}
+
+#[test]
+fn test_from_times() {
+    let times = vec![vec![11, 9, 23], vec![], vec![1, 29, 9, 11]];
+    let spikes = Spike::from_times(&times);
+
+    assert_eq!(spikes, Spike::create_terminal_vec(times.iter().enumerate().map(|(i, ts)| Spike::spike_vec_for(i, ts.clone())).collect()));
+    assert_eq!(spikes.len(), 7);
+    assert!(Spike::from_times(&[]).is_empty());
+}
+
+#[test]
+fn test_from_bitmatrix() {
+    let mut rng = Pcg64Mcg::seed_from_u64(13);
+    let bits = (0..5).map(|_| (0..rng.gen_range(0..40)).map(|_| rng.gen_bool(0.7)).collect::<Vec<_>>()).collect::<Vec<_>>();
+    let times = bits.iter()
+        .map(|row| row.iter().enumerate().filter(|(_, bit)| **bit).map(|(step, _)| step as u128 * 3).collect())
+        .collect::<Vec<_>>();
+
+    assert_eq!(Spike::from_bitmatrix(&bits, 3), Spike::from_times(&times));
+    assert!(Spike::from_bitmatrix(&[], 3).is_empty());
+    assert!(Spike::from_bitmatrix(&[vec![false; 10], vec![]], 3).is_empty());
+}
+
+#[test]
+fn test_rate_encode() {
+    let values = [0.0, 0.25, 1.0, 0.5];
+    let spikes = encoding::rate_encode(&values, 20_000, 50.0, 7);
+
+    assert!(spikes.windows(2).all(|w| w[0] < w[1]));
+    assert!(spikes.iter().all(|s| s.ts < 20_000 && s.neuron_id < values.len()));
+
+    // 20 s at 50 Hz: about 1000 spikes at full intensity
+    let rates = Spike::firing_rates(&spikes, values.len(), 20_000);
+    for (rate, value) in rates.iter().zip(values) {
+        assert!((rate * 1000.0 - value * 50.0).abs() <= 5.0, "{rate} for {value}");
+    }
+
+    assert!(encoding::rate_encode(&values, 20_000, 0.0, 7).is_empty());
+    assert_ne!(encoding::rate_encode(&values, 20_000, 50.0, 8), spikes);
+}
+
+#[test]
+fn test_latency_encode() {
+    let values = [0.9, f64::NAN, 1.5, -0.3, 0.1, 0.9];
+    let spikes = encoding::latency_encode(&values, 100);
+
+    assert_eq!(spikes, vec![Spike::new(0, 2), Spike::new(10, 0), Spike::new(10, 5), Spike::new(90, 4)]);
+    assert!(encoding::latency_encode(&[], 100).is_empty());
+}
+
+#[test]
+fn test_readout_rules() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 3.0, 1.2)),
+                From::from(&LifNeuronConfig::new(1.0, 0.4, 3.1, 1.1))
+            ],
+            [1.5, 1.8],
+            [[0.0, -0.3], [-0.2, 0.0]]
+        )
+        .build();
+
+    let output = nn.solve_sequential(Spike::create_terminal_vec(vec![
+        Spike::spike_vec_for(0, vec![1, 3, 4]),
+        Spike::spike_vec_for(1, vec![2, 3, 6])
+    ]));
+    assert_eq!(output, vec![Spike::new(3, 1), Spike::new(4, 0)]);
+
+    // One spike each: the tie goes to the lowest class, while neuron 1 fired first
+    assert_eq!(Spike::argmax_by_count(&output, 2), Some(0));
+    assert_eq!(Spike::argmax_by_count(&output[..1], 2), Some(1));
+    assert_eq!(Spike::argmax_by_count(&output[..1], 1), None);
+    assert_eq!(Spike::first_to_spike(&output), Some(1));
+
+    let silent = nn.solve_sequential(vec![]);
+    assert_eq!(Spike::argmax_by_count(&silent, 2), None);
+    assert_eq!(Spike::first_to_spike(&silent), None);
+}
+
+#[test]
+fn test_regular_train() {
+    let spikes = Spike::regular_train(2, 7, 1000, 3);
+
+    assert_eq!(spikes.len(), 1000);
+    assert!(spikes.iter().enumerate().all(|(i, s)| s.neuron_id == 2 && s.ts == 3 + 7 * i as u128));
+    assert!(Spike::regular_train(0, 7, 0, 3).is_empty());
+    assert_eq!(Spike::regular_train(0, 0, 1, 3), vec![Spike::new(3, 0)]);
+}
+
+#[test]
+fn test_firing_rates() {
+    let spikes = Spike::create_terminal_vec(vec![
+        Spike::regular_train(0, 10, 100, 0),
+        Spike::regular_train(1, 4, 1000, 2)
+    ]);
+
+    assert_eq!(Spike::firing_rates(&spikes, 3, 1000), vec![0.1, 0.25, 0.0]);
+    assert_eq!(Spike::firing_rates(&spikes, 2, 0), vec![0.0, 0.0]);
+    assert!(Spike::firing_rates(&[], 0, 10).is_empty());
+
+    // Poisson trains match their rate, with timestamps in ms
+    let rates = Spike::firing_rates(&Spike::poisson_train(0, 20.0, 100_000, 3), 1, 100_000);
+    assert!((rates[0] - 0.02).abs() < 0.002);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_late_intra_cascade() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_with_delay_matrices(
+            vec![LifNeuron::new(&config); 3],
+            array![[1.0, 0.0, 0.0]],
+            array![[0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, 0.0]],
+            array![[1, 1, 1]],
+            array![[0, 10, 0], [0, 0, 20], [0, 0, 0]]
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // The cascade through the delayed intra-layer synapses goes on long after the last input spike
+    let spikes = Spike::spike_vec_for(0, vec![1, 2]);
+    assert_eq!(Spike::time_span(&spikes), Some((1, 2)));
+
+    assert_eq!(nn.solve(spikes), vec![vec![2, 3], vec![12, 13], vec![32, 33]]);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_late_intra_cascade() {
+    let config = LifNeuronConfig::new(2.0, 0.5, 2.1, 1.0);
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new_dynamic()
+        .layer_with_delay_matrices(
+            vec![LifNeuron::new(&config); 3],
+            array![[1.0, 0.0, 0.0]],
+            array![[0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, 0.0]],
+            array![[1, 1, 1]],
+            array![[0, 10, 0], [0, 0, 20], [0, 0, 0]]
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // The cascade through the delayed intra-layer synapses goes on long after the last input spike
+    let spikes = Spike::spike_vec_for(0, vec![1, 2]);
+    assert_eq!(Spike::time_span(&spikes), Some((1, 2)));
+
+    assert_eq!(nn.solve(spikes).await, vec![vec![2, 3], vec![12, 13], vec![32, 33]]);
+}
+
+#[test]
+fn test_from_rate_schedule() {
+    let window = 20_000;
+    let rates = ndarray::array![
+        [0.01, 0.0, 0.05],
+        [0.02, 0.01, 0.0]
+    ];
+
+    let spikes = Spike::from_rate_schedule(&rates, window, 7);
+
+    assert!(spikes.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(spikes, Spike::from_rate_schedule(&rates, window, 7));
+
+    for ((neuron_id, window_index), rate) in rates.indexed_iter() {
+        let start = window_index as u128 * window;
+        let count = spikes.iter()
+            .filter(|s| s.neuron_id == neuron_id && (start..start + window).contains(&s.ts))
+            .count() as f64;
+
+        // Allow four standard deviations, plus the spikes merged when falling on the same tick
+        let expected = rate * window as f64;
+        assert!(
+            (count - expected).abs() <= 4.0 * expected.sqrt() + expected * rate,
+            "neuron {neuron_id}, window {window_index}: expected about {expected} spikes, found {count}"
+        );
+    }
+}
+
+#[test]
+fn test_nn_get_params() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 2.8, 0.9)),
+                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.6, 2.9, 1.2)),
+            ],
+            [
+                1.2, 1.1
+            ],
+            [
+                [0.0, -0.3],
+                [-0.2, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(0.8, 0.3, 2.5, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.1, 0.4, 2.6, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.4, 3.0, 1.0))
+            ],
+            [
+                [1.2, 1.3, 1.2],
+                [1.4, 1.3, 1.5]
+            ],
+            [
+                [0.0, -0.2, -0.3],
+                [-0.3, 0.0, -0.3],
+                [-0.2, -0.1, 0.0]
+            ]
+        )
+        .build();
+    
+    assert_eq!(nn.get_input_weight(0), Some(1.2));
+    assert_eq!(nn.get_input_weight(1), Some(1.1));
+    assert_eq!(nn.get_input_weight(2), None);
+
+    assert_eq!(nn[0][(0, 0)], 0.0);
+    assert_eq!(nn[0][(0, 1)], -0.3);
+    assert_eq!(nn[0][(1, 0)], -0.2);
+    assert_eq!(nn[0][(1, 1)], 0.0);
+
+    assert_eq!(nn[((0, 0), (1, 0))], 1.2);
+    assert_eq!(nn.get_weight((0, 1), (1, 1)), Some(1.3));
+    assert_eq!(nn.get_weight((1, 0), (0, 0)), None);
+}
+
+#[test]
+fn test_nn_topology() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 2.8, 0.9)),
+                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.6, 2.9, 1.2)),
+            ],
+            [
+                1.2, 1.1
+            ],
+            [
+                [0.0, -0.3],
+                [-0.2, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(0.8, 0.3, 2.5, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.1, 0.4, 2.6, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.4, 3.0, 1.0))
+            ],
+            [
+                [1.2, 1.3, 1.2],
+                [1.4, 1.3, 1.5]
+            ],
+            [
+                [0.0, -0.2, -0.3],
+                [-0.3, 0.0, -0.3],
+                [-0.2, -0.1, 0.0]
+            ]
+        )
+        .build();
+
+    assert_eq!(nn.num_layers(), 2);
+    assert_eq!(nn.layer_size(0), Some(2));
+    assert_eq!(nn.layer_size(1), Some(3));
+    assert_eq!(nn.layer_size(2), None);
+
+    assert_eq!(nn.input_weights().to_vec(), vec![1.2, 1.1]);
+
+    let synapses = nn.synapses_between(0).unwrap();
+    assert_eq!(synapses.dim(), (2, 3));
+    for from in 0..2 {
+        for to in 0..3 {
+            assert_eq!(Some(synapses[(from, to)]), nn.get_weight((0, from), (1, to)));
+        }
+    }
+    assert!(nn.synapses_between(1).is_none());
+    assert!(nn.synapses_between(usize::MAX).is_none());
+}
+
+#[test]
+fn test_nn_update_params() {
+    let mut nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.0, 0.5, 2.8, 0.9)),
+                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.6, 2.9, 1.2)),
+            ],
+            [
+                1.2, 1.1
+            ],
+            [
+                [0.0, -0.3],
+                [-0.2, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(0.8, 0.3, 2.5, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.1, 0.4, 2.6, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.2, 0.4, 3.0, 1.0))
+            ],
+            [
+                [1.2, 1.3, 1.2],
+                [1.4, 1.3, 1.5]
+            ],
+            [
+                [0.0, -0.2, -0.3],
+                [-0.3, 0.0, -0.3],
+                [-0.2, -0.1, 0.0]
+            ]
+        )
+        .build();
+    
+    nn[0][0].v_rest = 2.0;
+    assert_eq!(nn.get_neuron(0, 0).unwrap().v_rest, 2.0);
+
+    *nn.get_input_weight_mut(1).unwrap() = 1.5;
+    assert_eq!(nn.get_input_weight(1), Some(1.5));
+
+    nn[0][(0, 0)] = 1.0;
+    assert_eq!(nn.get_layer(0).unwrap().get_intra_weight(0, 0), Some(1.0));
+
+    nn[((0, 1), (1, 1))] = 3.0;
+    assert_eq!(nn.get_weight((0, 1), (1, 1)), Some(3.0));
+
+    for neuron in nn.iter_mut().flat_map(|l| l.iter_mut_neurons()) {
+        neuron.v_reset += 1.0;
+    }
+
+    assert_eq!(nn[0][1].v_reset, 1.6);
+    assert_eq!(nn[1][2].v_reset, 1.4);
+}
+
+#[cfg(feature = "expose-test-solver")]
+#[test]
+fn test_solver_v1() {
+    use pds_spiking_nn::test_solver;
+    
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
             Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
             Spike::spike_vec_for(2, vec![4, 9, 12])
         ]
@@ -404,3 +3493,184 @@ fn test_solver_v1() {
         ]
     );
 }
+
+#[cfg(all(feature = "serde", not(feature = "async")))]
+#[test]
+fn test_serde_round_trip() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+            Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+            Spike::spike_vec_for(2, vec![4, 9, 12])
+        ]
+    );
+
+    let json = serde_json::to_string(&nn).unwrap();
+    let restored: pds_spiking_nn::NN<LeakyIntegrateFire> = serde_json::from_str(&json).unwrap();
+
+    let spikes_json = serde_json::to_string(&spikes).unwrap();
+    let restored_spikes: Vec<Spike> = serde_json::from_str(&spikes_json).unwrap();
+    assert_eq!(restored_spikes, spikes);
+
+    assert_eq!(restored.solve(restored_spikes), nn.solve(spikes));
+}
+
+#[cfg(all(feature = "serde", feature = "async"))]
+#[tokio::test]
+async fn test_serde_round_trip() {
+    let nn = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(2.0, 0.5, 3.0, 1.6)),
+                LifNeuron::new(&LifNeuronConfig::new(1.5, 0.4, 3.1, 1.2)),
+                LifNeuron::new(&LifNeuronConfig::new(1.6, 0.3, 2.8, 1.1))
+            ],
+            [1.1, 1.2, 1.1],
+            [
+                [0.0, -0.1, -0.2],
+                [-0.15, 0.0, -0.1],
+                [-0.2, -0.15, 0.0]
+            ]
+        )
+        .layer(
+            [
+                LifNeuron::new(&LifNeuronConfig::new(1.8, 0.5, 2.8, 1.5)),
+                LifNeuron::new(&LifNeuronConfig::new(1.7, 0.8, 2.6, 1.6))
+            ],
+            [
+                [0.9, 0.85],
+                [0.8, 0.9],
+                [0.85, 0.7]
+            ],
+            [
+                [0.0, -0.2],
+                [-0.15, 0.0]
+            ]
+        )
+        .build();
+
+    let spikes = Spike::create_terminal_vec(
+        vec![
+            Spike::spike_vec_for(0, vec![2, 5, 6, 10]),
+            Spike::spike_vec_for(1, vec![3, 7, 8, 10]),
+            Spike::spike_vec_for(2, vec![4, 9, 12])
+        ]
+    );
+
+    let json = serde_json::to_string(&nn).unwrap();
+    let restored: pds_spiking_nn::NN<LeakyIntegrateFire> = serde_json::from_str(&json).unwrap();
+
+    let spikes_json = serde_json::to_string(&spikes).unwrap();
+    let restored_spikes: Vec<Spike> = serde_json::from_str(&spikes_json).unwrap();
+    assert_eq!(restored_spikes, spikes);
+
+    assert_eq!(restored.solve(restored_spikes).await, nn.solve(spikes).await);
+}
+
+#[test]
+fn test_random_init() {
+    let config = LifNeuronConfig::new(0.0, 0.0, 1.0, 1.0);
+    let spikes = (0..20).map(|n| Spike::new(0, n)).collect::<Vec<_>>();
+    let build = |seed: Option<u64>| {
+        let builder = NNBuilder::<LeakyIntegrateFire, _>::new().layer_uniform::<20>(&config, [0.9; 20], [[0.0; 20]; 20]);
+
+        match seed {
+            Some(seed) => builder.with_random_init(&mut Pcg64Mcg::seed_from_u64(seed), 0.0..0.2).build(),
+            None => builder.build()
+        }
+    };
+
+    // Identical neurons behave in lock-step: none of them reaches the threshold
+    assert_eq!(build(None).solve_sequential(spikes.clone()), vec![]);
+
+    // The jittered ones fire iff their initial potential is enough to make up for the missing 0.1
+    let nn = build(Some(42));
+    let expected = (0..20)
+        .filter(|&n| nn.get_neuron(0, n).unwrap().v_init_offset > 0.1)
+        .map(|n| Spike::new(0, n))
+        .collect::<Vec<_>>();
+    assert!(!expected.is_empty() && expected.len() < 20);
+    assert_eq!(nn.solve_sequential(spikes.clone()), expected);
+
+    // The jitter is reproducible
+    assert_eq!(format!("{:?}", build(Some(42))), format!("{:?}", nn));
+    assert_ne!(format!("{:?}", build(Some(43))), format!("{:?}", nn));
+}
+
+#[test]
+fn test_stochastic_lif_seeded() {
+    let config = StochasticLifNeuronConfig { v_threshold: 1.5, tau: 4.0, temperature: 0.2, seed: 42, ..Default::default() };
+    let build = |config: &StochasticLifNeuronConfig| NNBuilder::<StochasticLif, _>::new_dynamic()
+        .layer(StochasticLifNeuron::new_vec(config, 3), [0.5, 0.5, 0.5], [0.0; 9]).unwrap()
+        .build()
+        .unwrap();
+    let spikes = Spike::create_terminal_vec((0..3).map(|n| Spike::spike_vec_for(n, (1..=20).collect())).collect());
+
+    // The same drive makes every neuron fire irregularly, each in its own way
+    let nn = build(&config);
+    let expected = [(3, 1), (4, 0), (5, 2), (6, 1), (8, 2), (9, 0), (9, 1), (13, 2), (14, 0), (14, 1), (16, 2), (18, 0), (19, 1), (20, 2)]
+        .into_iter()
+        .map(|(ts, neuron_id)| Spike::new(ts, neuron_id))
+        .collect::<Vec<_>>();
+    assert_eq!(nn.solve_sequential(spikes.clone()), expected);
+
+    // Every solve starts from the seeds again, whatever the solver
+    assert_eq!(nn.solve_sequential(spikes.clone()), expected);
+    #[cfg(not(feature = "async"))]
+    assert_solvers_agree(&nn, &spikes);
+
+    // While a different seed gives different spikes
+    assert_ne!(build(&StochasticLifNeuronConfig { seed: 43, ..config.clone() }).solve_sequential(spikes.clone()), expected);
+}
+
+#[test]
+fn test_stochastic_lif_zero_temperature() {
+    // Without noise, the neurons fire exactly like lif ones
+    let stochastic = NNBuilder::<StochasticLif, _>::new()
+        .layer(
+            [From::from(&StochasticLifNeuronConfig { v_rest: 0.2, v_threshold: 1.2, tau: 3.0, temperature: 0.0, ..Default::default() })],
+            [0.45],
+            [[0.0]]
+        )
+        .build();
+    let lif = NNBuilder::<LeakyIntegrateFire, _>::new()
+        .layer([LifNeuron::new(&LifNeuronConfig::new(0.2, 0.0, 1.2, 3.0))], [0.45], [[0.0]])
+        .build();
+
+    let spikes = Spike::spike_vec_for(0, (1..100).filter(|ts| ts % 7 != 0).collect());
+    let output = stochastic.solve_sequential(spikes.clone());
+
+    assert!(!output.is_empty());
+    assert_eq!(output, lif.solve_sequential(spikes));
+}